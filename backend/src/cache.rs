@@ -0,0 +1,125 @@
+use actix_web::web::Bytes;
+use lru::LruCache;
+use log::info;
+use sha2::{Digest, Sha256};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Serialized response held in the cache
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub body: Bytes,
+    pub content_type: Option<String>,
+    pub etag: String,
+    stored_at: Instant,
+}
+
+impl CachedResponse {
+    pub fn new(body: Bytes, content_type: Option<String>) -> Self {
+        Self {
+            etag: etag(&body),
+            body,
+            content_type,
+            stored_at: Instant::now(),
+        }
+    }
+}
+
+/// In-process LRU cache of serialized GET responses keyed by path and query.
+/// Entries expire after the configured TTL and the whole cache is cleared when
+/// the underlying data changes (crawls, card syncs, meta jobs).
+pub struct ResponseCache {
+    entries: Mutex<LruCache<String, CachedResponse>>,
+    ttl: Duration,
+}
+
+impl ResponseCache {
+    /// Create a cache holding at most `capacity` responses for `ttl_secs` each
+    pub fn new(capacity: usize, ttl_secs: u64) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+            ttl: Duration::from_secs(ttl_secs),
+        }
+    }
+
+    /// Max age advertised to clients, in seconds
+    pub fn ttl_secs(&self) -> u64 {
+        self.ttl.as_secs()
+    }
+
+    /// Get a fresh entry, evicting it if it has expired
+    pub fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut entries = self.entries.lock().unwrap();
+
+        match entries.get(key) {
+            Some(entry) if entry.stored_at.elapsed() < self.ttl => Some(entry.clone()),
+            Some(_) => {
+                entries.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: String, response: CachedResponse) {
+        self.entries.lock().unwrap().put(key, response);
+    }
+
+    /// Drop every cached response
+    pub fn invalidate_all(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        let count = entries.len();
+        entries.clear();
+        info!("Invalidated {} cached responses", count);
+    }
+}
+
+/// Strong ETag for a response body
+pub fn etag(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    format!("\"{}\"", hex::encode(&digest[..16]))
+}
+
+/// Whether an `If-None-Match` header value matches `etag` (weak comparison)
+pub fn if_none_match(header: &str, etag: &str) -> bool {
+    header.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.trim_start_matches("W/") == etag
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_if_none_match() {
+        let tag = etag(b"{}");
+        assert!(if_none_match(&tag, &tag));
+        assert!(if_none_match(&format!("\"other\", W/{}", tag), &tag));
+        assert!(if_none_match("*", &tag));
+        assert!(!if_none_match("\"other\"", &tag));
+    }
+
+    #[test]
+    fn test_expired_entries_are_evicted() {
+        let cache = ResponseCache::new(2, 0);
+        cache.insert("a".to_string(), CachedResponse::new(Bytes::from_static(b"a"), None));
+        assert!(cache.get("a").is_none());
+    }
+
+    #[test]
+    fn test_least_recently_used_entry_is_evicted() {
+        let cache = ResponseCache::new(2, 60);
+        cache.insert("a".to_string(), CachedResponse::new(Bytes::from_static(b"a"), None));
+        cache.insert("b".to_string(), CachedResponse::new(Bytes::from_static(b"b"), None));
+        cache.get("a");
+        cache.insert("c".to_string(), CachedResponse::new(Bytes::from_static(b"c"), None));
+
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+    }
+}
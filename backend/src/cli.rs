@@ -0,0 +1,533 @@
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand};
+use crate::clients::GatcgApiClient;
+use crate::config::Config;
+use crate::controllers::filters::parse_date;
+use crate::migrations::{Migration, MigrationError, Migrator};
+use crate::models::{DateRange, EventFormat};
+use crate::services::event_crawler::IngestOptions;
+use crate::services::export::{Dataset, ExportError, ExportFormat, ExportScope};
+use crate::services::meta_analysis::MetaGrouping;
+use crate::services::{CardSyncService, ChampionAliasService, EventCrawler, ExportService, MetaAnalysisService, RatingService};
+use mongodb::Database;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// The command completed successfully
+pub const EXIT_OK: i32 = 0;
+
+/// The command ran but failed
+pub const EXIT_FAILURE: i32 = 1;
+
+/// Configuration is missing or MongoDB is unreachable. Invalid arguments
+/// exit with 2 before anything runs.
+pub const EXIT_UNAVAILABLE: i32 = 3;
+
+/// Grand Archive TCG meta analysis backend
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Run the HTTP API server and scheduled jobs (default)
+    Serve,
+    #[command(flatten)]
+    Operation(Operation),
+}
+
+impl Command {
+    /// Log level used when `RUST_LOG` is unset
+    pub fn default_log_level(&self) -> &'static str {
+        match self {
+            Command::Serve => "error",
+            Command::Operation(_) => "info",
+        }
+    }
+}
+
+/// One-off operational commands, run against the configured database
+#[derive(Subcommand, Debug)]
+pub enum Operation {
+    /// Crawl events from the Omnidex API
+    #[command(subcommand)]
+    Crawl(CrawlCommand),
+    /// Sync card data from the GATCG API
+    #[command(subcommand)]
+    Sync(SyncCommand),
+    /// Map upstream champion strings to canonical champion slugs
+    #[command(subcommand)]
+    Champions(ChampionsCommand),
+    /// Recompute player ratings and meta statistics
+    #[command(subcommand)]
+    Meta(MetaCommand),
+    /// Apply or revert schema migrations, including indexes
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+    /// Manage database indexes
+    #[command(subcommand)]
+    Indexes(IndexCommand),
+    /// Export tournament data to files
+    Export(ExportArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CrawlCommand {
+    /// Crawl a range of event IDs, stopping early after CRAWLER_MAX_404S consecutive misses
+    Historical {
+        /// First event ID
+        #[arg(long)]
+        from: i32,
+        /// Last event ID, inclusive (default: until the misses run out)
+        #[arg(long)]
+        to: Option<i32>,
+    },
+    /// Crawl new events after the last crawled ID
+    Incremental,
+    /// Ingest a single event, or re-ingest it after the organizer corrected the results
+    Event {
+        /// Omnidex event ID
+        event_id: i32,
+        /// Shorthand for --force-standings --force-decklists
+        #[arg(long)]
+        force: bool,
+        /// Refetch standings even for uninteresting events and delete standings no longer in the results
+        #[arg(long)]
+        force_standings: bool,
+        /// Refetch decklists even if the event reports none and delete decklists no longer published
+        #[arg(long)]
+        force_decklists: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SyncCommand {
+    /// Sync new and changed cards from the full GATCG catalogue
+    Catalogue,
+    /// Sync every card that appears in a decklist
+    Cards,
+    /// Sync champion cards for every champion in the standings or a material deck, and rebuild lineages
+    Champions,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ChampionsCommand {
+    /// Rewrite stored standings and decklists to canonical slugs and list unmatched strings
+    Normalize,
+    /// Map an upstream champion string to a champion slug
+    Alias {
+        /// Upstream string, e.g. "Lorraine, Crux Knight"
+        alias: String,
+        /// Canonical champion slug
+        slug: String,
+    },
+    /// Remove a champion alias
+    Unalias {
+        alias: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum MetaCommand {
+    /// Recompute player ratings and the meta breakdown and champion performance
+    Recompute {
+        /// Window for meta statistics, in days
+        #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(i64).range(1..=3650))]
+        days: i64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum MigrateCommand {
+    /// List migrations and whether they are applied
+    Status,
+    /// Apply pending migrations
+    Up {
+        /// Stop after this version (default: the latest)
+        #[arg(long)]
+        to: Option<i32>,
+        /// Print the migrations that would run without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Revert applied migrations, newest first (default: only the newest)
+    Down {
+        /// Revert every migration after this version; 0 reverts all of them
+        #[arg(long, conflicts_with = "steps")]
+        to: Option<i32>,
+        /// Number of migrations to revert
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+        /// Print the migrations that would be reverted without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum IndexCommand {
+    /// Create any missing indexes without applying other migrations
+    Ensure,
+}
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Output directory
+    #[arg(long, default_value = "exports")]
+    pub out: PathBuf,
+
+    /// File format: ndjson, csv or parquet
+    #[arg(long, default_value = "parquet", value_parser = parse_export_format)]
+    pub format: ExportFormat,
+
+    /// Dataset to export; repeat for several (default: every dataset the format supports)
+    #[arg(long = "dataset", value_parser = parse_dataset)]
+    pub datasets: Vec<Dataset>,
+
+    /// Only events of this format: standard, limited, sealed or draft
+    #[arg(long, value_parser = parse_event_format)]
+    pub event_format: Option<EventFormat>,
+
+    /// Only events from the last N days
+    #[arg(long, conflicts_with_all = ["from", "to"], value_parser = clap::value_parser!(i64).range(1..=3650))]
+    pub days: Option<i64>,
+
+    /// Earliest event start date, YYYY-MM-DD or RFC 3339
+    #[arg(long, value_parser = |s: &str| parse_date(s, false))]
+    pub from: Option<DateTime<Utc>>,
+
+    /// Latest event start date, inclusive
+    #[arg(long, value_parser = |s: &str| parse_date(s, true))]
+    pub to: Option<DateTime<Utc>>,
+
+    /// Minimum event player count
+    #[arg(long)]
+    pub min_players: Option<i32>,
+
+    /// Only standings and decklists of this champion
+    #[arg(long)]
+    pub champion: Option<String>,
+}
+
+fn parse_export_format(value: &str) -> Result<ExportFormat, String> {
+    ExportFormat::from_extension(value).ok_or_else(|| "expected ndjson, csv or parquet".to_string())
+}
+
+fn parse_dataset(value: &str) -> Result<Dataset, String> {
+    Dataset::parse(value).ok_or_else(|| "expected events, standings, decklists or cards".to_string())
+}
+
+fn parse_event_format(value: &str) -> Result<EventFormat, String> {
+    EventFormat::parse(value).ok_or_else(|| "expected standard, limited, sealed or draft".to_string())
+}
+
+impl ExportArgs {
+    fn scope(&self) -> ExportScope {
+        let range = match self.days {
+            Some(days) => DateRange::last_days(days),
+            None => DateRange {
+                from: self.from,
+                to: self.to,
+            },
+        };
+
+        ExportScope {
+            format: self.event_format,
+            range,
+            min_players: self.min_players,
+            champion: self.champion.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn datasets(&self) -> Result<Vec<Dataset>, ExportError> {
+        if self.datasets.is_empty() {
+            return Ok(Dataset::ALL.into_iter().filter(|d| d.supports(self.format)).collect());
+        }
+
+        for dataset in &self.datasets {
+            if !dataset.supports(self.format) {
+                return Err(ExportError::Unsupported(dataset.name(), self.format.extension()));
+            }
+        }
+        Ok(self.datasets.clone())
+    }
+}
+
+/// Run an operational command, printing a summary on success, and return the process exit code
+pub async fn run(operation: Operation, config: Config, database: Database) -> i32 {
+    let result = match operation {
+        Operation::Crawl(command) => crawl(command, &config, database).await,
+        Operation::Sync(command) => sync(command, &config, database).await,
+        Operation::Champions(command) => champions(command, database).await,
+        Operation::Meta(command) => meta(command, database).await,
+        Operation::Migrate(command) => migrate(command, database).await.map_err(Into::into),
+        Operation::Indexes(command) => indexes(command, database).await.map_err(Into::into),
+        Operation::Export(args) => export(args, database).await.map_err(Into::into),
+    };
+
+    match result {
+        Ok(()) => EXIT_OK,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            EXIT_FAILURE
+        }
+    }
+}
+
+async fn crawl(command: CrawlCommand, config: &Config, database: Database) -> Result<(), Box<dyn Error>> {
+    let crawler = EventCrawler::from_config(config, database);
+
+    let last_id = match command {
+        CrawlCommand::Historical { from, to } => {
+            if let Some(to) = to.filter(|&to| to < from) {
+                return Err(format!("--to {} is before --from {}", to, from).into());
+            }
+            crawler.crawl_historical_events(from, to).await?
+        }
+        CrawlCommand::Incremental => crawler.crawl_incremental().await?,
+        CrawlCommand::Event { event_id, force, force_standings, force_decklists } => {
+            let options = IngestOptions {
+                force_standings: force || force_standings,
+                force_decklists: force || force_decklists,
+            };
+            let report = crawler
+                .ingest_event(event_id, options)
+                .await?
+                .ok_or_else(|| format!("Event {} not found", event_id))?;
+
+            println!(
+                "Ingested event {} ({}): {} standings saved, {} removed; {} decklists saved, {} removed",
+                report.event_id,
+                report.name,
+                report.standings_saved,
+                report.standings_removed,
+                report.decklists_saved,
+                report.decklists_removed,
+            );
+            return Ok(());
+        }
+    };
+
+    println!("Crawl finished at event ID {}", last_id);
+    Ok(())
+}
+
+async fn sync(command: SyncCommand, config: &Config, database: Database) -> Result<(), Box<dyn Error>> {
+    let gatcg = GatcgApiClient::new(config.request_delay_ms, config.request_timeout_secs, config.max_retries);
+    let card_sync = CardSyncService::new(Arc::new(gatcg), database);
+
+    match command {
+        SyncCommand::Catalogue => {
+            let report = card_sync.sync_catalogue().await?;
+            println!(
+                "Synced {} pages ({} unchanged): {} cards created, {} updated, {} unchanged",
+                report.pages, report.pages_unchanged, report.cards_created, report.cards_updated, report.cards_unchanged
+            );
+        }
+        SyncCommand::Cards => {
+            let synced = card_sync.sync_cards_from_decklists().await?;
+            println!("Synced {} cards", synced);
+        }
+        SyncCommand::Champions => {
+            let slugs = card_sync.get_champion_slugs().await?;
+            let synced = card_sync.sync_champions(&slugs).await?;
+            println!("Synced {} of {} champions", synced, slugs.len());
+        }
+    }
+
+    Ok(())
+}
+
+async fn champions(command: ChampionsCommand, database: Database) -> Result<(), Box<dyn Error>> {
+    let aliases = ChampionAliasService::new(database);
+
+    match command {
+        ChampionsCommand::Normalize => {
+            let report = aliases.normalize_stored().await?;
+            println!(
+                "Normalized {} standings and {} decklists",
+                report.standings_updated, report.decklists_updated
+            );
+            for unresolved in &report.unresolved {
+                println!("  unresolved: {} ({} rows)", unresolved.value, unresolved.occurrences);
+            }
+        }
+        ChampionsCommand::Alias { alias, slug } => match aliases.set_alias(&alias, &slug).await? {
+            Some(alias) => println!("Mapped '{}' to {}; run `champions normalize` to apply", alias.alias, alias.slug),
+            None => return Err(format!("Champion {} not found", slug).into()),
+        },
+        ChampionsCommand::Unalias { alias } => {
+            if !aliases.remove_alias(&alias).await? {
+                return Err(format!("Alias {} not found", alias).into());
+            }
+            println!("Removed alias {}", alias);
+        }
+    }
+
+    Ok(())
+}
+
+async fn meta(command: MetaCommand, database: Database) -> Result<(), Box<dyn Error>> {
+    let MetaCommand::Recompute { days } = command;
+
+    let events = RatingService::new(database.clone()).recompute_all().await?;
+    println!("Rated {} events", events);
+
+    let meta_service = MetaAnalysisService::new(database);
+    let range = DateRange::last_days(days);
+
+    let breakdown = meta_service.calculate_meta_breakdown(None, range, MetaGrouping::Champion).await?;
+    println!("Meta breakdown: {} champions in the last {} days", breakdown.len(), days);
+
+    let performance = meta_service.calculate_champion_performance(None, range).await?;
+    println!("Champion performance: {} champions", performance.len());
+
+    Ok(())
+}
+
+async fn migrate(command: MigrateCommand, database: Database) -> Result<(), MigrationError> {
+    let migrator = Migrator::new(database);
+
+    let print_plan = |verb: &str, plan: &[&Migration]| {
+        for migration in plan {
+            println!("{} {:04} {}", verb, migration.version, migration.name);
+        }
+    };
+
+    match command {
+        MigrateCommand::Status => {
+            for status in migrator.status().await? {
+                let state = match status.applied_at {
+                    Some(at) if status.unknown => format!("applied {} (unknown to this build)", at.to_rfc3339()),
+                    Some(at) => format!("applied {}", at.to_rfc3339()),
+                    None => "pending".to_string(),
+                };
+                println!("{:04} {:<32} {}", status.version, status.name, state);
+            }
+        }
+        MigrateCommand::Up { to, dry_run } => {
+            let plan = migrator.up(to, dry_run).await?;
+            print_plan(if dry_run { "Would apply" } else { "Applied" }, &plan);
+            if plan.is_empty() {
+                println!("Schema is up to date");
+            }
+        }
+        MigrateCommand::Down { to, steps, dry_run } => {
+            let target = match to {
+                Some(to) => to,
+                None => migrator.steps_target(steps).await?,
+            };
+            let plan = migrator.down(target, dry_run).await?;
+            print_plan(if dry_run { "Would revert" } else { "Reverted" }, &plan);
+            if plan.is_empty() {
+                println!("Nothing to revert");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn indexes(command: IndexCommand, database: Database) -> Result<(), MigrationError> {
+    let IndexCommand::Ensure = command;
+
+    let count = Migrator::new(database).ensure_indexes().await?;
+    println!("Ensured {} indexes", count);
+    Ok(())
+}
+
+/// Write each requested dataset to `<out>/<dataset>.<format>`
+async fn export(args: ExportArgs, database: Database) -> Result<(), ExportError> {
+    let datasets = args.datasets()?;
+    let scope = args.scope();
+    let service = ExportService::new(database);
+
+    fs::create_dir_all(&args.out)?;
+
+    for dataset in datasets {
+        let path = args.out.join(format!("{}.{}", dataset.name(), args.format.extension()));
+        let mut writer = BufWriter::new(File::create(&path)?);
+
+        let rows = service.write(dataset, &scope, args.format, &mut writer).await?;
+        writer.flush()?;
+
+        println!("Wrote {} rows to {}", rows, path.display());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_args() {
+        let cli = Cli::try_parse_from([
+            "grand-archive-meta", "export", "--dataset", "decklists", "--event-format", "standard", "--days", "30",
+        ])
+        .unwrap();
+        let Some(Command::Operation(Operation::Export(args))) = cli.command else {
+            panic!("expected export command");
+        };
+        assert_eq!(args.format, ExportFormat::Parquet);
+        assert_eq!(args.datasets().unwrap(), [Dataset::Decklists]);
+        assert_eq!(args.scope().format, Some(EventFormat::Standard));
+
+        assert!(Cli::try_parse_from(["grand-archive-meta", "export", "--days", "7", "--from", "2024-01-01"]).is_err());
+        assert!(Cli::try_parse_from(["grand-archive-meta", "export", "--dataset", "players"]).is_err());
+    }
+
+    #[test]
+    fn test_operation_commands() {
+        let cli = Cli::try_parse_from(["grand-archive-meta", "crawl", "historical", "--from", "100", "--to", "200"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Operation(Operation::Crawl(CrawlCommand::Historical { from: 100, to: Some(200) })))
+        ));
+
+        let cli = Cli::try_parse_from(["grand-archive-meta", "crawl", "event", "4321", "--force-standings"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Operation(Operation::Crawl(CrawlCommand::Event {
+                event_id: 4321,
+                force: false,
+                force_standings: true,
+                force_decklists: false,
+            })))
+        ));
+
+        let cli = Cli::try_parse_from(["grand-archive-meta", "migrate", "down", "--to", "0", "--dry-run"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Operation(Operation::Migrate(MigrateCommand::Down { to: Some(0), dry_run: true, .. })))
+        ));
+        assert!(Cli::try_parse_from(["grand-archive-meta", "migrate", "down", "--to", "1", "--steps", "2"]).is_err());
+
+        let cli = Cli::try_parse_from(["grand-archive-meta", "indexes", "ensure"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Operation(Operation::Indexes(IndexCommand::Ensure)))));
+
+        let cli = Cli::try_parse_from(["grand-archive-meta", "meta", "recompute"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Operation(Operation::Meta(MetaCommand::Recompute { days: 30 })))
+        ));
+
+        let cli = Cli::try_parse_from(["grand-archive-meta", "champions", "alias", "Lorraine, Crux Knight", "lorraine"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Operation(Operation::Champions(ChampionsCommand::Alias { ref alias, ref slug })))
+                if alias == "Lorraine, Crux Knight" && slug == "lorraine"
+        ));
+
+        assert!(Cli::try_parse_from(["grand-archive-meta", "sync", "decks"]).is_err());
+        assert!(Cli::try_parse_from(["grand-archive-meta"]).unwrap().command.is_none());
+    }
+}
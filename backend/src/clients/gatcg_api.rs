@@ -1,6 +1,7 @@
 use super::ApiClientError;
-use crate::models::Card;
-use reqwest::Client;
+use crate::models::{parse_release_date, Card, CardSet, Printing};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tokio::time::sleep;
@@ -8,6 +9,9 @@ use log::{debug, warn};
 
 const API_BASE_URL: &str = "https://api.gatcg.com";
 
+/// Cards requested per catalogue page
+const CATALOGUE_PAGE_SIZE: u32 = 50;
+
 /// Response structure from the GATCG cards API
 #[derive(Debug, Deserialize, Serialize)]
 struct CardApiResponse {
@@ -15,6 +19,47 @@ struct CardApiResponse {
     data: Option<CardData>,
 }
 
+/// One page of the GATCG card search
+#[derive(Debug, Deserialize)]
+struct CardSearchResponse {
+    #[serde(default)]
+    data: Vec<CardData>,
+    #[serde(default)]
+    has_more: Option<bool>,
+    #[serde(default)]
+    total_pages: Option<u32>,
+}
+
+/// Set an edition was printed in
+#[derive(Debug, Deserialize, Serialize)]
+struct EditionSetData {
+    name: Option<String>,
+    prefix: Option<String>,
+    language: Option<String>,
+    release_date: Option<String>,
+}
+
+/// A print run of an edition, e.g. its foil version
+#[derive(Debug, Deserialize, Serialize)]
+struct CirculationData {
+    kind: Option<String>,
+    foil: Option<bool>,
+}
+
+/// A printing of a card in one set
+#[derive(Debug, Deserialize, Serialize)]
+struct EditionData {
+    slug: Option<String>,
+    uuid: Option<String>,
+    #[serde(default)]
+    circulations: Vec<CirculationData>,
+    collector_number: Option<String>,
+    rarity: Option<String>,
+    illustrator: Option<String>,
+    image: Option<String>,
+    set: Option<EditionSetData>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 struct CardData {
     slug: String,
@@ -27,6 +72,7 @@ struct CardData {
     reserve_cost: Option<i32>,
     power: Option<i32>,
     life_modifier: Option<i32>,
+    level: Option<i32>,
     effect_text: Option<String>,
     flavor_text: Option<String>,
     image_url: Option<String>,
@@ -35,6 +81,40 @@ struct CardData {
     rarity: Option<String>,
     artist: Option<String>,
     subtypes: Option<Vec<String>>,
+    #[serde(default)]
+    editions: Vec<EditionData>,
+}
+
+/// A card with the printings and sets listed for it
+#[derive(Debug, Clone)]
+pub struct FetchedCard {
+    pub card: Card,
+    pub printings: Vec<Printing>,
+    pub sets: Vec<CardSet>,
+}
+
+/// Cache validators of a previously fetched catalogue page
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PageValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// A fetched catalogue page
+#[derive(Debug)]
+pub struct CataloguePage {
+    pub cards: Vec<FetchedCard>,
+    pub validators: PageValidators,
+    /// Whether later pages exist
+    pub has_more: bool,
+}
+
+/// Result of a conditional catalogue page request
+#[derive(Debug)]
+pub enum CatalogueFetch {
+    /// The page is unchanged since the validators were issued
+    NotModified,
+    Page(CataloguePage),
 }
 
 /// Client for interacting with api.gatcg.com/cards
@@ -60,7 +140,7 @@ impl GatcgApiClient {
     }
 
     /// Fetch a card by its slug with retry logic
-    pub async fn fetch_card(&self, slug: &str) -> Result<Option<Card>, ApiClientError> {
+    pub async fn fetch_card(&self, slug: &str) -> Result<Option<FetchedCard>, ApiClientError> {
         let url = format!("{}/cards/{}", API_BASE_URL, slug);
         let mut attempt = 0;
 
@@ -117,9 +197,92 @@ impl GatcgApiClient {
         }
     }
 
+    /// Fetch one page (1-based) of the full card catalogue. With validators
+    /// from an earlier fetch the request is conditional, and an unchanged
+    /// page is reported without a body.
+    pub async fn fetch_catalogue_page(
+        &self,
+        page: u32,
+        validators: &PageValidators,
+    ) -> Result<CatalogueFetch, ApiClientError> {
+        let url = format!("{}/cards/search", API_BASE_URL);
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            debug!("Fetching catalogue page {} (attempt {}/{})", page, attempt, self.max_retries + 1);
+
+            let mut request = self
+                .client
+                .get(&url)
+                .query(&[("page", page), ("page_size", CATALOGUE_PAGE_SIZE)]);
+            if let Some(etag) = &validators.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+
+            match request.send().await {
+                Ok(response) => {
+                    let status = response.status();
+
+                    if status == StatusCode::NOT_MODIFIED {
+                        sleep(self.delay).await;
+                        return Ok(CatalogueFetch::NotModified);
+                    } else if status.is_success() {
+                        let header = |name| {
+                            response
+                                .headers()
+                                .get(name)
+                                .and_then(|v| v.to_str().ok())
+                                .map(str::to_string)
+                        };
+                        let validators = PageValidators {
+                            etag: header(ETAG),
+                            last_modified: header(LAST_MODIFIED),
+                        };
+
+                        let body = response
+                            .json::<CardSearchResponse>()
+                            .await
+                            .map_err(|e| ApiClientError::DeserializationError(e.to_string()))?;
+                        sleep(self.delay).await;
+
+                        let has_more = match (body.has_more, body.total_pages) {
+                            (Some(has_more), _) => has_more,
+                            (None, Some(total_pages)) => page < total_pages,
+                            (None, None) => !body.data.is_empty(),
+                        };
+
+                        return Ok(CatalogueFetch::Page(CataloguePage {
+                            cards: body.data.into_iter().map(|data| self.convert_to_card(data)).collect(),
+                            validators,
+                            has_more,
+                        }));
+                    } else if status.is_server_error() && attempt <= self.max_retries {
+                        warn!("Server error for catalogue page {}, retrying...", page);
+                        sleep(self.delay * 2).await;
+                        continue;
+                    } else {
+                        return Err(ApiClientError::RequestFailed(format!("Status: {}", status)));
+                    }
+                }
+                Err(e) if attempt <= self.max_retries => {
+                    warn!("Error fetching catalogue page {}: {}, retrying...", page, e);
+                    sleep(self.delay * 2).await;
+                    continue;
+                }
+                Err(e) => {
+                    return Err(ApiClientError::from(e));
+                }
+            }
+        }
+    }
+
     /// Fetch multiple cards by slugs
     #[allow(dead_code)]
-    pub async fn fetch_cards(&self, slugs: &[String]) -> Result<Vec<Card>, ApiClientError> {
+    pub async fn fetch_cards(&self, slugs: &[String]) -> Result<Vec<FetchedCard>, ApiClientError> {
         let mut cards = Vec::new();
 
         for slug in slugs {
@@ -134,9 +297,34 @@ impl GatcgApiClient {
         Ok(cards)
     }
 
-    /// Convert API response data to our Card model
-    fn convert_to_card(&self, data: CardData) -> Card {
-        Card {
+    /// Convert API response data to our Card model and its printings.
+    /// Set, number, rarity, artist and image fall back to the first edition.
+    fn convert_to_card(&self, data: CardData) -> FetchedCard {
+        let printings = data
+            .editions
+            .iter()
+            .map(|edition| convert_to_printing(&data.slug, edition))
+            .collect();
+
+        let mut sets: Vec<CardSet> = Vec::new();
+        for set in data.editions.iter().filter_map(|e| e.set.as_ref()) {
+            let (Some(prefix), Some(name)) = (&set.prefix, &set.name) else { continue };
+            if sets.iter().any(|s| &s.prefix == prefix) {
+                continue;
+            }
+            sets.push(CardSet {
+                id: None,
+                prefix: prefix.clone(),
+                name: name.clone(),
+                language: set.language.clone(),
+                release_date: set.release_date.as_deref().and_then(parse_release_date),
+            });
+        }
+
+        let edition = data.editions.into_iter().next();
+        let edition_set = edition.as_ref().and_then(|e| e.set.as_ref());
+
+        let card = Card {
             id: None,
             slug: data.slug,
             name: data.name,
@@ -147,18 +335,60 @@ impl GatcgApiClient {
             reserve_cost: data.reserve_cost,
             power: data.power,
             life_modifier: data.life_modifier,
+            level: data.level,
             card_text: data.effect_text,
             flavor_text: data.flavor_text,
-            image_url: data.image_url,
-            set_name: data.set,
-            card_number: data.collector_number,
-            rarity: data.rarity,
-            artist: data.artist,
+            image_url: data.image_url.or_else(|| edition.as_ref().and_then(|e| e.image.clone())),
+            set_name: data.set.or_else(|| edition_set.and_then(|s| s.name.clone())),
+            card_number: data
+                .collector_number
+                .or_else(|| edition.as_ref().and_then(|e| e.collector_number.clone())),
+            rarity: data.rarity.or_else(|| edition.as_ref().and_then(|e| e.rarity.clone())),
+            artist: data.artist.or_else(|| edition.as_ref().and_then(|e| e.illustrator.clone())),
             subtypes: data.subtypes,
             banned_standard: false,
             banned_limited: false,
+            content_hash: None,
             updated_at: chrono::Utc::now(),
-        }
+        };
+
+        FetchedCard { card, printings, sets }
+    }
+}
+
+fn convert_to_printing(card_slug: &str, edition: &EditionData) -> Printing {
+    let set = edition.set.as_ref();
+
+    let mut finishes: Vec<String> = edition
+        .circulations
+        .iter()
+        .filter_map(|c| match (&c.kind, c.foil) {
+            (Some(kind), _) => Some(kind.to_lowercase()),
+            (None, Some(true)) => Some("foil".to_string()),
+            (None, Some(false)) => Some("nonfoil".to_string()),
+            (None, None) => None,
+        })
+        .collect();
+    finishes.sort();
+    finishes.dedup();
+
+    let slug = edition.slug.clone().or_else(|| edition.uuid.clone()).unwrap_or_else(|| {
+        let prefix = set.and_then(|s| s.prefix.as_deref()).unwrap_or("unknown");
+        let number = edition.collector_number.as_deref().unwrap_or("0");
+        format!("{}-{}-{}", card_slug, prefix.to_lowercase(), number)
+    });
+
+    Printing {
+        id: None,
+        slug,
+        card_slug: card_slug.to_string(),
+        set_prefix: set.and_then(|s| s.prefix.clone()),
+        set_name: set.and_then(|s| s.name.clone()),
+        collector_number: edition.collector_number.clone(),
+        rarity: edition.rarity.clone(),
+        illustrator: edition.illustrator.clone(),
+        image_url: edition.image.clone(),
+        finishes,
     }
 }
 
@@ -166,6 +396,62 @@ impl GatcgApiClient {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_convert_falls_back_to_first_edition() {
+        let data: CardData = serde_json::from_value(serde_json::json!({
+            "slug": "zephyr",
+            "name": "Zephyr",
+            "rarity": "Common",
+            "editions": [
+                {
+                    "collector_number": "042",
+                    "rarity": "Rare",
+                    "illustrator": "Someone",
+                    "image": "/cards/images/zephyr.jpg",
+                    "set": { "name": "Dawn of Ashes", "prefix": "DOA" }
+                }
+            ]
+        }))
+        .unwrap();
+
+        let card = GatcgApiClient::new(0, 1, 0).convert_to_card(data).card;
+        assert_eq!(card.set_name.as_deref(), Some("Dawn of Ashes"));
+        assert_eq!(card.card_number.as_deref(), Some("042"));
+        assert_eq!(card.rarity.as_deref(), Some("Common"));
+        assert_eq!(card.artist.as_deref(), Some("Someone"));
+    }
+
+    #[test]
+    fn test_convert_printings_and_sets() {
+        let data: CardData = serde_json::from_value(serde_json::json!({
+            "slug": "zephyr",
+            "name": "Zephyr",
+            "editions": [
+                {
+                    "slug": "zephyr-doa",
+                    "collector_number": "042",
+                    "circulations": [{ "kind": "FOIL" }, { "kind": "NONFOIL" }],
+                    "set": { "name": "Dawn of Ashes", "prefix": "DOA", "release_date": "2024-03-01" }
+                },
+                {
+                    "collector_number": "007",
+                    "circulations": [{ "foil": true }],
+                    "set": { "name": "Dawn of Ashes", "prefix": "DOA" }
+                }
+            ]
+        }))
+        .unwrap();
+
+        let fetched = GatcgApiClient::new(0, 1, 0).convert_to_card(data);
+        let slugs: Vec<&str> = fetched.printings.iter().map(|p| p.slug.as_str()).collect();
+        assert_eq!(slugs, ["zephyr-doa", "zephyr-doa-007"]);
+        assert_eq!(fetched.printings[0].finishes, ["foil", "nonfoil"]);
+        assert_eq!(fetched.printings[1].finishes, ["foil"]);
+
+        assert_eq!(fetched.sets.len(), 1);
+        assert!(fetched.sets[0].release_date.is_some());
+    }
+
     #[tokio::test]
     #[ignore] // Requires network access
     async fn test_fetch_card() {
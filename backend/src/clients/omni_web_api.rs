@@ -2,6 +2,7 @@ use super::ApiClientError;
 use crate::models::{Decklist, DecklistCard};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::Duration;
 use tokio::time::sleep;
 use log::{debug, warn};
@@ -23,6 +24,8 @@ struct DecklistData {
     rank: i32,
     main_deck: Vec<DeckCardData>,
     sideboard: Option<Vec<DeckCardData>>,
+    #[serde(default, alias = "material")]
+    material_deck: Option<Vec<DeckCardData>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -34,6 +37,21 @@ struct DeckCardData {
     card_type: Option<String>,
     element: Option<String>,
     cost: Option<i32>,
+    level: Option<i32>,
+}
+
+impl From<DeckCardData> for DecklistCard {
+    fn from(card: DeckCardData) -> Self {
+        DecklistCard {
+            slug: card.slug,
+            name: card.name,
+            quantity: card.quantity,
+            card_type: card.card_type,
+            element: card.element,
+            cost: card.cost,
+            level: card.level,
+        }
+    }
 }
 
 /// Client for interacting with omni.gatcg.com/api
@@ -163,32 +181,12 @@ impl OmniWebApiClient {
 
     /// Convert API decklist data to our Decklist model
     fn convert_to_decklist(&self, event_id: i32, data: DecklistData) -> Decklist {
-        let main_deck: Vec<DecklistCard> = data
-            .main_deck
-            .into_iter()
-            .map(|card| DecklistCard {
-                slug: card.slug,
-                name: card.name,
-                quantity: card.quantity,
-                card_type: card.card_type,
-                element: card.element,
-                cost: card.cost,
-            })
-            .collect();
-
-        let sideboard: Vec<DecklistCard> = data
-            .sideboard
-            .unwrap_or_default()
-            .into_iter()
-            .map(|card| DecklistCard {
-                slug: card.slug,
-                name: card.name,
-                quantity: card.quantity,
-                card_type: card.card_type,
-                element: card.element,
-                cost: card.cost,
-            })
-            .collect();
+        let section = |cards: Option<Vec<DeckCardData>>| -> Vec<DecklistCard> {
+            cards.unwrap_or_default().into_iter().map(DecklistCard::from).collect()
+        };
+        let main_deck = section(Some(data.main_deck));
+        let sideboard = section(data.sideboard);
+        let material_deck = section(data.material_deck);
 
         let main_deck_count = main_deck.iter().map(|c| c.quantity).sum();
         let sideboard_count = sideboard.iter().map(|c| c.quantity).sum();
@@ -202,12 +200,18 @@ impl OmniWebApiClient {
             rank: data.rank,
             main_deck,
             sideboard,
+            material_deck,
+            champion_lineage: Vec::new(),
             main_deck_count,
             sideboard_count,
             card_frequencies: None,
+            deck_stats: None,
+            schema_version: 0,
             updated_at: chrono::Utc::now(),
         };
 
+        // Some events list material cards in the sideboard
+        decklist.separate_material_deck(&HashMap::new());
         decklist.calculate_frequencies();
         decklist
     }
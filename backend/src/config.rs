@@ -13,8 +13,20 @@ pub struct Config {
     pub crawler_max_404s: i32,
     #[allow(dead_code)]
     pub crawler_start_id: i32,
-    #[allow(dead_code)]
     pub cache_ttl_secs: u64,
+    pub cache_max_entries: usize,
+    pub rate_limit_enabled: bool,
+    pub rate_limit_window_secs: u64,
+    pub rate_limit_requests: u32,
+    pub rate_limit_api_key_requests: u32,
+    pub rate_limit_expensive_requests: u32,
+    pub trust_proxy_headers: bool,
+    pub jwt_secret: Option<String>,
+    pub jwt_ttl_secs: u64,
+    pub admin_api_key: Option<String>,
+    pub export_dir: Option<String>,
+    pub export_keep_dumps: usize,
+    pub migrate_on_startup: bool,
 }
 
 impl Config {
@@ -56,6 +68,46 @@ impl Config {
                 .unwrap_or_else(|_| "3600".to_string())
                 .parse()
                 .unwrap_or(3600),
+            cache_max_entries: env::var("CACHE_MAX_ENTRIES")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .unwrap_or(1000),
+            rate_limit_enabled: env::var("RATE_LIMIT_ENABLED")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(true),
+            rate_limit_window_secs: env::var("RATE_LIMIT_WINDOW_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            rate_limit_requests: env::var("RATE_LIMIT_REQUESTS")
+                .unwrap_or_else(|_| "120".to_string())
+                .parse()
+                .unwrap_or(120),
+            rate_limit_api_key_requests: env::var("RATE_LIMIT_API_KEY_REQUESTS")
+                .unwrap_or_else(|_| "600".to_string())
+                .parse()
+                .unwrap_or(600),
+            rate_limit_expensive_requests: env::var("RATE_LIMIT_EXPENSIVE_REQUESTS")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .unwrap_or(20),
+            trust_proxy_headers: env::var("TRUST_PROXY_HEADERS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            jwt_secret: env::var("JWT_SECRET").ok().filter(|s| !s.is_empty()),
+            jwt_ttl_secs: env::var("JWT_TTL_SECS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()
+                .unwrap_or(86400),
+            admin_api_key: env::var("ADMIN_API_KEY").ok().filter(|s| !s.is_empty()),
+            export_dir: env::var("EXPORT_DIR").ok().filter(|s| !s.is_empty()),
+            export_keep_dumps: env::var("EXPORT_KEEP_DUMPS")
+                .unwrap_or_else(|_| "7".to_string())
+                .parse()
+                .unwrap_or(7),
+            migrate_on_startup: env::var("MIGRATE_ON_STARTUP")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(true),
         })
     }
 }
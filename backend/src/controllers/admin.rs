@@ -0,0 +1,224 @@
+use actix_web::{web, HttpResponse};
+use mongodb::bson::oid::ObjectId;
+use crate::cache::ResponseCache;
+use crate::error::{ApiError, Problem};
+use crate::middleware::RequireRole;
+use crate::models::{ChampionAlias, ChampionAliasListResponse, Role, UserProfile, UserResponse};
+use crate::services::auth::AuthService;
+use crate::services::event_crawler::{IngestOptions, IngestReport};
+use crate::services::champion_aliases::NormalizeReport;
+use crate::services::{ChampionAliasService, EventCrawler};
+use mongodb::Database;
+use serde::Deserialize;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(Deserialize, ToSchema)]
+struct SetRoleRequest {
+    role: Role,
+}
+
+/// Change a user's role
+#[utoipa::path(
+    put,
+    path = "/api/admin/users/{user_id}/role",
+    tag = "admin",
+    params(("user_id" = String, Path, description = "User ID")),
+    request_body = SetRoleRequest,
+    responses(
+        (status = 200, description = "Updated user", body = UserResponse),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Admin role required", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn set_user_role(
+    auth: web::Data<AuthService>,
+    path: web::Path<String>,
+    body: web::Json<SetRoleRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = ObjectId::parse_str(path.into_inner())
+        .map_err(|_| ApiError::invalid_parameter("user_id", "not a valid user ID"))?;
+
+    let user = auth.set_role(user_id, body.role).await?;
+
+    Ok(HttpResponse::Ok().json(UserResponse {
+        user: UserProfile::from(&user),
+    }))
+}
+
+/// Fetch a single event from the upstream APIs and store it, optionally
+/// re-fetching standings and decklists to pick up organizer corrections
+#[utoipa::path(
+    post,
+    path = "/api/admin/events/{event_id}/ingest",
+    tag = "admin",
+    params(
+        ("event_id" = i32, Path, description = "Event ID"),
+        IngestOptions,
+    ),
+    responses(
+        (status = 200, description = "Ingestion report", body = IngestReport),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Admin role required", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Event not found upstream", body = Problem, content_type = "application/problem+json"),
+        (status = 500, description = "Upstream or database failure", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn ingest_event(
+    crawler: web::Data<EventCrawler>,
+    cache: web::Data<ResponseCache>,
+    path: web::Path<i32>,
+    options: web::Query<IngestOptions>,
+) -> Result<HttpResponse, ApiError> {
+    let event_id = path.into_inner();
+
+    let report = crawler
+        .ingest_event(event_id, options.into_inner())
+        .await
+        .map_err(|e| ApiError::internal("Failed to ingest event", e))?
+        .ok_or_else(|| ApiError::NotFound(format!("Event {} not found", event_id)))?;
+
+    // Cached statistics may include the re-ingested event
+    cache.invalidate_all();
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[derive(Deserialize, ToSchema)]
+struct SetAliasRequest {
+    /// Canonical champion slug
+    slug: String,
+}
+
+/// List champion aliases and the upstream champion strings no champion or alias matches
+#[utoipa::path(
+    get,
+    path = "/api/admin/champion-aliases",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Aliases and unresolved strings", body = ChampionAliasListResponse),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Admin role required", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn list_champion_aliases(db: web::Data<Database>) -> Result<HttpResponse, ApiError> {
+    let aliases = ChampionAliasService::new(db.get_ref().clone())
+        .list()
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch champion aliases", e))?;
+
+    Ok(HttpResponse::Ok().json(aliases))
+}
+
+/// Map an upstream champion string to a champion. Stored data is rewritten
+/// on the next normalization.
+#[utoipa::path(
+    put,
+    path = "/api/admin/champion-aliases/{alias}",
+    tag = "admin",
+    params(("alias" = String, Path, description = "Upstream champion string; stored slugified")),
+    request_body = SetAliasRequest,
+    responses(
+        (status = 200, description = "The alias", body = ChampionAlias),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Admin role required", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Champion not found", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn set_champion_alias(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    body: web::Json<SetAliasRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let alias = ChampionAliasService::new(db.get_ref().clone())
+        .set_alias(&path, &body.slug)
+        .await
+        .map_err(|e| ApiError::internal("Failed to save champion alias", e))?
+        .ok_or_else(|| ApiError::NotFound(format!("Champion {} not found", body.slug)))?;
+
+    Ok(HttpResponse::Ok().json(alias))
+}
+
+/// Remove a champion alias
+#[utoipa::path(
+    delete,
+    path = "/api/admin/champion-aliases/{alias}",
+    tag = "admin",
+    params(("alias" = String, Path, description = "Upstream champion string")),
+    responses(
+        (status = 204, description = "Alias removed"),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Admin role required", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Alias not found", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn delete_champion_alias(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let removed = ChampionAliasService::new(db.get_ref().clone())
+        .remove_alias(&path)
+        .await
+        .map_err(|e| ApiError::internal("Failed to remove champion alias", e))?;
+
+    if !removed {
+        return Err(ApiError::NotFound("Alias not found".to_string()));
+    }
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Rewrite stored standings and decklists to canonical champion slugs
+#[utoipa::path(
+    post,
+    path = "/api/admin/champion-aliases/normalize",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Normalization report", body = NormalizeReport),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Admin role required", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn normalize_champions(
+    db: web::Data<Database>,
+    cache: web::Data<ResponseCache>,
+) -> Result<HttpResponse, ApiError> {
+    let report = ChampionAliasService::new(db.get_ref().clone())
+        .normalize_stored()
+        .await
+        .map_err(|e| ApiError::internal("Failed to normalize champions", e))?;
+
+    // Cached statistics may group by the old strings
+    cache.invalidate_all();
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    set_user_role,
+    ingest_event,
+    list_champion_aliases,
+    set_champion_alias,
+    delete_champion_alias,
+    normalize_champions
+))]
+pub struct ApiDoc;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/admin")
+            .wrap(RequireRole::new(Role::Admin))
+            .route("/users/{user_id}/role", web::put().to(set_user_role))
+            .route("/events/{event_id}/ingest", web::post().to(ingest_event))
+            .route("/champion-aliases", web::get().to(list_champion_aliases))
+            .route("/champion-aliases/normalize", web::post().to(normalize_champions))
+            .route("/champion-aliases/{alias}", web::put().to(set_champion_alias))
+            .route("/champion-aliases/{alias}", web::delete().to(delete_champion_alias)),
+    );
+}
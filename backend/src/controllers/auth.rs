@@ -0,0 +1,219 @@
+use actix_web::{web, HttpResponse};
+use crate::error::{ApiError, Problem};
+use crate::models::{
+    ApiKeyCreatedResponse, ApiKeyInfo, ApiKeyListResponse, Role, TokenResponse, User, UserProfile,
+    UserResponse,
+};
+use crate::services::auth::{AuthError, AuthService, Principal, PrincipalKind};
+use mongodb::bson::oid::ObjectId;
+use serde::Deserialize;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(Deserialize, ToSchema)]
+struct RegisterRequest {
+    email: String,
+    username: String,
+    password: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct LoginRequest {
+    email: String,
+    password: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct CreateApiKeyRequest {
+    name: String,
+    role: Option<Role>,
+}
+
+fn token_response(auth: &AuthService, user: &User) -> Result<TokenResponse, AuthError> {
+    Ok(TokenResponse {
+        access_token: auth.issue_token(user)?,
+        token_type: "Bearer".to_string(),
+        expires_in: auth.token_ttl_secs(),
+        user: UserProfile::from(user),
+    })
+}
+
+/// Owner filter for API key management: admins manage all keys, users their own
+fn key_owner(principal: &Principal) -> Result<Option<ObjectId>, ApiError> {
+    if principal.role.satisfies(Role::Admin) {
+        return Ok(None);
+    }
+
+    principal
+        .user_id()
+        .map(Some)
+        .ok_or_else(|| ApiError::Forbidden("API keys can only be managed by users".to_string()))
+}
+
+/// Register a new user account
+#[utoipa::path(
+    post,
+    path = "/api/auth/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "Account created", body = TokenResponse),
+        (status = 400, description = "Invalid registration", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Email or username taken", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn register(
+    auth: web::Data<AuthService>,
+    body: web::Json<RegisterRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let user = auth.register(&body.email, &body.username, &body.password).await?;
+    Ok(HttpResponse::Created().json(token_response(&auth, &user)?))
+}
+
+/// Log in with email and password and receive a bearer token
+#[utoipa::path(
+    post,
+    path = "/api/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Logged in", body = TokenResponse),
+        (status = 401, description = "Invalid credentials", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn login(
+    auth: web::Data<AuthService>,
+    body: web::Json<LoginRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let user = auth.login(&body.email, &body.password).await?;
+    Ok(HttpResponse::Ok().json(token_response(&auth, &user)?))
+}
+
+/// Get the authenticated principal
+#[utoipa::path(
+    get,
+    path = "/api/auth/me",
+    tag = "auth",
+    responses(
+        (status = 200, description = "The user profile; API key callers receive `{ \"principal\": ... }` instead", body = UserResponse),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn me(auth: web::Data<AuthService>, principal: Principal) -> Result<HttpResponse, ApiError> {
+    match principal.user_id() {
+        Some(user_id) => {
+            let user = auth.get_user(user_id).await?;
+            Ok(HttpResponse::Ok().json(UserResponse {
+                user: UserProfile::from(&user),
+            }))
+        }
+        None => Ok(HttpResponse::Ok().json(serde_json::json!({ "principal": principal }))),
+    }
+}
+
+/// List the caller's API keys (admins see all keys)
+#[utoipa::path(
+    get,
+    path = "/api/auth/api-keys",
+    tag = "auth",
+    responses(
+        (status = 200, description = "API keys", body = ApiKeyListResponse),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a user account", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn list_api_keys(
+    auth: web::Data<AuthService>,
+    principal: Principal,
+) -> Result<HttpResponse, ApiError> {
+    let owner_id = key_owner(&principal)?;
+    let keys = auth.list_api_keys(owner_id).await?;
+
+    let api_keys: Vec<ApiKeyInfo> = keys.iter().map(ApiKeyInfo::from).collect();
+    let total = api_keys.len();
+
+    Ok(HttpResponse::Ok().json(ApiKeyListResponse { api_keys, total }))
+}
+
+/// Create an API key with at most the caller's role
+#[utoipa::path(
+    post,
+    path = "/api/auth/api-keys",
+    tag = "auth",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "API key created; the key is only returned once", body = ApiKeyCreatedResponse),
+        (status = 400, description = "Invalid request", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Role not allowed", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn create_api_key(
+    auth: web::Data<AuthService>,
+    principal: Principal,
+    body: web::Json<CreateApiKeyRequest>,
+) -> Result<HttpResponse, ApiError> {
+    if principal.kind != PrincipalKind::User && !principal.role.satisfies(Role::Admin) {
+        return Err(ApiError::Forbidden("API keys can only be managed by users".to_string()));
+    }
+
+    let role = body.role.unwrap_or(Role::User);
+    if !principal.role.satisfies(role) {
+        return Err(ApiError::Forbidden(
+            "Cannot create an API key with a higher role than your own".to_string(),
+        ));
+    }
+
+    if body.name.trim().is_empty() {
+        return Err(ApiError::invalid_parameter("name", "must not be empty"));
+    }
+
+    let (api_key, key) = auth.create_api_key(&body.name, role, principal.user_id()).await?;
+
+    Ok(HttpResponse::Created().json(ApiKeyCreatedResponse {
+        key,
+        api_key: ApiKeyInfo::from(&api_key),
+    }))
+}
+
+/// Revoke one of the caller's API keys (admins may revoke any key)
+#[utoipa::path(
+    delete,
+    path = "/api/auth/api-keys/{key_id}",
+    tag = "auth",
+    params(("key_id" = String, Path, description = "API key ID")),
+    responses(
+        (status = 204, description = "API key revoked"),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "API key not found", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn revoke_api_key(
+    auth: web::Data<AuthService>,
+    principal: Principal,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let owner_id = key_owner(&principal)?;
+    auth.revoke_api_key(&path.into_inner(), owner_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(register, login, me, list_api_keys, create_api_key, revoke_api_key), components(schemas(Principal)))]
+pub struct ApiDoc;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/auth")
+            .route("/register", web::post().to(register))
+            .route("/login", web::post().to(login))
+            .route("/me", web::get().to(me))
+            .route("/api-keys", web::get().to(list_api_keys))
+            .route("/api-keys", web::post().to(create_api_key))
+            .route("/api-keys/{key_id}", web::delete().to(revoke_api_key)),
+    );
+}
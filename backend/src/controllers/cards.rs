@@ -0,0 +1,405 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use mongodb::{Database, Collection};
+use mongodb::bson::{doc, Document};
+use crate::error::{ApiError, Problem};
+use crate::models::{
+    Card, CardHistoryResponse, CardListResponse, CardPerformanceResponse, CardPrintingsResponse, CardResponse,
+    CardRevision, CardSet, Printing, SetListResponse,
+};
+use crate::services::card_sync::{CARD_REVISIONS_COLLECTION, PRINTINGS_COLLECTION, SETS_COLLECTION};
+use crate::services::MetaAnalysisService;
+use serde::Deserialize;
+use log::error;
+use utoipa::{IntoParams, OpenApi};
+use super::filters::{contains_ci, exact_ci, EventFilterParams, EventFilters};
+use super::pagination::{parse_sort, Pagination};
+
+/// Fields that card lists may be sorted by
+const CARD_SORT_FIELDS: &[&str] = &["name", "cost", "power", "rarity", "set_name", "element", "card_type"];
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(super) struct CardSearchQuery {
+    /// Full-text query over name and card text
+    q: Option<String>,
+    /// Name substring (case-insensitive)
+    name: Option<String>,
+    /// Card text substring (case-insensitive)
+    text: Option<String>,
+    /// Card type, e.g. ACTION or ALLY
+    #[serde(rename = "type")]
+    card_type: Option<String>,
+    element: Option<String>,
+    class: Option<String>,
+    cost: Option<i32>,
+    rarity: Option<String>,
+    /// Set name or prefix; matches cards with any printing in the set
+    set: Option<String>,
+    /// Page number (1-based)
+    page: Option<u64>,
+    /// Items per page (default 50, max 200)
+    page_size: Option<u64>,
+    /// Sort field: name, cost, power, rarity, set_name, element or card_type; prefix with `-` for descending
+    sort: Option<String>,
+}
+
+/// Build the MongoDB filter for a card search, looking up the cards printed
+/// in the requested set
+pub(super) async fn card_filter(db: &Database, query: &CardSearchQuery) -> Result<Document, ApiError> {
+    let printed_in_set = match &query.set {
+        Some(set) => {
+            let filter = doc! { "$or": [{ "set_prefix": exact_ci(set) }, { "set_name": exact_ci(set) }] };
+            let slugs = db
+                .collection::<Printing>(PRINTINGS_COLLECTION)
+                .distinct("card_slug", filter, None)
+                .await
+                .map_err(|e| ApiError::internal("Failed to fetch cards", e))?
+                .into_iter()
+                .filter_map(|slug| slug.as_str().map(str::to_string))
+                .collect();
+            Some(slugs)
+        }
+        None => None,
+    };
+
+    Ok(build_card_filter(query, printed_in_set))
+}
+
+/// Build the MongoDB filter for a card search. `printed_in_set` holds the
+/// slugs of the cards printed in the requested set.
+fn build_card_filter(query: &CardSearchQuery, printed_in_set: Option<Vec<String>>) -> Document {
+    let mut filter = doc! {};
+
+    if let Some(q) = query.q.as_deref().filter(|q| !q.trim().is_empty()) {
+        filter.insert("$text", doc! { "$search": q });
+    }
+    if let Some(name) = &query.name {
+        filter.insert("name", contains_ci(name));
+    }
+    if let Some(text) = &query.text {
+        filter.insert("card_text", contains_ci(text));
+    }
+    if let Some(card_type) = &query.card_type {
+        filter.insert("card_type", exact_ci(card_type));
+    }
+    if let Some(element) = &query.element {
+        filter.insert("element", exact_ci(element));
+    }
+    if let Some(class) = &query.class {
+        filter.insert("classes", exact_ci(class));
+    }
+    if let Some(cost) = query.cost {
+        filter.insert("cost", cost);
+    }
+    if let Some(rarity) = &query.rarity {
+        filter.insert("rarity", exact_ci(rarity));
+    }
+    if let Some(slugs) = printed_in_set {
+        filter.insert("slug", doc! { "$in": slugs });
+    }
+
+    filter
+}
+
+/// Search cards by text and attribute filters
+#[utoipa::path(
+    get,
+    path = "/api/cards",
+    tag = "cards",
+    params(CardSearchQuery),
+    responses(
+        (status = 200, description = "Matching cards", body = CardListResponse),
+        (status = 400, description = "Invalid query parameters", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn search_cards(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    query: web::Query<CardSearchQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let collection: Collection<Card> = db.collection("cards");
+
+    let filter = card_filter(&db, &query).await?;
+    let pagination = Pagination::new(query.page, query.page_size)?;
+
+    // Rank by text relevance unless an explicit sort was requested
+    let text_search = filter.contains_key("$text");
+    let default_sort = if text_search {
+        doc! { "score": { "$meta": "textScore" }, "name": 1 }
+    } else {
+        doc! { "name": 1 }
+    };
+    let sort = parse_sort(query.sort.as_deref(), CARD_SORT_FIELDS, default_sort)?;
+
+    let mut find_options = pagination.find_options(sort);
+    if text_search {
+        find_options.projection = Some(doc! { "score": { "$meta": "textScore" } });
+    }
+
+    let total = collection
+        .count_documents(filter.clone(), None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to search cards", e))?;
+
+    let mut cursor = collection
+        .find(filter, find_options)
+        .await
+        .map_err(|e| ApiError::internal("Failed to search cards", e))?;
+
+    use futures::stream::StreamExt;
+    let mut cards = Vec::new();
+
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(card) => cards.push(card),
+            Err(e) => {
+                error!("Error reading card: {}", e);
+            }
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(CardListResponse {
+        cards,
+        pagination: pagination.meta(&req, total),
+    }))
+}
+
+/// Get a single card by slug together with its usage statistics
+#[utoipa::path(
+    get,
+    path = "/api/cards/{slug}",
+    tag = "cards",
+    params(("slug" = String, Path, description = "Card slug")),
+    responses(
+        (status = 200, description = "The card and its usage", body = CardResponse),
+        (status = 404, description = "Card not found", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn get_card_by_slug(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let slug = path.into_inner();
+    let collection: Collection<Card> = db.collection("cards");
+
+    let card = collection
+        .find_one(doc! { "slug": &slug }, None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch card", e))?
+        .ok_or_else(|| ApiError::NotFound("Card not found".to_string()))?;
+
+    let service = MetaAnalysisService::new(db.get_ref().clone());
+
+    let usage = service
+        .calculate_card_usage(&slug)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch card", e))?;
+
+    Ok(HttpResponse::Ok().json(CardResponse { card, usage }))
+}
+
+/// List card sets, newest release first
+#[utoipa::path(
+    get,
+    path = "/api/cards/sets",
+    tag = "cards",
+    responses(
+        (status = 200, description = "Card sets", body = SetListResponse),
+    ),
+)]
+async fn get_sets(db: web::Data<Database>) -> Result<HttpResponse, ApiError> {
+    use futures::stream::TryStreamExt;
+
+    let collection: Collection<CardSet> = db.collection(SETS_COLLECTION);
+    let mut sets: Vec<CardSet> = collection
+        .find(doc! {}, None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch sets", e))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch sets", e))?;
+
+    // Newest first, undated sets last
+    sets.sort_by(|a, b| {
+        a.release_date
+            .is_none()
+            .cmp(&b.release_date.is_none())
+            .then_with(|| b.release_date.cmp(&a.release_date))
+            .then_with(|| a.prefix.cmp(&b.prefix))
+    });
+
+    let total = sets.len();
+    Ok(HttpResponse::Ok().json(SetListResponse { sets, total }))
+}
+
+/// List the printings of a card across sets
+#[utoipa::path(
+    get,
+    path = "/api/cards/{slug}/printings",
+    tag = "cards",
+    params(("slug" = String, Path, description = "Card slug")),
+    responses(
+        (status = 200, description = "The card's printings", body = CardPrintingsResponse),
+        (status = 404, description = "Card not found", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn get_card_printings(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    use futures::stream::TryStreamExt;
+
+    let slug = path.into_inner();
+
+    let known = db
+        .collection::<Card>("cards")
+        .count_documents(doc! { "slug": &slug }, None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch printings", e))?;
+    if known == 0 {
+        return Err(ApiError::NotFound("Card not found".to_string()));
+    }
+
+    let options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "set_prefix": 1, "collector_number": 1 })
+        .build();
+    let printings = db
+        .collection::<Printing>(PRINTINGS_COLLECTION)
+        .find(doc! { "card_slug": &slug }, options)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch printings", e))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch printings", e))?;
+
+    Ok(HttpResponse::Ok().json(CardPrintingsResponse { slug, printings }))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct CardHistoryQuery {
+    /// Page number (1-based)
+    page: Option<u64>,
+    /// Items per page (default 50, max 200)
+    page_size: Option<u64>,
+}
+
+/// Get the changes card syncs detected in a card, such as errata, bans or
+/// cost and power edits, newest first
+#[utoipa::path(
+    get,
+    path = "/api/cards/{slug}/history",
+    tag = "cards",
+    params(("slug" = String, Path, description = "Card slug"), CardHistoryQuery),
+    responses(
+        (status = 200, description = "Card revisions", body = CardHistoryResponse),
+        (status = 404, description = "Card not found", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn get_card_history(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    query: web::Query<CardHistoryQuery>,
+) -> Result<HttpResponse, ApiError> {
+    use futures::stream::TryStreamExt;
+
+    let slug = path.into_inner();
+
+    let known = db
+        .collection::<Card>("cards")
+        .count_documents(doc! { "slug": &slug }, None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch card history", e))?;
+    if known == 0 {
+        return Err(ApiError::NotFound("Card not found".to_string()));
+    }
+
+    let collection: Collection<CardRevision> = db.collection(CARD_REVISIONS_COLLECTION);
+    let pagination = Pagination::new(query.page, query.page_size)?;
+    let filter = doc! { "slug": &slug };
+
+    let total = collection
+        .count_documents(filter.clone(), None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch card history", e))?;
+
+    let revisions = collection
+        .find(filter, pagination.find_options(doc! { "recorded_at": -1 }))
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch card history", e))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch card history", e))?;
+
+    Ok(HttpResponse::Ok().json(CardHistoryResponse {
+        slug,
+        revisions,
+        pagination: pagination.meta(&req, total),
+    }))
+}
+
+/// Get card performance statistics
+#[utoipa::path(
+    get,
+    path = "/api/cards/performance",
+    tag = "cards",
+    params(EventFilterParams),
+    responses(
+        (status = 200, description = "Card inclusion statistics in ranked events", body = CardPerformanceResponse),
+        (status = 400, description = "Invalid query parameters", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn get_card_performance(
+    db: web::Data<Database>,
+    filters: EventFilters,
+) -> Result<HttpResponse, ApiError> {
+    let filters = filters.supporting(&["limit"])?;
+    let service = MetaAnalysisService::new(db.get_ref().clone());
+
+    let cards = service
+        .calculate_card_performance(filters.format, filters.range, filters.limit)
+        .await
+        .map_err(|e| ApiError::internal("Failed to calculate card performance", e))?;
+
+    let total = cards.len();
+    Ok(HttpResponse::Ok().json(CardPerformanceResponse { cards, total }))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(search_cards, get_sets, get_card_by_slug, get_card_printings, get_card_history, get_card_performance))]
+pub struct ApiDoc;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/cards")
+            .route("", web::get().to(search_cards))
+            .route("/performance", web::get().to(get_card_performance))
+            .route("/sets", web::get().to(get_sets))
+            .route("/{slug}", web::get().to(get_card_by_slug))
+            .route("/{slug}/printings", web::get().to(get_card_printings))
+            .route("/{slug}/history", web::get().to(get_card_history)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(query: &str) -> CardSearchQuery {
+        web::Query::<CardSearchQuery>::from_query(query).unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_set_filter_matches_printings() {
+        let filter = build_card_filter(&query("set=DOA&cost=2"), Some(vec!["fireball".to_string()]));
+        assert_eq!(filter, doc! { "cost": 2, "slug": { "$in": ["fireball"] } });
+        assert!(!filter.contains_key("set_name"));
+
+        // A set without printings matches nothing rather than every card
+        let filter = build_card_filter(&query("set=XYZ"), Some(Vec::new()));
+        assert_eq!(filter, doc! { "slug": { "$in": [] } });
+
+        assert_eq!(build_card_filter(&query("cost=2"), None), doc! { "cost": 2 });
+    }
+}
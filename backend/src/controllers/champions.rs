@@ -1,65 +1,254 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse};
 use mongodb::{Database, Collection};
 use mongodb::bson::doc;
-use crate::models::{Champion, ChampionListResponse, ChampionResponse};
+use crate::error::{ApiError, Problem};
+use crate::models::{Champion, ChampionLineage, ChampionLineageListResponse, ChampionListResponse, ChampionResponse};
+use crate::services::meta_analysis::{ChampionOverview, MaterialChoices};
+use crate::services::MetaAnalysisService;
+use serde::{Deserialize, Serialize};
+use futures::stream::TryStreamExt;
 use log::error;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use super::filters::{EventFilterParams, EventFilters};
+use super::pagination::{parse_sort, Pagination};
+
+/// Fields that champion lists may be sorted by
+const CHAMPION_SORT_FIELDS: &[&str] = &["name", "slug", "element", "class"];
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ChampionQuery {
+    /// Page number (1-based)
+    page: Option<u64>,
+    /// Items per page (default 50, max 200)
+    page_size: Option<u64>,
+    /// Sort field: name, slug, element or class; prefix with `-` for descending
+    sort: Option<String>,
+}
+
+/// Response structure for champion overview queries
+#[derive(Serialize, ToSchema)]
+struct ChampionOverviewResponse {
+    /// Catalogue entry, if the champion is known to the card database
+    champion: Option<Champion>,
+    overview: ChampionOverview,
+}
+
+/// Response structure for champion material choices
+#[derive(Serialize, ToSchema)]
+struct MaterialChoicesResponse {
+    materials: MaterialChoices,
+}
 
 /// Get all champions
-async fn get_champions(db: web::Data<Database>) -> impl Responder {
+#[utoipa::path(
+    get,
+    path = "/api/champions",
+    tag = "champions",
+    params(ChampionQuery),
+    responses(
+        (status = 200, description = "Champions", body = ChampionListResponse),
+    ),
+)]
+async fn get_champions(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    query: web::Query<ChampionQuery>,
+) -> Result<HttpResponse, ApiError> {
     let collection: Collection<Champion> = db.collection("champions");
 
-    match collection.find(doc! {}, None).await {
-        Ok(mut cursor) => {
-            use futures::stream::StreamExt;
-            let mut champions = Vec::new();
-
-            while let Some(result) = cursor.next().await {
-                match result {
-                    Ok(champion) => champions.push(champion),
-                    Err(e) => {
-                        error!("Error reading champion: {}", e);
-                    }
-                }
-            }
+    let pagination = Pagination::new(query.page, query.page_size)?;
+    let sort = parse_sort(query.sort.as_deref(), CHAMPION_SORT_FIELDS, doc! { "name": 1 })?;
 
-            let total = champions.len();
-            HttpResponse::Ok().json(ChampionListResponse { champions, total })
-        }
-        Err(e) => {
-            error!("Failed to fetch champions: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to fetch champions"
-            }))
+    let total = collection
+        .count_documents(doc! {}, None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch champions", e))?;
+
+    let mut cursor = collection
+        .find(doc! {}, pagination.find_options(sort))
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch champions", e))?;
+
+    use futures::stream::StreamExt;
+    let mut champions = Vec::new();
+
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(champion) => champions.push(champion),
+            Err(e) => {
+                error!("Error reading champion: {}", e);
+            }
         }
     }
+
+    Ok(HttpResponse::Ok().json(ChampionListResponse {
+        champions,
+        pagination: pagination.meta(&req, total),
+    }))
+}
+
+/// Get all champion lineages (champion cards of one family across levels)
+#[utoipa::path(
+    get,
+    path = "/api/champions/lineages",
+    tag = "champions",
+    responses(
+        (status = 200, description = "Champion lineages", body = ChampionLineageListResponse),
+    ),
+)]
+async fn get_lineages(db: web::Data<Database>) -> Result<HttpResponse, ApiError> {
+    let collection: Collection<ChampionLineage> = db.collection("champion_lineages");
+
+    let options = mongodb::options::FindOptions::builder().sort(doc! { "name": 1 }).build();
+    let lineages: Vec<ChampionLineage> = collection
+        .find(doc! {}, options)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch lineages", e))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch lineages", e))?;
+
+    let total = lineages.len();
+    Ok(HttpResponse::Ok().json(ChampionLineageListResponse { lineages, total }))
+}
+
+/// Get a single champion lineage by family slug
+#[utoipa::path(
+    get,
+    path = "/api/champions/lineages/{family}",
+    tag = "champions",
+    params(("family" = String, Path, description = "Family slug, e.g. `lorraine`")),
+    responses(
+        (status = 200, description = "The lineage", body = ChampionLineage),
+        (status = 404, description = "Lineage not found", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn get_lineage(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let collection: Collection<ChampionLineage> = db.collection("champion_lineages");
+
+    match collection
+        .find_one(doc! { "family": path.as_str() }, None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch lineage", e))?
+    {
+        Some(lineage) => Ok(HttpResponse::Ok().json(lineage)),
+        None => Err(ApiError::NotFound("Lineage not found".to_string())),
+    }
 }
 
 /// Get a single champion by slug
+#[utoipa::path(
+    get,
+    path = "/api/champions/{slug}",
+    tag = "champions",
+    params(("slug" = String, Path, description = "Champion slug")),
+    responses(
+        (status = 200, description = "The champion", body = ChampionResponse),
+        (status = 404, description = "Champion not found", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
 async fn get_champion_by_slug(
     db: web::Data<Database>,
     path: web::Path<String>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let slug = path.into_inner();
     let collection: Collection<Champion> = db.collection("champions");
 
-    match collection.find_one(doc! { "slug": &slug }, None).await {
-        Ok(Some(champion)) => HttpResponse::Ok().json(ChampionResponse { champion }),
-        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Champion not found"
-        })),
-        Err(e) => {
-            error!("Failed to fetch champion '{}': {}", slug, e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to fetch champion"
-            }))
-        }
+    match collection
+        .find_one(doc! { "slug": &slug }, None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch champion", e))?
+    {
+        Some(champion) => Ok(HttpResponse::Ok().json(ChampionResponse { champion })),
+        None => Err(ApiError::NotFound("Champion not found".to_string())),
     }
 }
 
+/// Get all champion page data (meta share, results, top lists and cards) in one response
+#[utoipa::path(
+    get,
+    path = "/api/champions/{slug}/overview",
+    tag = "champions",
+    params(("slug" = String, Path, description = "Champion slug"), EventFilterParams),
+    responses(
+        (status = 200, description = "Champion overview", body = ChampionOverviewResponse),
+        (status = 400, description = "Invalid query parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Champion not found", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn get_champion_overview(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    filters: EventFilters,
+) -> Result<HttpResponse, ApiError> {
+    let filters = filters.supporting(&[])?;
+    let slug = path.into_inner();
+
+    let collection: Collection<Champion> = db.collection("champions");
+
+    let champion = collection
+        .find_one(doc! { "slug": &slug }, None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch champion overview", e))?;
+
+    let service = MetaAnalysisService::new(db.get_ref().clone());
+
+    let overview = service
+        .calculate_champion_overview(&slug, filters.format, filters.range)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch champion overview", e))?;
+
+    if champion.is_none() && overview.total_appearances == 0 {
+        return Err(ApiError::NotFound("Champion not found".to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(ChampionOverviewResponse { champion, overview }))
+}
+
+/// Get the champion lineages, champion cards and regalia played in a champion's material decks
+#[utoipa::path(
+    get,
+    path = "/api/champions/{slug}/materials",
+    tag = "champions",
+    params(("slug" = String, Path, description = "Champion slug"), EventFilterParams),
+    responses(
+        (status = 200, description = "Material deck choices", body = MaterialChoicesResponse),
+        (status = 400, description = "Invalid query parameters", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn get_champion_materials(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    filters: EventFilters,
+) -> Result<HttpResponse, ApiError> {
+    let filters = filters.supporting(&[])?;
+    let service = MetaAnalysisService::new(db.get_ref().clone());
+
+    let materials = service
+        .calculate_material_choices(&path, filters.format, filters.range)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch material choices", e))?;
+
+    Ok(HttpResponse::Ok().json(MaterialChoicesResponse { materials }))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(get_champions, get_lineages, get_lineage, get_champion_by_slug, get_champion_overview, get_champion_materials))]
+pub struct ApiDoc;
+
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/champions")
             .route("", web::get().to(get_champions))
-            .route("/{slug}", web::get().to(get_champion_by_slug)),
+            // Registered before /{slug} so "lineages" is not taken for a champion slug
+            .route("/lineages", web::get().to(get_lineages))
+            .route("/lineages/{family}", web::get().to(get_lineage))
+            .route("/{slug}", web::get().to(get_champion_by_slug))
+            .route("/{slug}/overview", web::get().to(get_champion_overview))
+            .route("/{slug}/materials", web::get().to(get_champion_materials)),
     );
 }
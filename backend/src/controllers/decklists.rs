@@ -1,32 +1,65 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse};
 use mongodb::{Database, Collection};
 use mongodb::bson::{doc, Document};
+use crate::error::{ApiError, Problem};
 use crate::models::{Decklist, DecklistListResponse, DecklistResponse};
 use serde::Deserialize;
 use log::error;
-use chrono::{Utc, Duration};
+use utoipa::{IntoParams, OpenApi};
+use super::filters::{EventFilterParams, EventFilters};
+use super::pagination::{parse_sort, Pagination};
 
-#[derive(Deserialize)]
+/// Fields that decklist lists may be sorted by
+const DECKLIST_SORT_FIELDS: &[&str] = &["rank", "event_id", "player_name", "champion", "updated_at"];
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct DecklistQuery {
+    /// Champion slug
     champion: Option<String>,
-    format: Option<String>,
-    days: Option<i32>,
-    limit: Option<i64>,
+    /// Deprecated alias for `page_size`
+    limit: Option<u64>,
+    /// Page number (1-based)
+    page: Option<u64>,
+    /// Items per page (default 50, max 200)
+    page_size: Option<u64>,
+    /// Sort field: rank, event_id, player_name, champion or updated_at; prefix with `-` for descending
+    sort: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct PlayerDecklistQuery {
+    /// Only the decklist from this event
     event: Option<i32>,
+    /// Page number (1-based)
+    page: Option<u64>,
+    /// Items per page (default 50, max 200)
+    page_size: Option<u64>,
 }
 
 /// Get decklists with optional filters
+#[utoipa::path(
+    get,
+    path = "/api/decklists",
+    tag = "decklists",
+    params(EventFilterParams, DecklistQuery),
+    responses(
+        (status = 200, description = "Tournament decklists", body = DecklistListResponse),
+        (status = 400, description = "Invalid query parameters", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
 async fn get_decklists(
+    req: HttpRequest,
     db: web::Data<Database>,
+    filters: EventFilters,
     query: web::Query<DecklistQuery>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    let filters = filters.supporting(&["limit"])?;
     let decklists_collection: Collection<Decklist> = db.collection("decklists");
     let events_collection: Collection<Document> = db.collection("events");
 
+    let pagination = Pagination::new(query.page, query.page_size.or(query.limit))?;
     let mut filter = doc! {};
 
     // Filter by champion
@@ -34,92 +67,95 @@ async fn get_decklists(
         filter.insert("champion", champion);
     }
 
-    // If filtering by format or days, we need to join with events
-    if query.format.is_some() || query.days.is_some() {
+    // If filtering by format or date range, we need to join with events
+    if filters.format.is_some() || !filters.range.is_unbounded() {
         let mut event_filter = doc! {};
 
-        if let Some(format_str) = &query.format {
-            event_filter.insert("format", format_str.to_uppercase());
+        if let Some(format) = &filters.format {
+            if let Ok(format_bson) = mongodb::bson::to_bson(format) {
+                event_filter.insert("format", format_bson);
+            }
         }
 
-        if let Some(days) = query.days {
-            let cutoff_date = Utc::now() - Duration::days(days as i64);
-            event_filter.insert("start_date", doc! { "$gte": mongodb::bson::DateTime::from_chrono(cutoff_date) });
+        if let Some(start_date) = filters.range.condition() {
+            event_filter.insert("start_date", start_date);
         }
 
         // Get matching event IDs
-        match events_collection.find(event_filter, None).await {
-            Ok(mut cursor) => {
-                use futures::stream::StreamExt;
-                let mut event_ids = Vec::new();
-
-                while let Some(result) = cursor.next().await {
-                    if let Ok(doc) = result {
-                        if let Ok(event_id) = doc.get_i32("event_id") {
-                            event_ids.push(event_id);
-                        }
-                    }
-                }
-
-                if !event_ids.is_empty() {
-                    filter.insert("event_id", doc! { "$in": event_ids });
-                } else {
-                    // No events match, return empty result
-                    return HttpResponse::Ok().json(DecklistListResponse {
-                        decklists: Vec::new(),
-                        total: 0,
-                    });
+        let mut cursor = events_collection
+            .find(event_filter, None)
+            .await
+            .map_err(|e| ApiError::internal("Failed to fetch decklists", e))?;
+
+        use futures::stream::StreamExt;
+        let mut event_ids = Vec::new();
+
+        while let Some(result) = cursor.next().await {
+            if let Ok(doc) = result {
+                if let Ok(event_id) = doc.get_i32("event_id") {
+                    event_ids.push(event_id);
                 }
             }
-            Err(e) => {
-                error!("Failed to fetch events for decklist filter: {}", e);
-                return HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": "Failed to fetch decklists"
-                }));
-            }
         }
-    }
 
-    let mut find_options = mongodb::options::FindOptions::builder()
-        .sort(doc! { "rank": 1 })
-        .build();
+        if event_ids.is_empty() {
+            // No events match, return empty result
+            return Ok(HttpResponse::Ok().json(DecklistListResponse {
+                decklists: Vec::new(),
+                pagination: pagination.meta(&req, 0),
+            }));
+        }
 
-    if let Some(limit) = query.limit {
-        find_options.limit = Some(limit);
+        filter.insert("event_id", doc! { "$in": event_ids });
     }
 
-    match decklists_collection.find(filter, find_options).await {
-        Ok(mut cursor) => {
-            use futures::stream::StreamExt;
-            let mut decklists = Vec::new();
-
-            while let Some(result) = cursor.next().await {
-                match result {
-                    Ok(decklist) => decklists.push(decklist),
-                    Err(e) => {
-                        error!("Error reading decklist: {}", e);
-                    }
-                }
-            }
+    let sort = parse_sort(query.sort.as_deref(), DECKLIST_SORT_FIELDS, doc! { "rank": 1 })?;
 
-            let total = decklists.len();
-            HttpResponse::Ok().json(DecklistListResponse { decklists, total })
-        }
-        Err(e) => {
-            error!("Failed to fetch decklists: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to fetch decklists"
-            }))
+    let total = decklists_collection
+        .count_documents(filter.clone(), None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch decklists", e))?;
+
+    let mut cursor = decklists_collection
+        .find(filter, pagination.find_options(sort))
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch decklists", e))?;
+
+    use futures::stream::StreamExt;
+    let mut decklists = Vec::new();
+
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(decklist) => decklists.push(decklist),
+            Err(e) => {
+                error!("Error reading decklist: {}", e);
+            }
         }
     }
+
+    Ok(HttpResponse::Ok().json(DecklistListResponse {
+        decklists,
+        pagination: pagination.meta(&req, total),
+    }))
 }
 
 /// Get a player's decklist, optionally filtered by event
+#[utoipa::path(
+    get,
+    path = "/api/decklists/{player_id}",
+    tag = "decklists",
+    params(("player_id" = String, Path, description = "Omnidex player ID"), PlayerDecklistQuery),
+    responses(
+        (status = 200, description = "A `DecklistResponse` when `event` matches a single decklist, otherwise a `DecklistListResponse`", body = DecklistListResponse),
+        (status = 404, description = "No decklists found for player", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
 async fn get_player_decklist(
+    req: HttpRequest,
     db: web::Data<Database>,
     path: web::Path<String>,
     query: web::Query<PlayerDecklistQuery>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let player_id = path.into_inner();
     let collection: Collection<Decklist> = db.collection("decklists");
 
@@ -129,52 +165,50 @@ async fn get_player_decklist(
         filter.insert("event_id", event_id);
     }
 
-    use mongodb::options::FindOptions;
-    let find_options = FindOptions::builder()
-        .sort(doc! { "event_id": -1 })
-        .build();
+    let pagination = Pagination::new(query.page, query.page_size)?;
 
-    match collection
-        .find(filter, find_options)
+    let total = collection
+        .count_documents(filter.clone(), None)
         .await
-    {
-        Ok(mut cursor) => {
-            use futures::stream::StreamExt;
-            let mut decklists = Vec::new();
-
-            while let Some(result) = cursor.next().await {
-                match result {
-                    Ok(decklist) => decklists.push(decklist),
-                    Err(e) => {
-                        error!("Error reading decklist: {}", e);
-                    }
-                }
-            }
+        .map_err(|e| ApiError::internal("Failed to fetch decklists", e))?;
+
+    let mut cursor = collection
+        .find(filter, pagination.find_options(doc! { "event_id": -1 }))
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch decklists", e))?;
+
+    use futures::stream::StreamExt;
+    let mut decklists = Vec::new();
 
-            if decklists.is_empty() {
-                HttpResponse::NotFound().json(serde_json::json!({
-                    "error": "No decklists found for player"
-                }))
-            } else if query.event.is_some() && decklists.len() == 1 {
-                // Return single decklist if event filter was specified
-                HttpResponse::Ok().json(DecklistResponse {
-                    decklist: decklists.into_iter().next().unwrap(),
-                })
-            } else {
-                // Return list of decklists
-                let total = decklists.len();
-                HttpResponse::Ok().json(DecklistListResponse { decklists, total })
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(decklist) => decklists.push(decklist),
+            Err(e) => {
+                error!("Error reading decklist: {}", e);
             }
         }
-        Err(e) => {
-            error!("Failed to fetch decklists for player '{}': {}", player_id, e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to fetch decklists"
-            }))
-        }
+    }
+
+    if decklists.is_empty() {
+        Err(ApiError::NotFound("No decklists found for player".to_string()))
+    } else if query.event.is_some() && decklists.len() == 1 {
+        // Return single decklist if event filter was specified
+        Ok(HttpResponse::Ok().json(DecklistResponse {
+            decklist: decklists.into_iter().next().unwrap(),
+        }))
+    } else {
+        // Return list of decklists
+        Ok(HttpResponse::Ok().json(DecklistListResponse {
+            decklists,
+            pagination: pagination.meta(&req, total),
+        }))
     }
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(get_decklists, get_player_decklist), components(schemas(DecklistResponse)))]
+pub struct ApiDoc;
+
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/decklists")
@@ -1,145 +1,195 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse};
 use mongodb::{Database, Collection};
 use mongodb::bson::doc;
-use crate::models::{Event, EventFormat, EventListResponse, EventResponse, Standing, StandingListResponse};
+use crate::error::{ApiError, Problem};
+use crate::models::{Event, EventListResponse, EventResponse, Standing, StandingListResponse};
 use serde::Deserialize;
 use log::error;
-use chrono::{Utc, Duration};
+use utoipa::{IntoParams, OpenApi};
+use super::filters::{EventFilterParams, EventFilters};
+use super::pagination::{parse_sort, Pagination};
 
-#[derive(Deserialize)]
+/// Fields that event lists may be sorted by
+const EVENT_SORT_FIELDS: &[&str] = &["start_date", "player_count", "name", "event_id"];
+
+/// Fields that standings lists may be sorted by
+const STANDING_SORT_FIELDS: &[&str] = &["rank", "wins", "match_win_rate", "player_name"];
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct EventQuery {
-    format: Option<String>,
-    days: Option<i32>,
-    #[serde(rename = "minPlayers")]
-    min_players: Option<i32>,
+    /// Page number (1-based)
+    page: Option<u64>,
+    /// Items per page (default 50, max 200)
+    page_size: Option<u64>,
+    /// Sort field: start_date, player_count, name or event_id; prefix with `-` for descending
+    sort: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct StandingQuery {
+    /// Page number (1-based)
+    page: Option<u64>,
+    /// Items per page (default 50, max 200)
+    page_size: Option<u64>,
+    /// Sort field: rank, wins, match_win_rate or player_name; prefix with `-` for descending
+    sort: Option<String>,
 }
 
 /// Get events with optional filters
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    tag = "events",
+    params(EventFilterParams, EventQuery),
+    responses(
+        (status = 200, description = "Completed events", body = EventListResponse),
+        (status = 400, description = "Invalid query parameters", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 async fn get_events(
+    req: HttpRequest,
     db: web::Data<Database>,
+    filters: EventFilters,
     query: web::Query<EventQuery>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    let filters = filters.supporting(&["minPlayers"])?;
     let collection: Collection<Event> = db.collection("events");
 
     let mut filter = doc! { "status": "complete" };
 
     // Filter by format
-    if let Some(format_str) = &query.format {
-        let format = EventFormat::from_str(format_str);
-        if let Ok(format_bson) = mongodb::bson::to_bson(&format) {
+    if let Some(format) = &filters.format {
+        if let Ok(format_bson) = mongodb::bson::to_bson(format) {
             filter.insert("format", format_bson);
         }
     }
 
-    // Filter by days
-    if let Some(days) = query.days {
-        let cutoff_date = Utc::now() - Duration::days(days as i64);
-        filter.insert("start_date", doc! { "$gte": mongodb::bson::DateTime::from_chrono(cutoff_date) });
+    // Filter by date range
+    if let Some(start_date) = filters.range.condition() {
+        filter.insert("start_date", start_date);
     }
 
     // Filter by minimum players
-    if let Some(min_players) = query.min_players {
+    if let Some(min_players) = filters.min_players {
         filter.insert("player_count", doc! { "$gte": min_players });
     }
 
-    use mongodb::options::FindOptions;
-    let find_options = FindOptions::builder()
-        .sort(doc! { "start_date": -1 })
-        .build();
+    let pagination = Pagination::new(query.page, query.page_size)?;
+    let sort = parse_sort(query.sort.as_deref(), EVENT_SORT_FIELDS, doc! { "start_date": -1 })?;
 
-    match collection
-        .find(filter, find_options)
+    let total = collection
+        .count_documents(filter.clone(), None)
         .await
-    {
-        Ok(mut cursor) => {
-            use futures::stream::StreamExt;
-            let mut events = Vec::new();
-
-            while let Some(result) = cursor.next().await {
-                match result {
-                    Ok(event) => events.push(event),
-                    Err(e) => {
-                        error!("Error reading event: {}", e);
-                    }
-                }
-            }
+        .map_err(|e| ApiError::internal("Failed to fetch events", e))?;
 
-            let total = events.len();
-            HttpResponse::Ok().json(EventListResponse { events, total })
-        }
-        Err(e) => {
-            error!("Failed to fetch events: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to fetch events"
-            }))
+    let mut cursor = collection
+        .find(filter, pagination.find_options(sort))
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch events", e))?;
+
+    use futures::stream::StreamExt;
+    let mut events = Vec::new();
+
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(event) => events.push(event),
+            Err(e) => {
+                error!("Error reading event: {}", e);
+            }
         }
     }
+
+    Ok(HttpResponse::Ok().json(EventListResponse {
+        events,
+        pagination: pagination.meta(&req, total),
+    }))
 }
 
 /// Get a single event by ID
+#[utoipa::path(
+    get,
+    path = "/api/events/{event_id}",
+    tag = "events",
+    params(("event_id" = i32, Path, description = "Omnidex event ID")),
+    responses(
+        (status = 200, description = "The event", body = EventResponse),
+        (status = 404, description = "Event not found", body = Problem, content_type = "application/problem+json"),
+    )
+)]
 async fn get_event_by_id(
     db: web::Data<Database>,
     path: web::Path<i32>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let event_id = path.into_inner();
     let collection: Collection<Event> = db.collection("events");
 
-    match collection.find_one(doc! { "event_id": event_id }, None).await {
-        Ok(Some(event)) => HttpResponse::Ok().json(EventResponse { event }),
-        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Event not found"
-        })),
-        Err(e) => {
-            error!("Failed to fetch event {}: {}", event_id, e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to fetch event"
-            }))
-        }
+    match collection
+        .find_one(doc! { "event_id": event_id }, None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch event", e))?
+    {
+        Some(event) => Ok(HttpResponse::Ok().json(EventResponse { event })),
+        None => Err(ApiError::NotFound("Event not found".to_string())),
     }
 }
 
 /// Get standings for an event
+#[utoipa::path(
+    get,
+    path = "/api/events/{event_id}/standings",
+    tag = "events",
+    params(("event_id" = i32, Path, description = "Omnidex event ID"), StandingQuery),
+    responses(
+        (status = 200, description = "Final standings", body = StandingListResponse),
+    )
+)]
 async fn get_event_standings(
+    req: HttpRequest,
     db: web::Data<Database>,
     path: web::Path<i32>,
-) -> impl Responder {
+    query: web::Query<StandingQuery>,
+) -> Result<HttpResponse, ApiError> {
     let event_id = path.into_inner();
     let collection: Collection<Standing> = db.collection("standings");
 
-    use mongodb::options::FindOptions;
-    let find_options = FindOptions::builder()
-        .sort(doc! { "rank": 1 })
-        .build();
+    let filter = doc! { "event_id": event_id };
+    let pagination = Pagination::new(query.page, query.page_size)?;
+    let sort = parse_sort(query.sort.as_deref(), STANDING_SORT_FIELDS, doc! { "rank": 1 })?;
 
-    match collection
-        .find(doc! { "event_id": event_id }, find_options)
+    let total = collection
+        .count_documents(filter.clone(), None)
         .await
-    {
-        Ok(mut cursor) => {
-            use futures::stream::StreamExt;
-            let mut standings = Vec::new();
-
-            while let Some(result) = cursor.next().await {
-                match result {
-                    Ok(standing) => standings.push(standing),
-                    Err(e) => {
-                        error!("Error reading standing: {}", e);
-                    }
-                }
-            }
+        .map_err(|e| ApiError::internal("Failed to fetch standings", e))?;
 
-            let total = standings.len();
-            HttpResponse::Ok().json(StandingListResponse { standings, total })
-        }
-        Err(e) => {
-            error!("Failed to fetch standings for event {}: {}", event_id, e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to fetch standings"
-            }))
+    let mut cursor = collection
+        .find(filter, pagination.find_options(sort))
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch standings", e))?;
+
+    use futures::stream::StreamExt;
+    let mut standings = Vec::new();
+
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(standing) => standings.push(standing),
+            Err(e) => {
+                error!("Error reading standing for event {}: {}", event_id, e);
+            }
         }
     }
+
+    Ok(HttpResponse::Ok().json(StandingListResponse {
+        standings,
+        pagination: pagination.meta(&req, total),
+    }))
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(get_events, get_event_by_id, get_event_standings))]
+pub struct ApiDoc;
+
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/events")
@@ -0,0 +1,142 @@
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
+use actix_web::{web, HttpRequest, HttpResponse};
+use futures::stream::StreamExt;
+use mongodb::Database;
+use crate::error::{ApiError, Problem};
+use crate::services::export::{Dataset, ExportFormat, ExportScope};
+use crate::services::ExportService;
+use serde::Deserialize;
+use log::error;
+use utoipa::{IntoParams, OpenApi};
+use super::cards::{card_filter, CardSearchQuery};
+use super::filters::{EventFilterParams, EventFilters};
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ExportQuery {
+    /// Champion slug (standings and decklists)
+    champion: Option<String>,
+}
+
+/// Split an export path segment such as `decklists.csv` into dataset and
+/// file format. Without an extension the export is NDJSON.
+fn parse_target(target: &str) -> Result<(Dataset, ExportFormat), ApiError> {
+    let (name, extension) = target.split_once('.').unwrap_or((target, "ndjson"));
+
+    let dataset = Dataset::parse(name).ok_or_else(|| {
+        ApiError::invalid_parameter(
+            "dataset",
+            format!("unknown dataset '{}', expected one of events, standings, decklists, cards", name),
+        )
+    })?;
+    let format = ExportFormat::from_extension(extension).ok_or_else(|| {
+        ApiError::invalid_parameter(
+            "dataset",
+            format!("unknown export format '{}', expected ndjson, csv or parquet", extension),
+        )
+    })?;
+
+    if !dataset.supports(format) {
+        return Err(ApiError::invalid_parameter(
+            "dataset",
+            format!("{} cannot be exported as {}", name, extension),
+        ));
+    }
+
+    Ok((dataset, format))
+}
+
+/// Stream a whole dataset as NDJSON, CSV or Parquet
+#[utoipa::path(
+    get,
+    path = "/api/export/{dataset}",
+    tag = "export",
+    params(
+        ("dataset" = String, Path, description = "events, standings, decklists or cards, optionally suffixed with .ndjson, .csv or .parquet", example = "decklists.csv"),
+        EventFilterParams,
+        ExportQuery,
+    ),
+    responses(
+        (status = 200, description = "NDJSON or CSV with one record per line, or a Parquet file", content(
+            (String = "application/x-ndjson"),
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.apache.parquet"),
+        )),
+        (status = 400, description = "Unknown dataset or invalid filters", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn export_dataset(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    filters: EventFilters,
+    query: web::Query<ExportQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let filters = filters.supporting(&["minPlayers"])?;
+    let (dataset, format) = parse_target(&path)?;
+
+    let mut scope = ExportScope {
+        format: filters.format,
+        range: filters.range,
+        min_players: filters.min_players,
+        champion: query.into_inner().champion,
+        ..Default::default()
+    };
+
+    // Cards accept the same filters as the card search
+    if dataset == Dataset::Cards {
+        let card_query = web::Query::<CardSearchQuery>::from_query(req.query_string())
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        scope.card_filter = card_filter(&db, &card_query).await?;
+    }
+
+    let service = ExportService::new(db.get_ref().clone());
+    let chunks = service
+        .stream(dataset, &scope, format)
+        .await
+        .map_err(|e| ApiError::internal("Failed to export data", e))?;
+
+    // The status line is already sent, so a failure mid-stream can only abort the body
+    let body = chunks.map(move |chunk| {
+        chunk.map_err(|e| {
+            error!("Export of {} failed: {}", dataset.name(), e);
+            e
+        })
+    });
+
+    let file_name = format!("{}.{}", dataset.name(), format.extension());
+
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(file_name)],
+        })
+        .streaming(body))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(export_dataset))]
+pub struct ApiDoc;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/export")
+            .route("/{dataset}", web::get().to(export_dataset)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(parse_target("events").unwrap(), (Dataset::Events, ExportFormat::Ndjson));
+        assert_eq!(parse_target("decklists.csv").unwrap(), (Dataset::Decklists, ExportFormat::Csv));
+        assert!(parse_target("players").is_err());
+        assert!(parse_target("cards.xml").is_err());
+        assert_eq!(parse_target("standings.parquet").unwrap(), (Dataset::Standings, ExportFormat::Parquet));
+        assert!(parse_target("cards.parquet").is_err());
+    }
+}
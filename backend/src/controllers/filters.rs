@@ -0,0 +1,298 @@
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use mongodb::bson::Regex;
+use serde::Deserialize;
+use std::future::{ready, Ready};
+use utoipa::IntoParams;
+use crate::error::{ApiError, FieldError};
+use crate::models::{DateRange, EventFormat};
+
+/// Largest accepted `days` window (about ten years)
+pub const MAX_DAYS: i64 = 3650;
+
+/// Largest accepted `limit`
+pub const MAX_LIMIT: usize = 500;
+
+/// Escape a user-supplied string for literal use inside a regex
+pub fn escape_regex(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Case-insensitive regex matching the whole value
+pub fn exact_ci(value: &str) -> Regex {
+    Regex {
+        pattern: format!("^{}$", escape_regex(value)),
+        options: "i".to_string(),
+    }
+}
+
+/// Case-insensitive regex matching a substring
+pub fn contains_ci(value: &str) -> Regex {
+    Regex {
+        pattern: escape_regex(value),
+        options: "i".to_string(),
+    }
+}
+
+fn unknown_format(value: &str) -> String {
+    format!("unknown format '{}', expected one of standard, limited, sealed, draft", value)
+}
+
+/// Parse an optional `format` query value, rejecting unknown formats
+pub fn parse_format(value: Option<&str>) -> Result<Option<EventFormat>, ApiError> {
+    match value {
+        None => Ok(None),
+        Some(s) => EventFormat::parse(s)
+            .map(Some)
+            .ok_or_else(|| ApiError::invalid_parameter("format", unknown_format(s))),
+    }
+}
+
+/// Validate an optional positive integer query value such as `days`
+pub fn positive(param: &str, value: Option<i32>) -> Result<Option<i32>, ApiError> {
+    match value {
+        Some(v) if v <= 0 => Err(ApiError::invalid_parameter(param, "must be a positive number")),
+        _ => Ok(value),
+    }
+}
+
+/// Parse an integer within `min..=max`
+fn parse_bounded(value: &str, min: i64, max: i64) -> Result<i64, String> {
+    let parsed: i64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not a whole number", value))?;
+
+    if parsed < min || parsed > max {
+        return Err(format!("must be between {} and {}", min, max));
+    }
+    Ok(parsed)
+}
+
+/// Parse an RFC 3339 timestamp or a `YYYY-MM-DD` date. Bare dates cover the
+/// whole day, so as an upper bound they resolve to the end of that day.
+pub fn parse_date(value: &str, end_of_day: bool) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("'{}' is not a date (expected YYYY-MM-DD or RFC 3339)", value))?;
+
+    let time = if end_of_day {
+        NaiveTime::from_hms_milli_opt(23, 59, 59, 999)
+    } else {
+        NaiveTime::from_hms_opt(0, 0, 0)
+    };
+    Ok(date.and_time(time.unwrap_or_default()).and_utc())
+}
+
+/// Query parameters accepted by `EventFilters`
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EventFilterParams {
+    /// Event format: STANDARD, LIMITED, SEALED or DRAFT (case-insensitive)
+    #[param(example = "STANDARD")]
+    format: Option<String>,
+    /// Only events from the last N days; cannot be combined with `from`/`to`
+    #[param(value_type = Option<i64>, minimum = 1, maximum = 3650)]
+    days: Option<String>,
+    /// Earliest event start date, `YYYY-MM-DD` or RFC 3339
+    #[param(format = Date)]
+    from: Option<String>,
+    /// Latest event start date, inclusive; a bare date covers the whole day
+    #[param(format = Date)]
+    to: Option<String>,
+    /// Maximum number of results, where supported
+    #[param(value_type = Option<i64>, minimum = 1, maximum = 500)]
+    limit: Option<String>,
+    /// Minimum event player count, where supported
+    #[serde(rename = "minPlayers")]
+    #[param(value_type = Option<i32>, minimum = 1)]
+    min_players: Option<String>,
+}
+
+/// Validated event filters shared by list and statistics endpoints:
+/// `format`, `days` or `from`/`to`, `limit` and `minPlayers`.
+/// Every invalid parameter is reported in a single 400 response.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilters {
+    pub format: Option<EventFormat>,
+    pub range: DateRange,
+    pub limit: Option<usize>,
+    pub min_players: Option<i32>,
+}
+
+impl EventFilters {
+    /// Parse filters from a raw query string, resolving `days` relative to `now`
+    pub fn from_query(query: &str, now: DateTime<Utc>) -> Result<Self, ApiError> {
+        let raw = web::Query::<EventFilterParams>::from_query(query)
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?
+            .into_inner();
+
+        let mut errors = Vec::new();
+        let mut filters = EventFilters::default();
+
+        if let Some(value) = &raw.format {
+            match EventFormat::parse(value) {
+                Some(format) => filters.format = Some(format),
+                None => errors.push(FieldError::new("format", unknown_format(value))),
+            }
+        }
+
+        if let Some(value) = &raw.from {
+            match parse_date(value, false) {
+                Ok(from) => filters.range.from = Some(from),
+                Err(message) => errors.push(FieldError::new("from", message)),
+            }
+        }
+
+        if let Some(value) = &raw.to {
+            match parse_date(value, true) {
+                Ok(to) => filters.range.to = Some(to),
+                Err(message) => errors.push(FieldError::new("to", message)),
+            }
+        }
+
+        if let (Some(from), Some(to)) = (filters.range.from, filters.range.to) {
+            if from > to {
+                errors.push(FieldError::new("from", "must not be after 'to'"));
+            }
+        }
+
+        if let Some(value) = &raw.days {
+            if raw.from.is_some() || raw.to.is_some() {
+                errors.push(FieldError::new("days", "cannot be combined with 'from' or 'to'"));
+            } else {
+                match parse_bounded(value, 1, MAX_DAYS) {
+                    Ok(days) => filters.range.from = Some(now - Duration::days(days)),
+                    Err(message) => errors.push(FieldError::new("days", message)),
+                }
+            }
+        }
+
+        if let Some(value) = &raw.limit {
+            match parse_bounded(value, 1, MAX_LIMIT as i64) {
+                Ok(limit) => filters.limit = Some(limit as usize),
+                Err(message) => errors.push(FieldError::new("limit", message)),
+            }
+        }
+
+        if let Some(value) = &raw.min_players {
+            match parse_bounded(value, 1, i32::MAX as i64) {
+                Ok(min_players) => filters.min_players = Some(min_players as i32),
+                Err(message) => errors.push(FieldError::new("minPlayers", message)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(filters)
+        } else {
+            Err(ApiError::Validation(errors))
+        }
+    }
+
+    /// Reject `limit` and `minPlayers` unless listed in `supported`, so an
+    /// endpoint that does not apply them answers 400 instead of ignoring them
+    pub fn supporting(self, supported: &[&str]) -> Result<Self, ApiError> {
+        let given = [("limit", self.limit.is_some()), ("minPlayers", self.min_players.is_some())];
+        let errors: Vec<FieldError> = given
+            .into_iter()
+            .filter(|&(param, set)| set && !supported.contains(&param))
+            .map(|(param, _)| FieldError::new(param, "is not supported by this endpoint"))
+            .collect();
+
+        if errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(ApiError::Validation(errors))
+        }
+    }
+}
+
+impl FromRequest for EventFilters {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(Self::from_query(req.query_string(), Utc::now()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_regex() {
+        assert_eq!(escape_regex("Fire (Ember)"), "Fire \\(Ember\\)");
+        assert_eq!(exact_ci("a.b").pattern, "^a\\.b$");
+        assert_eq!(contains_ci("x+y").options, "i");
+    }
+
+    #[test]
+    fn test_query_validation() {
+        assert_eq!(parse_format(Some("Standard")).unwrap(), Some(EventFormat::Standard));
+        assert!(parse_format(Some("modern")).is_err());
+        assert!(positive("days", Some(-7)).is_err());
+        assert_eq!(positive("days", Some(30)).unwrap(), Some(30));
+    }
+
+    #[test]
+    fn test_event_filters() {
+        let now = Utc::now();
+
+        let filters = EventFilters::from_query("format=draft&days=30&limit=10&minPlayers=8", now).unwrap();
+        assert_eq!(filters.format, Some(EventFormat::Draft));
+        assert_eq!(filters.range.from, Some(now - Duration::days(30)));
+        assert_eq!(filters.limit, Some(10));
+        assert_eq!(filters.min_players, Some(8));
+
+        let filters = EventFilters::from_query("from=2024-01-01&to=2024-01-31", now).unwrap();
+        assert_eq!(filters.range.from.unwrap().to_rfc3339(), "2024-01-01T00:00:00+00:00");
+        assert_eq!(filters.range.to.unwrap().to_rfc3339(), "2024-01-31T23:59:59.999+00:00");
+    }
+
+    #[test]
+    fn test_event_filters_report_every_invalid_field() {
+        let err = EventFilters::from_query("format=modern&days=-3&limit=abc&minPlayers=0", Utc::now())
+            .unwrap_err();
+        let ApiError::Validation(errors) = err else {
+            panic!("expected validation error");
+        };
+        let params: Vec<_> = errors.iter().map(|e| e.param.as_str()).collect();
+        assert_eq!(params, ["format", "days", "limit", "minPlayers"]);
+
+        let err = EventFilters::from_query("from=2024-02-01&to=2024-01-01&days=7", Utc::now())
+            .unwrap_err();
+        let ApiError::Validation(errors) = err else {
+            panic!("expected validation error");
+        };
+        let params: Vec<_> = errors.iter().map(|e| e.param.as_str()).collect();
+        assert_eq!(params, ["from", "days"]);
+    }
+
+    #[test]
+    fn test_event_filters_reject_unsupported() {
+        let filters = EventFilters::from_query("limit=10&minPlayers=8", Utc::now()).unwrap();
+
+        let err = filters.clone().supporting(&["minPlayers"]).unwrap_err();
+        let ApiError::Validation(errors) = err else {
+            panic!("expected validation error");
+        };
+        let params: Vec<_> = errors.iter().map(|e| e.param.as_str()).collect();
+        assert_eq!(params, ["limit"]);
+
+        assert!(filters.supporting(&["limit", "minPlayers"]).is_ok());
+        assert!(EventFilters::default().supporting(&[]).is_ok());
+    }
+}
@@ -0,0 +1,62 @@
+use actix_web::{web, HttpResponse};
+use async_graphql::http::GraphiQLSource;
+use mongodb::Database;
+use utoipa::OpenApi;
+use crate::graphql::{self, AppSchema};
+
+/// Path of the GraphQL endpoint
+const GRAPHQL_PATH: &str = "/api/graphql";
+
+/// Execute a GraphQL query
+#[utoipa::path(
+    post,
+    path = "/api/graphql",
+    tag = "graphql",
+    request_body(content = Object, description = "GraphQL request with `query`, optional `variables` and `operationName`"),
+    responses(
+        (status = 200, description = "GraphQL response with `data` and/or `errors`", body = Object),
+    )
+)]
+async fn execute(
+    schema: web::Data<AppSchema>,
+    db: web::Data<Database>,
+    body: web::Json<async_graphql::Request>,
+) -> HttpResponse {
+    let request = body.into_inner().data(graphql::loader(db.get_ref().clone()));
+    HttpResponse::Ok().json(schema.execute(request).await)
+}
+
+/// Interactive GraphiQL explorer
+async fn graphiql() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(GraphiQLSource::build().endpoint(GRAPHQL_PATH).finish())
+}
+
+/// Schema in GraphQL SDL, for client code generation
+#[utoipa::path(
+    get,
+    path = "/api/graphql/schema",
+    tag = "graphql",
+    responses(
+        (status = 200, description = "GraphQL schema definition", body = String, content_type = "text/plain"),
+    )
+)]
+async fn schema_sdl(schema: web::Data<AppSchema>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(schema.sdl())
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(execute, schema_sdl))]
+pub struct ApiDoc;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/graphql")
+            .route("", web::post().to(execute))
+            .route("", web::get().to(graphiql))
+            .route("/schema", web::get().to(schema_sdl)),
+    );
+}
@@ -1,8 +1,9 @@
 use actix_web::{web, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
 use mongodb::Database;
+use utoipa::{OpenApi, ToSchema};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 struct HealthResponse {
     status: String,
     database: String,
@@ -10,6 +11,14 @@ struct HealthResponse {
 }
 
 /// Health check endpoint
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    tag = "health",
+    responses(
+        (status = 200, description = "Service status and database connectivity", body = HealthResponse),
+    )
+)]
 async fn health_check(db: web::Data<Database>) -> impl Responder {
     // Try to ping the database
     let db_status = match db.run_command(mongodb::bson::doc! { "ping": 1 }, None).await {
@@ -24,6 +33,10 @@ async fn health_check(db: web::Data<Database>) -> impl Responder {
     })
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(health_check))]
+pub struct ApiDoc;
+
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(web::resource("/health").route(web::get().to(health_check)));
 }
@@ -1,98 +1,140 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpResponse};
 use mongodb::Database;
+use crate::error::{ApiError, Problem};
+use crate::services::meta_analysis::{ChampionPerformance, MetaBreakdown, MetaGrouping, NewCardAdoption, NEW_CARD_WINDOW_DAYS};
 use crate::services::MetaAnalysisService;
-use crate::models::{EventFormat, CardPerformanceResponse};
-use serde::Deserialize;
-use log::error;
-
-#[derive(Deserialize)]
-struct MetaQuery {
-    format: Option<String>,
-    days: Option<i32>,
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use super::filters::{EventFilterParams, EventFilters};
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct BreakdownQuery {
+    /// Group rows by `champion` (default) or by champion `lineage`
+    #[serde(default)]
+    group_by: MetaGrouping,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct NewCardQuery {
+    /// Days after its first set's release during which a card counts as new (1-365, default 90)
+    window_days: Option<i64>,
 }
 
-#[derive(Deserialize)]
-struct CardPerformanceQuery {
-    format: Option<String>,
-    days: Option<i32>,
-    limit: Option<i64>,
+/// Response structure for meta breakdown queries
+#[derive(Serialize, ToSchema)]
+struct MetaBreakdownResponse {
+    breakdown: Vec<MetaBreakdown>,
+    total: usize,
+}
+
+/// Response structure for champion performance queries
+#[derive(Serialize, ToSchema)]
+struct ChampionPerformanceResponse {
+    champions: Vec<ChampionPerformance>,
+    total: usize,
 }
 
 /// Get meta breakdown statistics
+#[utoipa::path(
+    get,
+    path = "/api/meta/breakdown",
+    tag = "meta",
+    params(EventFilterParams, BreakdownQuery),
+    responses(
+        (status = 200, description = "Champion or lineage share of ranked events", body = MetaBreakdownResponse),
+        (status = 400, description = "Invalid query parameters", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
 async fn get_meta_breakdown(
     db: web::Data<Database>,
-    query: web::Query<MetaQuery>,
-) -> impl Responder {
+    filters: EventFilters,
+    query: web::Query<BreakdownQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let filters = filters.supporting(&[])?;
     let service = MetaAnalysisService::new(db.get_ref().clone());
 
-    let format = query.format.as_ref().map(|s| EventFormat::from_str(s));
-
-    match service.calculate_meta_breakdown(format, query.days).await {
-        Ok(breakdown) => HttpResponse::Ok().json(serde_json::json!({
-            "breakdown": breakdown,
-            "total": breakdown.len(),
-        })),
-        Err(e) => {
-            error!("Failed to calculate meta breakdown: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to calculate meta breakdown"
-            }))
-        }
-    }
+    let breakdown = service
+        .calculate_meta_breakdown(filters.format, filters.range, query.group_by)
+        .await
+        .map_err(|e| ApiError::internal("Failed to calculate meta breakdown", e))?;
+
+    let total = breakdown.len();
+    Ok(HttpResponse::Ok().json(MetaBreakdownResponse { breakdown, total }))
 }
 
 /// Get champion performance statistics
+#[utoipa::path(
+    get,
+    path = "/api/meta/champion-performance",
+    tag = "meta",
+    params(EventFilterParams),
+    responses(
+        (status = 200, description = "Champion results in ranked events", body = ChampionPerformanceResponse),
+        (status = 400, description = "Invalid query parameters", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
 async fn get_champion_performance(
     db: web::Data<Database>,
-    query: web::Query<MetaQuery>,
-) -> impl Responder {
+    filters: EventFilters,
+) -> Result<HttpResponse, ApiError> {
+    let filters = filters.supporting(&[])?;
     let service = MetaAnalysisService::new(db.get_ref().clone());
 
-    match service.calculate_champion_performance(query.days).await {
-        Ok(performance) => HttpResponse::Ok().json(serde_json::json!({
-            "champions": performance,
-            "total": performance.len(),
-        })),
-        Err(e) => {
-            error!("Failed to calculate champion performance: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to calculate champion performance"
-            }))
-        }
-    }
+    let performance = service
+        .calculate_champion_performance(filters.format, filters.range)
+        .await
+        .map_err(|e| ApiError::internal("Failed to calculate champion performance", e))?;
+
+    let total = performance.len();
+    Ok(HttpResponse::Ok().json(ChampionPerformanceResponse {
+        champions: performance,
+        total,
+    }))
 }
 
-/// Get card performance statistics
-async fn get_card_performance(
+/// Get how widely cards were played while new, based on set release dates
+#[utoipa::path(
+    get,
+    path = "/api/meta/new-cards",
+    tag = "meta",
+    params(EventFilterParams, NewCardQuery),
+    responses(
+        (status = 200, description = "New card adoption in ranked events", body = NewCardAdoption),
+        (status = 400, description = "Invalid query parameters", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn get_new_card_adoption(
     db: web::Data<Database>,
-    query: web::Query<CardPerformanceQuery>,
-) -> impl Responder {
+    filters: EventFilters,
+    query: web::Query<NewCardQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let filters = filters.supporting(&[])?;
+    let window_days = query.window_days.unwrap_or(NEW_CARD_WINDOW_DAYS);
+    if !(1..=365).contains(&window_days) {
+        return Err(ApiError::invalid_parameter("window_days", "must be between 1 and 365"));
+    }
+
     let service = MetaAnalysisService::new(db.get_ref().clone());
 
-    let format = query.format.as_ref().map(|s| EventFormat::from_str(s));
-
-    match service.calculate_card_performance(format, query.days, query.limit).await {
-        Ok(cards) => {
-            let total = cards.len();
-            HttpResponse::Ok().json(CardPerformanceResponse { cards, total })
-        }
-        Err(e) => {
-            error!("Failed to calculate card performance: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to calculate card performance"
-            }))
-        }
-    }
+    let adoption = service
+        .calculate_new_card_adoption(filters.format, filters.range, window_days)
+        .await
+        .map_err(|e| ApiError::internal("Failed to calculate new card adoption", e))?;
+
+    Ok(HttpResponse::Ok().json(adoption))
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(get_meta_breakdown, get_champion_performance, get_new_card_adoption))]
+pub struct ApiDoc;
+
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/meta")
             .route("/breakdown", web::get().to(get_meta_breakdown))
-            .route("/champion-performance", web::get().to(get_champion_performance)),
-    )
-    .service(
-        web::scope("/cards")
-            .route("/performance", web::get().to(get_card_performance)),
+            .route("/champion-performance", web::get().to(get_champion_performance))
+            .route("/new-cards", web::get().to(get_new_card_adoption)),
     );
 }
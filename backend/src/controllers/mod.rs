@@ -1,11 +1,50 @@
+pub mod admin;
+pub mod auth;
+pub mod cards;
 pub mod champions;
 pub mod events;
+pub mod export;
+pub mod graphql;
 pub mod decklists;
 pub mod meta;
+pub mod players;
+pub mod ratings;
+pub mod saved_decklists;
 pub mod health;
+pub mod filters;
+pub mod pagination;
 
+pub use admin::configure as configure_admin;
+pub use auth::configure as configure_auth;
+pub use cards::configure as configure_cards;
 pub use champions::configure as configure_champions;
 pub use events::configure as configure_events;
+pub use export::configure as configure_export;
+pub use graphql::configure as configure_graphql;
 pub use decklists::configure as configure_decklists;
 pub use meta::configure as configure_meta;
+pub use players::configure as configure_players;
+pub use ratings::configure as configure_ratings;
+pub use saved_decklists::configure as configure_saved_decklists;
 pub use health::configure as configure_health;
+
+use actix_web::web;
+use crate::error::ApiError;
+
+/// Report malformed query strings as problem+json 400s
+pub fn query_config() -> web::QueryConfig {
+    web::QueryConfig::default()
+        .error_handler(|err, _req| ApiError::BadRequest(err.to_string()).into())
+}
+
+/// Report malformed JSON bodies as problem+json 400s
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default()
+        .error_handler(|err, _req| ApiError::BadRequest(err.to_string()).into())
+}
+
+/// Report malformed path segments as problem+json 400s
+pub fn path_config() -> web::PathConfig {
+    web::PathConfig::default()
+        .error_handler(|err, _req| ApiError::BadRequest(err.to_string()).into())
+}
@@ -0,0 +1,211 @@
+use actix_web::HttpRequest;
+use mongodb::bson::{doc, Document};
+use mongodb::options::FindOptions;
+use crate::error::{ApiError, FieldError};
+use crate::models::PageMeta;
+
+/// Default number of items per page
+pub const DEFAULT_PAGE_SIZE: u64 = 50;
+
+/// Maximum number of items per page
+pub const MAX_PAGE_SIZE: u64 = 200;
+
+/// Resolved page/page_size pair for a list request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    pub page: u64,
+    pub page_size: u64,
+}
+
+impl Pagination {
+    /// Build pagination from optional query values. Out-of-range values are
+    /// rejected, as is a page beyond what MongoDB can skip to.
+    pub fn new(page: Option<u64>, page_size: Option<u64>) -> Result<Self, ApiError> {
+        let mut errors = Vec::new();
+        if page == Some(0) {
+            errors.push(FieldError::new("page", "must be at least 1"));
+        }
+        if page_size.is_some_and(|size| !(1..=MAX_PAGE_SIZE).contains(&size)) {
+            errors.push(FieldError::new("page_size", format!("must be between 1 and {}", MAX_PAGE_SIZE)));
+        }
+        if !errors.is_empty() {
+            return Err(ApiError::Validation(errors));
+        }
+
+        let pagination = Self {
+            page: page.unwrap_or(1),
+            page_size: page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+        };
+
+        match (pagination.page - 1).checked_mul(pagination.page_size) {
+            Some(skip) if skip <= i64::MAX as u64 => Ok(pagination),
+            _ => Err(ApiError::invalid_parameter("page", "is too large")),
+        }
+    }
+
+    /// Number of documents to skip
+    pub fn skip(&self) -> u64 {
+        (self.page - 1) * self.page_size
+    }
+
+    /// Find options applying this page and the given sort. `_id` is appended
+    /// as a tiebreaker so pages are stable when sort values repeat.
+    pub fn find_options(&self, mut sort: Document) -> FindOptions {
+        if !sort.contains_key("_id") {
+            sort.insert("_id", 1);
+        }
+
+        FindOptions::builder()
+            .sort(sort)
+            .skip(self.skip())
+            .limit(self.page_size as i64)
+            .build()
+    }
+
+    /// Build page metadata, including next/prev links relative to the request
+    pub fn meta(&self, req: &HttpRequest, total: u64) -> PageMeta {
+        let next = if self.skip() + self.page_size < total {
+            Some(page_link(req, self.page + 1))
+        } else {
+            None
+        };
+
+        let prev = if self.page > 1 {
+            Some(page_link(req, self.page - 1))
+        } else {
+            None
+        };
+
+        PageMeta {
+            total,
+            page: self.page,
+            page_size: self.page_size,
+            next,
+            prev,
+        }
+    }
+}
+
+/// Parse a `sort` parameter such as `-start_date,name` into a MongoDB sort document.
+/// Fields not in `allowed` are rejected; `default` is used when no field is given.
+pub fn parse_sort(sort: Option<&str>, allowed: &[&str], default: Document) -> Result<Document, ApiError> {
+    let mut sort_doc = doc! {};
+    let mut errors = Vec::new();
+
+    for field in sort.unwrap_or("").split(',').map(str::trim).filter(|f| !f.is_empty()) {
+        let (name, direction) = match field.strip_prefix('-') {
+            Some(name) => (name, -1),
+            None => (field.strip_prefix('+').unwrap_or(field), 1),
+        };
+
+        if allowed.contains(&name) {
+            sort_doc.insert(name, direction);
+        } else {
+            errors.push(FieldError::new(
+                "sort",
+                format!("unknown field '{}', expected one of {}", name, allowed.join(", ")),
+            ));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(ApiError::Validation(errors));
+    }
+
+    Ok(if sort_doc.is_empty() { default } else { sort_doc })
+}
+
+/// Rebuild the request URL with the `page` parameter replaced
+fn page_link(req: &HttpRequest, page: u64) -> String {
+    let mut params: Vec<&str> = req
+        .query_string()
+        .split('&')
+        .filter(|p| !p.is_empty() && !p.starts_with("page="))
+        .collect();
+
+    let page_param = format!("page={}", page);
+    params.push(&page_param);
+
+    format!("{}?{}", req.path(), params.join("&"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_pagination_rejects_out_of_range_values() {
+        let err = Pagination::new(Some(0), Some(10_000)).unwrap_err();
+        let ApiError::Validation(errors) = err else {
+            panic!("expected validation error");
+        };
+        let params: Vec<_> = errors.iter().map(|e| e.param.as_str()).collect();
+        assert_eq!(params, ["page", "page_size"]);
+        assert!(Pagination::new(None, Some(0)).is_err());
+
+        let pagination = Pagination::new(None, None).unwrap();
+        assert_eq!(pagination.page, 1);
+        assert_eq!(pagination.page_size, DEFAULT_PAGE_SIZE);
+        assert_eq!(pagination.skip(), 0);
+
+        let pagination = Pagination::new(Some(3), Some(MAX_PAGE_SIZE)).unwrap();
+        assert_eq!(pagination.skip(), 2 * MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_pagination_rejects_overflowing_page() {
+        assert!(Pagination::new(Some(u64::MAX), None).is_err());
+        assert!(Pagination::new(Some(u64::MAX / 2), Some(10)).is_err());
+
+        let last = Pagination::new(Some(i64::MAX as u64 / 10 + 1), Some(10)).unwrap();
+        let meta = last.meta(&TestRequest::get().uri("/api/events").to_http_request(), 10);
+        assert!(meta.next.is_none());
+    }
+
+    #[test]
+    fn test_find_options_add_id_tiebreaker() {
+        let pagination = Pagination::new(None, None).unwrap();
+
+        let options = pagination.find_options(doc! { "rank": 1 });
+        assert_eq!(options.sort, Some(doc! { "rank": 1, "_id": 1 }));
+
+        let options = pagination.find_options(doc! { "_id": -1 });
+        assert_eq!(options.sort, Some(doc! { "_id": -1 }));
+    }
+
+    #[test]
+    fn test_parse_sort() {
+        let sort = parse_sort(Some("-player_count, +name"), &["player_count", "name"], doc! { "x": 1 }).unwrap();
+        assert_eq!(sort, doc! { "player_count": -1, "name": 1 });
+
+        let sort = parse_sort(None, &["name"], doc! { "start_date": -1 }).unwrap();
+        assert_eq!(sort, doc! { "start_date": -1 });
+    }
+
+    #[test]
+    fn test_parse_sort_rejects_unknown_fields() {
+        let err = parse_sort(Some("name,-bogus,nmae"), &["name"], doc! {}).unwrap_err();
+        let ApiError::Validation(errors) = err else {
+            panic!("expected validation error");
+        };
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| e.param == "sort"));
+        assert!(errors[0].message.contains("'bogus'"));
+    }
+
+    #[test]
+    fn test_page_links() {
+        let req = TestRequest::get()
+            .uri("/api/events?format=STANDARD&page=2&page_size=10")
+            .to_http_request();
+        let meta = Pagination::new(Some(2), Some(10)).unwrap().meta(&req, 35);
+
+        assert_eq!(meta.total, 35);
+        assert_eq!(meta.next.as_deref(), Some("/api/events?format=STANDARD&page_size=10&page=3"));
+        assert_eq!(meta.prev.as_deref(), Some("/api/events?format=STANDARD&page_size=10&page=1"));
+
+        let last = Pagination::new(Some(4), Some(10)).unwrap().meta(&req, 35);
+        assert!(last.next.is_none());
+    }
+}
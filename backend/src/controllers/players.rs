@@ -0,0 +1,94 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use mongodb::Database;
+use crate::error::{ApiError, Problem};
+use crate::models::{PlayerListResponse, PlayerResponse};
+use crate::services::PlayerStatsService;
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
+use super::filters::contains_ci;
+use super::pagination::Pagination;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct PlayerSearchQuery {
+    /// Player name substring (case-insensitive)
+    search: Option<String>,
+    /// Page number (1-based)
+    page: Option<u64>,
+    /// Items per page (default 50, max 200)
+    page_size: Option<u64>,
+}
+
+/// Search players by name
+#[utoipa::path(
+    get,
+    path = "/api/players",
+    tag = "players",
+    params(PlayerSearchQuery),
+    responses(
+        (status = 200, description = "Matching players", body = PlayerListResponse),
+    ),
+)]
+async fn search_players(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    query: web::Query<PlayerSearchQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let service = PlayerStatsService::new(db.get_ref().clone());
+    let pagination = Pagination::new(query.page, query.page_size)?;
+
+    let name = query
+        .search
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| contains_ci(s.trim()));
+
+    let (players, total) = service
+        .search_players(name, pagination.skip(), pagination.page_size)
+        .await
+        .map_err(|e| ApiError::internal("Failed to search players", e))?;
+
+    Ok(HttpResponse::Ok().json(PlayerListResponse {
+        players,
+        pagination: pagination.meta(&req, total),
+    }))
+}
+
+/// Get a player's profile and tournament history
+#[utoipa::path(
+    get,
+    path = "/api/players/{player_id}",
+    tag = "players",
+    params(("player_id" = String, Path, description = "Omnidex player ID")),
+    responses(
+        (status = 200, description = "Player profile", body = PlayerResponse),
+        (status = 404, description = "Player not found", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn get_player(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let player_id = path.into_inner();
+    let service = PlayerStatsService::new(db.get_ref().clone());
+
+    let player = service
+        .get_profile(&player_id)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch player", e))?
+        .ok_or_else(|| ApiError::NotFound("Player not found".to_string()))?;
+
+    Ok(HttpResponse::Ok().json(PlayerResponse { player }))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(search_players, get_player))]
+pub struct ApiDoc;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/players")
+            .route("", web::get().to(search_players))
+            .route("/{player_id}", web::get().to(get_player)),
+    );
+}
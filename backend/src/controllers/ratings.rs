@@ -0,0 +1,155 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use mongodb::{Database, Collection};
+use mongodb::bson::doc;
+use mongodb::options::FindOptions;
+use crate::error::{ApiError, Problem};
+use crate::models::{
+    rating_pool, LeaderboardResponse, PlayerRating, PlayerRatingResponse, RatingHistoryEntry,
+};
+use serde::Deserialize;
+use log::error;
+use utoipa::{IntoParams, OpenApi};
+use super::filters::{parse_format, positive};
+use super::pagination::Pagination;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct LeaderboardQuery {
+    /// Format pool; defaults to all formats
+    format: Option<String>,
+    /// Minimum number of rated events
+    #[serde(rename = "minEvents")]
+    #[param(minimum = 1)]
+    min_events: Option<i32>,
+    /// Page number (1-based)
+    page: Option<u64>,
+    /// Items per page (default 50, max 200)
+    page_size: Option<u64>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct PlayerRatingQuery {
+    /// Format pool; defaults to all formats
+    format: Option<String>,
+}
+
+/// Get the rating leaderboard, optionally for a single format
+#[utoipa::path(
+    get,
+    path = "/api/ratings/leaderboard",
+    tag = "ratings",
+    params(LeaderboardQuery),
+    responses(
+        (status = 200, description = "Players ordered by rating", body = LeaderboardResponse),
+        (status = 400, description = "Invalid query parameters", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn get_leaderboard(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    query: web::Query<LeaderboardQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let collection: Collection<PlayerRating> = db.collection("player_ratings");
+
+    let format = parse_format(query.format.as_deref())?;
+    let mut filter = doc! { "pool": rating_pool(format.as_ref()) };
+    if let Some(min_events) = positive("minEvents", query.min_events)? {
+        filter.insert("events_played", doc! { "$gte": min_events });
+    }
+
+    let pagination = Pagination::new(query.page, query.page_size)?;
+
+    let total = collection
+        .count_documents(filter.clone(), None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch leaderboard", e))?;
+
+    let mut cursor = collection
+        .find(filter, pagination.find_options(doc! { "rating": -1, "player_id": 1 }))
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch leaderboard", e))?;
+
+    use futures::stream::StreamExt;
+    let mut ratings = Vec::new();
+
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(rating) => ratings.push(rating),
+            Err(e) => {
+                error!("Error reading rating: {}", e);
+            }
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(LeaderboardResponse {
+        ratings,
+        pagination: pagination.meta(&req, total),
+    }))
+}
+
+/// Get a player's current rating and rating history
+#[utoipa::path(
+    get,
+    path = "/api/ratings/players/{player_id}",
+    tag = "ratings",
+    params(("player_id" = String, Path, description = "Omnidex player ID"), PlayerRatingQuery),
+    responses(
+        (status = 200, description = "Rating and history", body = PlayerRatingResponse),
+        (status = 400, description = "Invalid query parameters", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Rating not found", body = Problem, content_type = "application/problem+json"),
+    ),
+)]
+async fn get_player_rating(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    query: web::Query<PlayerRatingQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let player_id = path.into_inner();
+    let ratings_collection: Collection<PlayerRating> = db.collection("player_ratings");
+    let history_collection: Collection<RatingHistoryEntry> = db.collection("rating_history");
+
+    let format = parse_format(query.format.as_deref())?;
+    let filter = doc! { "player_id": &player_id, "pool": rating_pool(format.as_ref()) };
+
+    let rating = ratings_collection
+        .find_one(filter.clone(), None)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch rating", e))?
+        .ok_or_else(|| ApiError::NotFound("Rating not found".to_string()))?;
+
+    let find_options = FindOptions::builder()
+        .sort(doc! { "event_date": 1, "event_id": 1 })
+        .build();
+
+    let mut cursor = history_collection
+        .find(filter, find_options)
+        .await
+        .map_err(|e| ApiError::internal("Failed to fetch rating", e))?;
+
+    use futures::stream::StreamExt;
+    let mut history = Vec::new();
+
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(entry) => history.push(entry),
+            Err(e) => {
+                error!("Error reading rating history: {}", e);
+            }
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(PlayerRatingResponse { rating, history }))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(get_leaderboard, get_player_rating))]
+pub struct ApiDoc;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/ratings")
+            .route("/leaderboard", web::get().to(get_leaderboard))
+            .route("/players/{player_id}", web::get().to(get_player_rating)),
+    );
+}
@@ -0,0 +1,263 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use mongodb::Database;
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use crate::error::{ApiError, Problem};
+use crate::models::{SavedDecklistInput, SavedDecklistListResponse, SavedDecklistResponse};
+use crate::services::auth::Principal;
+use crate::services::saved_decklists::SavedDecklistService;
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use super::pagination::{parse_sort, Pagination};
+
+/// Fields that saved decklist lists may be sorted by
+const SAVED_DECKLIST_SORT_FIELDS: &[&str] = &["name", "champion_slug", "created_at", "updated_at"];
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct SavedDecklistQuery {
+    /// Champion slug
+    champion: Option<String>,
+    /// Only decklists with this tag
+    tag: Option<String>,
+    /// Page number (1-based)
+    page: Option<u64>,
+    /// Items per page (default 50, max 200)
+    page_size: Option<u64>,
+    /// Sort field: name, champion_slug, created_at or updated_at; prefix with `-` for descending
+    sort: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct ForkRequest {
+    event_id: i32,
+    player_id: String,
+    name: Option<String>,
+}
+
+/// Saved decklists belong to user accounts, so API key principals are rejected
+fn owner(principal: &Principal) -> Result<ObjectId, ApiError> {
+    principal
+        .user_id()
+        .ok_or_else(|| ApiError::Forbidden("Saved decklists require a user account".to_string()))
+}
+
+fn parse_id(id: &str) -> Result<ObjectId, ApiError> {
+    ObjectId::parse_str(id).map_err(|_| ApiError::invalid_parameter("id", "not a valid decklist ID"))
+}
+
+/// List the caller's saved decklists
+#[utoipa::path(
+    get,
+    path = "/api/saved-decklists",
+    tag = "saved-decklists",
+    params(SavedDecklistQuery),
+    responses(
+        (status = 200, description = "Saved decklists", body = SavedDecklistListResponse),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Not a user account", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn list_saved_decklists(
+    req: HttpRequest,
+    db: web::Data<Database>,
+    principal: Principal,
+    query: web::Query<SavedDecklistQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = owner(&principal)?;
+
+    let pagination = Pagination::new(query.page, query.page_size)?;
+    let mut filter = doc! {};
+
+    if let Some(champion) = &query.champion {
+        filter.insert("champion_slug", champion.to_lowercase());
+    }
+
+    if let Some(tag) = &query.tag {
+        filter.insert("tags", tag.to_lowercase());
+    }
+
+    let sort = parse_sort(
+        query.sort.as_deref(),
+        SAVED_DECKLIST_SORT_FIELDS,
+        doc! { "updated_at": -1 },
+    )?;
+
+    let service = SavedDecklistService::new(db.get_ref().clone());
+
+    let (decklists, total) = service
+        .list(user_id, filter, pagination.find_options(sort))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(SavedDecklistListResponse {
+        decklists,
+        pagination: pagination.meta(&req, total),
+    }))
+}
+
+/// Get one of the caller's saved decklists
+#[utoipa::path(
+    get,
+    path = "/api/saved-decklists/{id}",
+    tag = "saved-decklists",
+    params(("id" = String, Path, description = "Saved decklist ID")),
+    responses(
+        (status = 200, description = "The saved decklist", body = SavedDecklistResponse),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Saved decklist not found", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn get_saved_decklist(
+    db: web::Data<Database>,
+    principal: Principal,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = owner(&principal)?;
+    let id = parse_id(&path)?;
+
+    let service = SavedDecklistService::new(db.get_ref().clone());
+    let decklist = service.get(user_id, id).await?;
+
+    Ok(HttpResponse::Ok().json(SavedDecklistResponse { decklist }))
+}
+
+/// Save a new decklist
+#[utoipa::path(
+    post,
+    path = "/api/saved-decklists",
+    tag = "saved-decklists",
+    request_body = SavedDecklistInput,
+    responses(
+        (status = 201, description = "Decklist saved", body = SavedDecklistResponse),
+        (status = 400, description = "Invalid decklist", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Identical decklist already saved", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn create_saved_decklist(
+    db: web::Data<Database>,
+    principal: Principal,
+    body: web::Json<SavedDecklistInput>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = owner(&principal)?;
+
+    let service = SavedDecklistService::new(db.get_ref().clone());
+    let decklist = service.create(user_id, body.into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(SavedDecklistResponse { decklist }))
+}
+
+/// Replace one of the caller's saved decklists
+#[utoipa::path(
+    put,
+    path = "/api/saved-decklists/{id}",
+    tag = "saved-decklists",
+    params(("id" = String, Path, description = "Saved decklist ID")),
+    request_body = SavedDecklistInput,
+    responses(
+        (status = 200, description = "Decklist updated", body = SavedDecklistResponse),
+        (status = 400, description = "Invalid decklist", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Saved decklist not found", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Identical decklist already saved", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn update_saved_decklist(
+    db: web::Data<Database>,
+    principal: Principal,
+    path: web::Path<String>,
+    body: web::Json<SavedDecklistInput>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = owner(&principal)?;
+    let id = parse_id(&path)?;
+
+    let service = SavedDecklistService::new(db.get_ref().clone());
+    let decklist = service.update(user_id, id, body.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(SavedDecklistResponse { decklist }))
+}
+
+/// Delete one of the caller's saved decklists
+#[utoipa::path(
+    delete,
+    path = "/api/saved-decklists/{id}",
+    tag = "saved-decklists",
+    params(("id" = String, Path, description = "Saved decklist ID")),
+    responses(
+        (status = 204, description = "Decklist deleted"),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Saved decklist not found", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn delete_saved_decklist(
+    db: web::Data<Database>,
+    principal: Principal,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = owner(&principal)?;
+    let id = parse_id(&path)?;
+
+    let service = SavedDecklistService::new(db.get_ref().clone());
+    service.delete(user_id, id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Fork a tournament decklist into the caller's saved decklists
+#[utoipa::path(
+    post,
+    path = "/api/saved-decklists/fork",
+    tag = "saved-decklists",
+    request_body = ForkRequest,
+    responses(
+        (status = 201, description = "Decklist forked", body = SavedDecklistResponse),
+        (status = 401, description = "Not authenticated", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "Tournament decklist not found", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Identical decklist already saved", body = Problem, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []), ("api_key" = [])),
+)]
+async fn fork_decklist(
+    db: web::Data<Database>,
+    principal: Principal,
+    body: web::Json<ForkRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = owner(&principal)?;
+
+    let request = body.into_inner();
+    let service = SavedDecklistService::new(db.get_ref().clone());
+
+    let decklist = service
+        .fork(user_id, request.event_id, &request.player_id, request.name)
+        .await?;
+
+    Ok(HttpResponse::Created().json(SavedDecklistResponse { decklist }))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_saved_decklists,
+    create_saved_decklist,
+    fork_decklist,
+    get_saved_decklist,
+    update_saved_decklist,
+    delete_saved_decklist,
+))]
+pub struct ApiDoc;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/saved-decklists")
+            .route("", web::get().to(list_saved_decklists))
+            .route("", web::post().to(create_saved_decklist))
+            .route("/fork", web::post().to(fork_decklist))
+            .route("/{id}", web::get().to(get_saved_decklist))
+            .route("/{id}", web::put().to(update_saved_decklist))
+            .route("/{id}", web::delete().to(delete_saved_decklist)),
+    );
+}
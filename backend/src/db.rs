@@ -0,0 +1,16 @@
+use crate::config::Config;
+use log::info;
+use mongodb::options::ClientOptions;
+use mongodb::{Client, Database};
+
+/// Connect to MongoDB and verify the connection with a ping
+pub async fn connect(config: &Config) -> Result<Database, mongodb::error::Error> {
+    let client_options = ClientOptions::parse(&config.mongodb_uri).await?;
+    let client = Client::with_options(client_options)?;
+    let database = client.database(&config.mongodb_database);
+
+    database.run_command(mongodb::bson::doc! { "ping": 1 }, None).await?;
+    info!("Successfully connected to MongoDB");
+
+    Ok(database)
+}
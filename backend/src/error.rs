@@ -0,0 +1,303 @@
+use actix_web::{
+    http::{header, StatusCode},
+    HttpResponse, ResponseError,
+};
+use crate::models::ModelError;
+use crate::rate_limit::RateLimitDecision;
+use crate::services::auth::AuthError;
+use crate::services::saved_decklists::SavedDecklistError;
+use log::error;
+use serde::Serialize;
+use std::fmt::Display;
+use thiserror::Error;
+use utoipa::ToSchema;
+
+/// Media type of RFC 7807 problem details
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Per-request context available while a request is being handled
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub request_id: String,
+    pub path: String,
+}
+
+tokio::task_local! {
+    /// Context of the request being handled on the current task
+    pub static REQUEST_CONTEXT: RequestContext;
+}
+
+/// Application error returned by handlers and middleware.
+/// Rendered as RFC 7807 problem+json with a stable error code.
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("Invalid value for '{param}': {message}")]
+    InvalidParameter { param: String, message: String },
+
+    #[error("Invalid query parameters: {}", field_names(.0))]
+    Validation(Vec<FieldError>),
+
+    #[error("{0}")]
+    BadRequest(String),
+
+    #[error("{0}")]
+    Unauthorized(String),
+
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{message}")]
+    Conflict {
+        message: String,
+        details: Option<serde_json::Value>,
+    },
+
+    #[error("Rate limit exceeded")]
+    RateLimited(RateLimitDecision),
+
+    /// Server-side failure. The message is shown to clients, so it should not
+    /// contain internal details; those are logged by `ApiError::internal`.
+    #[error("{0}")]
+    Internal(String),
+}
+
+/// A single invalid request field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FieldError {
+    pub param: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(param: &str, message: impl Into<String>) -> Self {
+        Self {
+            param: param.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+fn field_names(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|e| e.param.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// RFC 7807 problem details body
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    /// Stable machine-readable error code
+    #[schema(value_type = String, example = "invalid_parameter")]
+    pub code: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<serde_json::Value>,
+}
+
+impl ApiError {
+    pub fn invalid_parameter(param: &str, message: impl Into<String>) -> Self {
+        ApiError::InvalidParameter {
+            param: param.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Log `cause` and return an internal error exposing only `message`
+    pub fn internal(message: &str, cause: impl Display) -> Self {
+        error!("{}: {}", message, cause);
+        ApiError::Internal(message.to_string())
+    }
+
+    /// Stable machine-readable error code
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::InvalidParameter { .. } => "invalid_parameter",
+            ApiError::Validation(_) => "validation_failed",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Conflict { .. } => "conflict",
+            ApiError::RateLimited(_) => "rate_limited",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+
+    fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            ApiError::InvalidParameter { param, .. } => Some(serde_json::json!({ "param": param })),
+            ApiError::Validation(errors) => Some(serde_json::json!({ "errors": errors })),
+            ApiError::Conflict { details, .. } => details.clone(),
+            ApiError::RateLimited(decision) => Some(serde_json::json!({
+                "retry_after": decision.retry_after_secs
+            })),
+            _ => None,
+        }
+    }
+
+    /// Problem details for this error in the current request context
+    pub fn problem(&self) -> Problem {
+        let status = self.status_code();
+        let context = REQUEST_CONTEXT.try_with(|context| context.clone()).ok();
+
+        Problem {
+            problem_type: "about:blank".to_string(),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: self.to_string(),
+            code: self.code(),
+            instance: context.as_ref().map(|c| c.path.clone()),
+            request_id: context.map(|c| c.request_id),
+            details: self.details(),
+        }
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::InvalidParameter { .. } | ApiError::Validation(_) | ApiError::BadRequest(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict { .. } => StatusCode::CONFLICT,
+            ApiError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut builder = HttpResponse::build(self.status_code());
+        builder.content_type(PROBLEM_JSON);
+
+        match self {
+            ApiError::Unauthorized(_) => {
+                builder.insert_header((header::WWW_AUTHENTICATE, "Bearer"));
+            }
+            ApiError::RateLimited(decision) => {
+                builder
+                    .insert_header((header::RETRY_AFTER, decision.retry_after_secs))
+                    .insert_header(("X-RateLimit-Limit", decision.limit))
+                    .insert_header(("X-RateLimit-Remaining", decision.remaining))
+                    .insert_header(("X-RateLimit-Reset", decision.reset_secs));
+            }
+            _ => {}
+        }
+
+        builder.body(serde_json::to_string(&self.problem()).unwrap_or_default())
+    }
+}
+
+impl From<ModelError> for ApiError {
+    fn from(err: ModelError) -> Self {
+        match err {
+            ModelError::NotFound(message) => ApiError::NotFound(message),
+            ModelError::InvalidData(message) => ApiError::BadRequest(message),
+            ModelError::Database(_) | ModelError::Serialization(_) => {
+                ApiError::internal("Database operation failed", err)
+            }
+        }
+    }
+}
+
+impl From<mongodb::error::Error> for ApiError {
+    fn from(err: mongodb::error::Error) -> Self {
+        ModelError::from(err).into()
+    }
+}
+
+impl From<AuthError> for ApiError {
+    fn from(err: AuthError) -> Self {
+        match err {
+            AuthError::InvalidCredentials | AuthError::InvalidToken | AuthError::InvalidApiKey => {
+                ApiError::Unauthorized(err.to_string())
+            }
+            AuthError::Conflict(_) => ApiError::Conflict {
+                message: err.to_string(),
+                details: None,
+            },
+            AuthError::InvalidInput(message) => ApiError::BadRequest(message),
+            AuthError::NotFound(_) => ApiError::NotFound(err.to_string()),
+            AuthError::Forbidden(message) => ApiError::Forbidden(message),
+            _ => ApiError::internal("Authentication failure", err),
+        }
+    }
+}
+
+impl From<SavedDecklistError> for ApiError {
+    fn from(err: SavedDecklistError) -> Self {
+        match err {
+            SavedDecklistError::NotFound | SavedDecklistError::SourceNotFound => {
+                ApiError::NotFound(err.to_string())
+            }
+            SavedDecklistError::Duplicate(existing_id) => ApiError::Conflict {
+                message: err.to_string(),
+                details: Some(serde_json::json!({ "existing_id": existing_id.to_hex() })),
+            },
+            SavedDecklistError::InvalidInput(message) => ApiError::BadRequest(message),
+            _ => ApiError::internal("Failed to process saved decklist", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problem_includes_code_and_request_context() {
+        let context = RequestContext {
+            request_id: "abc123".to_string(),
+            path: "/api/events".to_string(),
+        };
+
+        let problem = REQUEST_CONTEXT.sync_scope(context, || {
+            ApiError::invalid_parameter("days", "must be a positive number").problem()
+        });
+
+        assert_eq!(problem.status, 400);
+        assert_eq!(problem.code, "invalid_parameter");
+        assert_eq!(problem.request_id.as_deref(), Some("abc123"));
+        assert_eq!(problem.instance.as_deref(), Some("/api/events"));
+        assert_eq!(problem.detail, "Invalid value for 'days': must be a positive number");
+    }
+
+    #[test]
+    fn test_validation_lists_every_field() {
+        let err = ApiError::Validation(vec![
+            FieldError::new("days", "must be a positive number"),
+            FieldError::new("format", "unknown format 'modern'"),
+        ]);
+        let problem = err.problem();
+
+        assert_eq!(problem.status, 400);
+        assert_eq!(problem.code, "validation_failed");
+        assert_eq!(problem.detail, "Invalid query parameters: days, format");
+        assert_eq!(problem.details.unwrap()["errors"][1]["param"], "format");
+    }
+
+    #[test]
+    fn test_model_errors_map_to_status() {
+        let not_found: ApiError = ModelError::NotFound("Event".to_string()).into();
+        assert_eq!(not_found.status_code(), StatusCode::NOT_FOUND);
+
+        let invalid: ApiError = ModelError::InvalidData("bad".to_string()).into();
+        assert_eq!(invalid.status_code(), StatusCode::BAD_REQUEST);
+    }
+}
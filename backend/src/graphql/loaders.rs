@@ -0,0 +1,198 @@
+use async_graphql::dataloader::Loader;
+use crate::models::{Card, Champion, Decklist, Event, Standing};
+use crate::services::meta_analysis::ChampionPerformance;
+use crate::services::MetaAnalysisService;
+use futures::stream::StreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::Database;
+use serde::de::DeserializeOwned;
+use std::collections::{HashMap, HashSet};
+use super::{internal, EventFilter};
+
+/// Event by Omnidex event ID
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EventId(pub i32);
+
+/// All standings of an event
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StandingsOf(pub i32);
+
+/// A player's decklist for an event
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DecklistKey {
+    pub event_id: i32,
+    pub player_id: String,
+}
+
+/// Card by slug
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CardSlug(pub String);
+
+/// Champion by slug
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChampionSlug(pub String);
+
+/// A champion's performance under an event filter
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PerformanceKey {
+    pub champion: String,
+    pub filter: EventFilter,
+}
+
+/// Batches GraphQL lookups into one MongoDB query per collection and key type
+pub struct MongoLoader {
+    database: Database,
+}
+
+impl MongoLoader {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// Fetch all documents of `collection` matching `filter`
+    async fn find_all<T>(&self, collection: &str, filter: Document) -> async_graphql::Result<Vec<T>>
+    where
+        T: DeserializeOwned + Unpin + Send + Sync,
+    {
+        let mut cursor = self
+            .database
+            .collection::<T>(collection)
+            .find(filter, None)
+            .await
+            .map_err(|e| internal("Failed to load data", e))?;
+
+        let mut documents = Vec::new();
+        while let Some(result) = cursor.next().await {
+            documents.push(result.map_err(|e| internal("Failed to load data", e))?);
+        }
+
+        Ok(documents)
+    }
+}
+
+impl Loader<EventId> for MongoLoader {
+    type Value = Event;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[EventId]) -> Result<HashMap<EventId, Event>, Self::Error> {
+        let ids: Vec<i32> = keys.iter().map(|k| k.0).collect();
+        let events: Vec<Event> = self.find_all("events", doc! { "event_id": { "$in": ids } }).await?;
+
+        Ok(events.into_iter().map(|e| (EventId(e.event_id), e)).collect())
+    }
+}
+
+impl Loader<StandingsOf> for MongoLoader {
+    type Value = Vec<Standing>;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[StandingsOf]) -> Result<HashMap<StandingsOf, Vec<Standing>>, Self::Error> {
+        let ids: Vec<i32> = keys.iter().map(|k| k.0).collect();
+        let standings: Vec<Standing> = self
+            .find_all("standings", doc! { "event_id": { "$in": ids } })
+            .await?;
+
+        let mut by_event: HashMap<StandingsOf, Vec<Standing>> = HashMap::new();
+        for standing in standings {
+            by_event.entry(StandingsOf(standing.event_id)).or_default().push(standing);
+        }
+        for standings in by_event.values_mut() {
+            standings.sort_by_key(|s| s.rank);
+        }
+
+        Ok(by_event)
+    }
+}
+
+impl Loader<DecklistKey> for MongoLoader {
+    type Value = Decklist;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[DecklistKey]) -> Result<HashMap<DecklistKey, Decklist>, Self::Error> {
+        let event_ids: Vec<i32> = keys.iter().map(|k| k.event_id).collect();
+        let player_ids: Vec<&str> = keys.iter().map(|k| k.player_id.as_str()).collect();
+
+        // Fetch the cross product of events and players, then keep the requested pairs
+        let decklists: Vec<Decklist> = self
+            .find_all(
+                "decklists",
+                doc! { "event_id": { "$in": event_ids }, "player_id": { "$in": player_ids } },
+            )
+            .await?;
+
+        Ok(decklists
+            .into_iter()
+            .map(|d| {
+                let key = DecklistKey {
+                    event_id: d.event_id,
+                    player_id: d.player_id.clone(),
+                };
+                (key, d)
+            })
+            .filter(|(key, _)| keys.contains(key))
+            .collect())
+    }
+}
+
+impl Loader<CardSlug> for MongoLoader {
+    type Value = Card;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[CardSlug]) -> Result<HashMap<CardSlug, Card>, Self::Error> {
+        let slugs: Vec<&str> = keys.iter().map(|k| k.0.as_str()).collect();
+        let cards: Vec<Card> = self.find_all("cards", doc! { "slug": { "$in": slugs } }).await?;
+
+        Ok(cards.into_iter().map(|c| (CardSlug(c.slug.clone()), c)).collect())
+    }
+}
+
+impl Loader<ChampionSlug> for MongoLoader {
+    type Value = Champion;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[ChampionSlug]) -> Result<HashMap<ChampionSlug, Champion>, Self::Error> {
+        let slugs: Vec<&str> = keys.iter().map(|k| k.0.as_str()).collect();
+        let champions: Vec<Champion> = self
+            .find_all("champions", doc! { "slug": { "$in": slugs } })
+            .await?;
+
+        Ok(champions.into_iter().map(|c| (ChampionSlug(c.slug.clone()), c)).collect())
+    }
+}
+
+impl Loader<PerformanceKey> for MongoLoader {
+    type Value = ChampionPerformance;
+    type Error = async_graphql::Error;
+
+    /// Computes performance once per distinct filter and picks out the requested champions
+    async fn load(
+        &self,
+        keys: &[PerformanceKey],
+    ) -> Result<HashMap<PerformanceKey, ChampionPerformance>, Self::Error> {
+        let service = MetaAnalysisService::new(self.database.clone());
+        let now = chrono::Utc::now();
+
+        let filters: HashSet<&EventFilter> = keys.iter().map(|k| &k.filter).collect();
+
+        let mut values = HashMap::new();
+        for filter in filters {
+            let range = filter.range(now)?;
+            let performance = service
+                .calculate_champion_performance(filter.format, range)
+                .await
+                .map_err(|e| internal("Failed to calculate champion performance", e))?;
+
+            for entry in performance {
+                let key = PerformanceKey {
+                    champion: entry.champion.clone(),
+                    filter: filter.clone(),
+                };
+                if keys.contains(&key) {
+                    values.insert(key, entry);
+                }
+            }
+        }
+
+        Ok(values)
+    }
+}
@@ -0,0 +1,131 @@
+pub mod loaders;
+pub mod query;
+pub mod resolvers;
+
+use async_graphql::dataloader::{DataLoader, HashMapCache};
+use async_graphql::{EmptyMutation, EmptySubscription, ErrorExtensions, InputObject, Schema};
+use chrono::{DateTime, Utc};
+use crate::controllers::filters::MAX_DAYS;
+use crate::models::{DateRange, EventFormat};
+use log::error;
+use mongodb::Database;
+use std::fmt::Display;
+
+pub use loaders::MongoLoader;
+pub use query::QueryRoot;
+
+/// Maximum nesting depth of a GraphQL query
+const MAX_DEPTH: usize = 10;
+
+/// Maximum static complexity of a GraphQL query
+const MAX_COMPLEXITY: usize = 500;
+
+pub type AppSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Build the GraphQL schema. The database is shared by all requests;
+/// data loaders are per request (see `loader`).
+pub fn build_schema(database: Database) -> AppSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(database)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// Per-request data loader batching and caching MongoDB lookups
+pub fn loader(database: Database) -> DataLoader<MongoLoader, HashMapCache> {
+    DataLoader::with_cache(MongoLoader::new(database), tokio::spawn, HashMapCache::default())
+}
+
+/// Log `cause` and return an error exposing only `message`
+pub fn internal(message: &str, cause: impl Display) -> async_graphql::Error {
+    error!("{}: {}", message, cause);
+    async_graphql::Error::new(message).extend_with(|_, e| e.set("code", "internal_error"))
+}
+
+fn invalid(field: &str, message: &str) -> async_graphql::Error {
+    async_graphql::Error::new(format!("Invalid value for '{}': {}", field, message))
+        .extend_with(|_, e| e.set("code", "invalid_parameter"))
+}
+
+/// Event filters accepted by statistics fields, matching the REST
+/// `format`, `days` and `from`/`to` query parameters
+#[derive(InputObject, Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct EventFilter {
+    pub format: Option<EventFormat>,
+    /// Only events from the last N days; cannot be combined with `from`/`to`
+    #[graphql(validator(minimum = 1, maximum = 3650))]
+    pub days: Option<i32>,
+    /// Earliest event start date
+    pub from: Option<DateTime<Utc>>,
+    /// Latest event start date, inclusive
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl EventFilter {
+    /// Resolve the filter into a date range relative to `now`
+    pub fn range(&self, now: DateTime<Utc>) -> async_graphql::Result<DateRange> {
+        if let Some(days) = self.days {
+            if self.from.is_some() || self.to.is_some() {
+                return Err(invalid("days", "cannot be combined with 'from' or 'to'"));
+            }
+            let days = i64::from(days).clamp(1, MAX_DAYS);
+            return Ok(DateRange {
+                from: Some(now - chrono::Duration::days(days)),
+                to: None,
+            });
+        }
+
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(invalid("from", "must not be after 'to'"));
+            }
+        }
+
+        Ok(DateRange {
+            from: self.from,
+            to: self.to,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_exposes_relations() {
+        let sdl = Schema::build(QueryRoot, EmptyMutation, EmptySubscription).finish().sdl();
+
+        assert!(sdl.contains("standings(first: Int): [Standing!]!"));
+        assert!(sdl.contains("decklist: Decklist"));
+        assert!(sdl.contains("card: Card"));
+        assert!(sdl.contains("performance(filter: EventFilter): ChampionPerformance"));
+        assert!(sdl.contains("player(playerId: String!): PlayerProfile"));
+    }
+
+    #[test]
+    fn test_event_filter_range() {
+        let now = Utc::now();
+
+        let filter = EventFilter {
+            days: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(filter.range(now).unwrap().from, Some(now - chrono::Duration::days(30)));
+
+        let filter = EventFilter {
+            days: Some(30),
+            from: Some(now),
+            ..Default::default()
+        };
+        assert!(filter.range(now).is_err());
+
+        let filter = EventFilter {
+            from: Some(now),
+            to: Some(now - chrono::Duration::days(1)),
+            ..Default::default()
+        };
+        assert!(filter.range(now).is_err());
+    }
+}
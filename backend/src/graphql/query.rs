@@ -0,0 +1,139 @@
+use async_graphql::{Context, Object, Result};
+use crate::models::{Card, Champion, Event, PlayerProfile};
+use crate::services::meta_analysis::{ChampionPerformance, MetaBreakdown, MetaGrouping};
+use crate::services::{MetaAnalysisService, PlayerStatsService};
+use futures::stream::StreamExt;
+use mongodb::bson::doc;
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use super::loaders::{CardSlug, ChampionSlug, EventId};
+use super::resolvers::loader;
+use super::{internal, EventFilter};
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// A single event by Omnidex event ID
+    async fn event(&self, ctx: &Context<'_>, event_id: i32) -> Result<Option<Event>> {
+        loader(ctx)?.load_one(EventId(event_id)).await
+    }
+
+    /// Completed events, most recent first
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<EventFilter>,
+        #[graphql(validator(minimum = 1))] min_players: Option<i32>,
+        #[graphql(default = 20, validator(minimum = 1, maximum = 100))] first: i64,
+        #[graphql(default = 0, validator(minimum = 0))] offset: u64,
+    ) -> Result<Vec<Event>> {
+        let filter = filter.unwrap_or_default();
+        let range = filter.range(chrono::Utc::now())?;
+
+        let mut query = doc! { "status": "complete" };
+        if let Some(format) = &filter.format {
+            query.insert("format", mongodb::bson::to_bson(format)?);
+        }
+        if let Some(start_date) = range.condition() {
+            query.insert("start_date", start_date);
+        }
+        if let Some(min_players) = min_players {
+            query.insert("player_count", doc! { "$gte": min_players });
+        }
+
+        let options = FindOptions::builder()
+            .sort(doc! { "start_date": -1, "_id": 1 })
+            .skip(offset)
+            .limit(first)
+            .build();
+
+        let mut cursor = ctx
+            .data::<Database>()?
+            .collection::<Event>("events")
+            .find(query, options)
+            .await
+            .map_err(|e| internal("Failed to fetch events", e))?;
+
+        let mut events = Vec::new();
+        while let Some(result) = cursor.next().await {
+            events.push(result.map_err(|e| internal("Failed to fetch events", e))?);
+        }
+
+        Ok(events)
+    }
+
+    /// A single champion by slug
+    async fn champion(&self, ctx: &Context<'_>, slug: String) -> Result<Option<Champion>> {
+        loader(ctx)?.load_one(ChampionSlug(slug)).await
+    }
+
+    /// All champions, by name
+    async fn champions(&self, ctx: &Context<'_>) -> Result<Vec<Champion>> {
+        let options = FindOptions::builder().sort(doc! { "name": 1 }).build();
+
+        let mut cursor = ctx
+            .data::<Database>()?
+            .collection::<Champion>("champions")
+            .find(doc! {}, options)
+            .await
+            .map_err(|e| internal("Failed to fetch champions", e))?;
+
+        let mut champions = Vec::new();
+        while let Some(result) = cursor.next().await {
+            champions.push(result.map_err(|e| internal("Failed to fetch champions", e))?);
+        }
+
+        Ok(champions)
+    }
+
+    /// A single card by slug
+    async fn card(&self, ctx: &Context<'_>, slug: String) -> Result<Option<Card>> {
+        loader(ctx)?.load_one(CardSlug(slug)).await
+    }
+
+    /// A player's profile and tournament history
+    async fn player(&self, ctx: &Context<'_>, player_id: String) -> Result<Option<PlayerProfile>> {
+        let service = PlayerStatsService::new(ctx.data::<Database>()?.clone());
+
+        service
+            .get_profile(&player_id)
+            .await
+            .map_err(|e| internal("Failed to fetch player", e))
+    }
+
+    /// Champion or lineage share of ranked events
+    #[graphql(complexity = 50)]
+    async fn meta_breakdown(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<EventFilter>,
+        #[graphql(default)] group_by: MetaGrouping,
+    ) -> Result<Vec<MetaBreakdown>> {
+        let filter = filter.unwrap_or_default();
+        let range = filter.range(chrono::Utc::now())?;
+        let service = MetaAnalysisService::new(ctx.data::<Database>()?.clone());
+
+        service
+            .calculate_meta_breakdown(filter.format, range, group_by)
+            .await
+            .map_err(|e| internal("Failed to calculate meta breakdown", e))
+    }
+
+    /// Results of every champion in ranked events
+    #[graphql(complexity = 50)]
+    async fn champion_performance(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<EventFilter>,
+    ) -> Result<Vec<ChampionPerformance>> {
+        let filter = filter.unwrap_or_default();
+        let range = filter.range(chrono::Utc::now())?;
+        let service = MetaAnalysisService::new(ctx.data::<Database>()?.clone());
+
+        service
+            .calculate_champion_performance(filter.format, range)
+            .await
+            .map_err(|e| internal("Failed to calculate champion performance", e))
+    }
+}
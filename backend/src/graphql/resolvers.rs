@@ -0,0 +1,107 @@
+//! Relations between the REST models, resolved through the request's `MongoLoader`
+
+use async_graphql::dataloader::{DataLoader, HashMapCache};
+use async_graphql::{ComplexObject, Context, Result};
+use crate::models::{Card, Champion, Decklist, DecklistCard, Event, PlayerEventResult, Standing};
+use crate::services::meta_analysis::{ChampionOverview, ChampionPerformance};
+use crate::services::MetaAnalysisService;
+use mongodb::Database;
+use super::loaders::{CardSlug, DecklistKey, EventId, MongoLoader, PerformanceKey, StandingsOf};
+use super::{internal, EventFilter};
+
+pub(super) fn loader<'a>(ctx: &Context<'a>) -> Result<&'a DataLoader<MongoLoader, HashMapCache>> {
+    ctx.data::<DataLoader<MongoLoader, HashMapCache>>()
+}
+
+#[ComplexObject]
+impl Event {
+    /// Final standings, best placement first
+    async fn standings(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(validator(minimum = 1, maximum = 512))] first: Option<i32>,
+    ) -> Result<Vec<Standing>> {
+        let mut standings = loader(ctx)?
+            .load_one(StandingsOf(self.event_id))
+            .await?
+            .unwrap_or_default();
+
+        if let Some(first) = first {
+            standings.truncate(first as usize);
+        }
+        Ok(standings)
+    }
+}
+
+#[ComplexObject]
+impl Standing {
+    async fn event(&self, ctx: &Context<'_>) -> Result<Option<Event>> {
+        loader(ctx)?.load_one(EventId(self.event_id)).await
+    }
+
+    /// The player's decklist for this event, if published
+    async fn decklist(&self, ctx: &Context<'_>) -> Result<Option<Decklist>> {
+        if !self.has_decklist {
+            return Ok(None);
+        }
+
+        loader(ctx)?
+            .load_one(DecklistKey {
+                event_id: self.event_id,
+                player_id: self.player_id.clone(),
+            })
+            .await
+    }
+}
+
+#[ComplexObject]
+impl Decklist {
+    async fn event(&self, ctx: &Context<'_>) -> Result<Option<Event>> {
+        loader(ctx)?.load_one(EventId(self.event_id)).await
+    }
+}
+
+#[ComplexObject]
+impl DecklistCard {
+    /// Full card details from the catalogue
+    async fn card(&self, ctx: &Context<'_>) -> Result<Option<Card>> {
+        loader(ctx)?.load_one(CardSlug(self.slug.clone())).await
+    }
+}
+
+#[ComplexObject]
+impl Champion {
+    /// Results in ranked events matching `filter`
+    async fn performance(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<EventFilter>,
+    ) -> Result<Option<ChampionPerformance>> {
+        loader(ctx)?
+            .load_one(PerformanceKey {
+                champion: self.slug.clone(),
+                filter: filter.unwrap_or_default(),
+            })
+            .await
+    }
+
+    /// Meta share, top finishes and most played cards
+    #[graphql(complexity = 50)]
+    async fn overview(&self, ctx: &Context<'_>, filter: Option<EventFilter>) -> Result<ChampionOverview> {
+        let filter = filter.unwrap_or_default();
+        let range = filter.range(chrono::Utc::now())?;
+        let service = MetaAnalysisService::new(ctx.data::<Database>()?.clone());
+
+        service
+            .calculate_champion_overview(&self.slug, filter.format, range)
+            .await
+            .map_err(|e| internal("Failed to fetch champion overview", e))
+    }
+}
+
+#[ComplexObject]
+impl PlayerEventResult {
+    async fn event(&self, ctx: &Context<'_>) -> Result<Option<Event>> {
+        loader(ctx)?.load_one(EventId(self.event_id)).await
+    }
+}
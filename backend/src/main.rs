@@ -1,20 +1,36 @@
+mod cache;
+mod cli;
 mod config;
+mod db;
+mod error;
+mod graphql;
+mod rate_limit;
 mod models;
 mod clients;
 mod services;
 mod controllers;
 mod middleware;
+mod migrations;
+mod openapi;
 mod scheduler;
 
 use actix_web::{web, App, HttpServer, middleware::Logger};
+use clap::Parser;
+use cli::{Cli, Command};
 use config::Config;
 use log::{info, error};
-use mongodb::{Client, options::ClientOptions};
+use std::sync::Arc;
+use mongodb::Database;
+use utoipa_swagger_ui::SwaggerUi;
 
 #[actix_web::main]
-async fn main() -> std::io::Result<()> {
-    // Initialize logger
-    env_logger::init();
+async fn main() {
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or(Command::Serve);
+
+    // Initialize logger; operational commands report progress at info level by default
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(command.default_log_level()))
+        .init();
 
     // Load configuration
     let config = match Config::from_env() {
@@ -24,60 +40,58 @@ async fn main() -> std::io::Result<()> {
         }
         Err(e) => {
             error!("Failed to load configuration: {}", e);
-            std::process::exit(1);
+            std::process::exit(cli::EXIT_UNAVAILABLE);
         }
     };
 
     // Connect to MongoDB
-    let mongodb_uri = config.mongodb_uri.clone();
-    let database_name = config.mongodb_database.clone();
-
-    let client_options = match ClientOptions::parse(&mongodb_uri).await {
-        Ok(opts) => opts,
+    let database = match db::connect(&config).await {
+        Ok(database) => database,
         Err(e) => {
-            error!("Failed to parse MongoDB URI: {}", e);
-            std::process::exit(1);
+            error!("Failed to connect to MongoDB: {}", e);
+            std::process::exit(cli::EXIT_UNAVAILABLE);
         }
     };
 
-    let client = match Client::with_options(client_options) {
-        Ok(c) => {
-            info!("MongoDB client created successfully");
-            c
-        }
-        Err(e) => {
-            error!("Failed to create MongoDB client: {}", e);
-            std::process::exit(1);
-        }
+    let code = match command {
+        Command::Serve => match serve(config, database).await {
+            Ok(()) => cli::EXIT_OK,
+            Err(e) => {
+                error!("HTTP server failed: {}", e);
+                cli::EXIT_FAILURE
+            }
+        },
+        Command::Operation(operation) => cli::run(operation, config, database).await,
     };
 
-    // Test database connection
-    match client
-        .database(&database_name)
-        .run_command(mongodb::bson::doc! { "ping": 1 }, None)
-        .await
-    {
-        Ok(_) => info!("Successfully connected to MongoDB"),
-        Err(e) => {
-            error!("Failed to connect to MongoDB: {}", e);
-            std::process::exit(1);
+    std::process::exit(code);
+}
+
+/// Run the HTTP API server and the job scheduler
+async fn serve(config: Config, database: Database) -> std::io::Result<()> {
+    // Apply pending schema migrations, including index creation
+    if config.migrate_on_startup {
+        match migrations::Migrator::new(database.clone()).up(None, false).await {
+            Ok(applied) if !applied.is_empty() => info!("Applied {} migrations", applied.len()),
+            Ok(_) => {}
+            // Don't exit; the API can still serve from the existing schema
+            Err(e) => error!("Failed to apply migrations: {}", e),
         }
     }
 
-    let database = client.database(&database_name);
-
-    // Create indexes for better query performance
-    if let Err(e) = create_indexes(&database).await {
-        error!("Failed to create indexes: {}", e);
-        // Don't exit, indexes are optional for startup
-    }
+    // Response cache shared by the HTTP workers and invalidated by scheduled jobs
+    let response_cache = Arc::new(cache::ResponseCache::new(
+        config.cache_max_entries,
+        config.cache_ttl_secs,
+    ));
 
     // Setup and start the job scheduler
     let scheduler_db = database.clone();
     let scheduler_config = config.clone();
+    let scheduler_cache = response_cache.clone();
 
     tokio::spawn(async move {
-        match scheduler::setup_scheduler(scheduler_config, scheduler_db).await {
+        match scheduler::setup_scheduler(scheduler_config, scheduler_db, scheduler_cache).await {
             Ok(scheduler) => {
                 info!("Job scheduler configured");
                 if let Err(e) = scheduler::start_scheduler(scheduler).await {
@@ -90,6 +104,21 @@ async fn main() -> std::io::Result<()> {
         }
     });
 
+    // Rate limit counters shared across workers
+    let rate_limits = Arc::new(rate_limit::RateLimits::from_config(&config));
+
+    // Authentication shared across workers
+    let auth_service = web::Data::new(services::AuthService::new(database.clone(), &config));
+
+    // Crawler for admin-triggered ingestion
+    let event_crawler = web::Data::new(services::EventCrawler::from_config(&config, database.clone()));
+
+    // GraphQL schema shared across workers
+    let graphql_schema = web::Data::new(graphql::build_schema(database.clone()));
+
+    // OpenAPI document, generated once from the handler annotations
+    let api_spec = openapi::spec();
+
     // Server configuration
     let host = config.host.clone();
     let port = config.port;
@@ -101,6 +130,17 @@ async fn main() -> std::io::Result<()> {
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(database.clone()))
+            .app_data(auth_service.clone())
+            .app_data(graphql_schema.clone())
+            .app_data(event_crawler.clone())
+            .app_data(web::Data::from(response_cache.clone()))
+            .app_data(controllers::query_config())
+            .app_data(controllers::json_config())
+            .app_data(controllers::path_config())
+            .wrap(middleware::ResponseCaching::new(response_cache.clone()))
+            .wrap(middleware::Authentication)
+            .wrap(middleware::RateLimiting::new(rate_limits.clone()))
+            .wrap(middleware::RequestId)
             .wrap(Logger::default())
             .wrap(middleware::configure_cors())
             .service(
@@ -108,128 +148,20 @@ async fn main() -> std::io::Result<()> {
                     .configure(controllers::configure_health)
                     .configure(controllers::configure_champions)
                     .configure(controllers::configure_events)
+                    .configure(controllers::configure_export)
                     .configure(controllers::configure_decklists)
                     .configure(controllers::configure_meta)
+                    .configure(controllers::configure_cards)
+                    .configure(controllers::configure_players)
+                    .configure(controllers::configure_ratings)
+                    .configure(controllers::configure_saved_decklists)
+                    .configure(controllers::configure_auth)
+                    .configure(controllers::configure_admin)
+                    .configure(controllers::configure_graphql)
             )
+            .service(SwaggerUi::new("/api/docs/{_:.*}").url(openapi::SPEC_PATH, api_spec.clone()))
     })
     .bind(&bind_address)?
     .run()
     .await
 }
-
-/// Create database indexes for optimized queries
-async fn create_indexes(database: &mongodb::Database) -> Result<(), mongodb::error::Error> {
-    use mongodb::options::IndexOptions;
-    use mongodb::IndexModel;
-    use mongodb::bson::doc;
-
-    info!("Creating database indexes");
-
-    // Events collection indexes
-    {
-        let collection = database.collection::<mongodb::bson::Document>("events");
-
-        let index_models = vec![
-            IndexModel::builder()
-                .keys(doc! { "event_id": 1 })
-                .options(IndexOptions::builder().unique(true).build())
-                .build(),
-            IndexModel::builder()
-                .keys(doc! { "status": 1, "start_date": -1 })
-                .build(),
-            IndexModel::builder()
-                .keys(doc! { "format": 1, "start_date": -1 })
-                .build(),
-        ];
-
-        collection.create_indexes(index_models, None).await?;
-    }
-
-    // Standings collection indexes
-    {
-        let collection = database.collection::<mongodb::bson::Document>("standings");
-
-        let index_models = vec![
-            IndexModel::builder()
-                .keys(doc! { "event_id": 1, "player_id": 1 })
-                .options(IndexOptions::builder().unique(true).build())
-                .build(),
-            IndexModel::builder()
-                .keys(doc! { "event_id": 1, "rank": 1 })
-                .build(),
-            IndexModel::builder()
-                .keys(doc! { "champion": 1 })
-                .build(),
-        ];
-
-        collection.create_indexes(index_models, None).await?;
-    }
-
-    // Decklists collection indexes
-    {
-        let collection = database.collection::<mongodb::bson::Document>("decklists");
-
-        let index_models = vec![
-            IndexModel::builder()
-                .keys(doc! { "event_id": 1, "player_id": 1 })
-                .options(IndexOptions::builder().unique(true).build())
-                .build(),
-            IndexModel::builder()
-                .keys(doc! { "champion": 1 })
-                .build(),
-            IndexModel::builder()
-                .keys(doc! { "player_id": 1 })
-                .build(),
-        ];
-
-        collection.create_indexes(index_models, None).await?;
-    }
-
-    // Champions collection indexes
-    {
-        let collection = database.collection::<mongodb::bson::Document>("champions");
-
-        let index_models = vec![
-            IndexModel::builder()
-                .keys(doc! { "slug": 1 })
-                .options(IndexOptions::builder().unique(true).build())
-                .build(),
-        ];
-
-        collection.create_indexes(index_models, None).await?;
-    }
-
-    // Cards collection indexes
-    {
-        let collection = database.collection::<mongodb::bson::Document>("cards");
-
-        let index_models = vec![
-            IndexModel::builder()
-                .keys(doc! { "slug": 1 })
-                .options(IndexOptions::builder().unique(true).build())
-                .build(),
-            IndexModel::builder()
-                .keys(doc! { "name": 1 })
-                .build(),
-        ];
-
-        collection.create_indexes(index_models, None).await?;
-    }
-
-    // Crawler state collection indexes
-    {
-        let collection = database.collection::<mongodb::bson::Document>("crawler_state");
-
-        let index_models = vec![
-            IndexModel::builder()
-                .keys(doc! { "last_crawl": -1 })
-                .build(),
-        ];
-
-        collection.create_indexes(index_models, None).await?;
-    }
-
-    info!("Database indexes created successfully");
-
-    Ok(())
-}
@@ -0,0 +1,191 @@
+use actix_web::{
+    dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header,
+    web, Error, FromRequest, HttpMessage, HttpRequest,
+};
+use futures::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use crate::error::ApiError;
+use crate::models::Role;
+use crate::services::auth::{AuthError, AuthService, Principal};
+
+/// Header carrying API keys for service clients
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+fn unauthorized(message: &str) -> Error {
+    ApiError::Unauthorized(message.to_string()).into()
+}
+
+fn forbidden(message: &str) -> Error {
+    ApiError::Forbidden(message.to_string()).into()
+}
+
+/// Credentials presented by a request
+pub(super) enum Credentials {
+    None,
+    Bearer(String),
+    ApiKey(String),
+}
+
+pub(super) fn credentials(req: &ServiceRequest) -> Credentials {
+    if let Some(key) = req.headers().get(API_KEY_HEADER).and_then(|v| v.to_str().ok()) {
+        return Credentials::ApiKey(key.trim().to_string());
+    }
+
+    match req.headers().get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()) {
+        Some(value) => match value.strip_prefix("Bearer ") {
+            Some(token) => Credentials::Bearer(token.trim().to_string()),
+            None => Credentials::None,
+        },
+        None => Credentials::None,
+    }
+}
+
+/// Authentication middleware. Resolves bearer tokens and API keys into a
+/// `Principal` stored in the request extensions. Requests without credentials
+/// pass through anonymously; invalid credentials are rejected with 401.
+pub struct Authentication;
+
+impl<S, B> Transform<S, ServiceRequest> for Authentication
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = AuthenticationMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AuthenticationMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct AuthenticationMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for AuthenticationMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let auth = req.app_data::<web::Data<AuthService>>().cloned();
+
+            if let Some(auth) = auth {
+                let principal = match credentials(&req) {
+                    Credentials::None => None,
+                    Credentials::Bearer(token) => match auth.authenticate_token(&token).await {
+                        Ok(principal) => Some(principal),
+                        Err(e @ AuthError::Database(_)) => return Err(ApiError::from(e).into()),
+                        Err(_) => return Err(unauthorized("Invalid or expired token")),
+                    },
+                    Credentials::ApiKey(key) => match auth.verify_api_key(&key).await {
+                        Ok(principal) => Some(principal),
+                        Err(e @ AuthError::Database(_)) => return Err(ApiError::from(e).into()),
+                        Err(_) => return Err(unauthorized("Invalid API key")),
+                    },
+                };
+
+                if let Some(principal) = principal {
+                    req.extensions_mut().insert(principal);
+                }
+            }
+
+            service.call(req).await
+        })
+    }
+}
+
+/// Route guard middleware requiring an authenticated principal with at least `role`.
+/// Must run inside `Authentication`.
+pub struct RequireRole {
+    role: Role,
+}
+
+impl RequireRole {
+    pub fn new(role: Role) -> Self {
+        Self { role }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequireRole
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequireRoleMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireRoleMiddleware {
+            service,
+            role: self.role,
+        }))
+    }
+}
+
+pub struct RequireRoleMiddleware<S> {
+    service: S,
+    role: Role,
+}
+
+impl<S, B> Service<ServiceRequest> for RequireRoleMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let role = req.extensions().get::<Principal>().map(|p| p.role);
+
+        match role {
+            None => Box::pin(async { Err(unauthorized("Authentication required")) }),
+            Some(role) if !role.satisfies(self.role) => {
+                Box::pin(async { Err(forbidden("Insufficient permissions")) })
+            }
+            Some(_) => Box::pin(self.service.call(req)),
+        }
+    }
+}
+
+/// Extracting a `Principal` requires an authenticated request (401 otherwise)
+impl FromRequest for Principal {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(
+            req.extensions()
+                .get::<Principal>()
+                .cloned()
+                .ok_or_else(|| unauthorized("Authentication required")),
+        )
+    }
+}
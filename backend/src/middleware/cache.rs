@@ -1,83 +1,195 @@
 use actix_web::{
+    body::{self, BoxBody, MessageBody},
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error,
+    http::{header, Method, StatusCode},
+    Error, HttpMessage, HttpRequest, HttpResponse,
 };
 use futures::future::LocalBoxFuture;
 use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Arc;
+use crate::cache::{if_none_match, CachedResponse, ResponseCache};
+use crate::services::auth::Principal;
 
-/// Cache control middleware for adding cache headers to responses
-#[allow(dead_code)]
-pub struct CacheControl {
-    max_age: u32,
+/// Paths that must always reach their handler
+const UNCACHED_PATHS: &[&str] = &["/api/health"];
+
+/// Path prefixes whose responses are streamed and too large to buffer
+const UNCACHED_PREFIXES: &[&str] = &["/api/export/"];
+
+/// Response caching middleware. Serves anonymous GET requests from the shared
+/// `ResponseCache`, sets `ETag` and `Cache-Control` headers and answers
+/// matching `If-None-Match` requests with 304. Must run inside `Authentication`
+/// so per-user responses are never cached.
+pub struct ResponseCaching {
+    cache: Arc<ResponseCache>,
 }
 
-#[allow(dead_code)]
-impl CacheControl {
-    pub fn new(max_age: u32) -> Self {
-        Self { max_age }
+impl ResponseCaching {
+    pub fn new(cache: Arc<ResponseCache>) -> Self {
+        Self { cache }
     }
 }
 
-impl<S, B> Transform<S, ServiceRequest> for CacheControl
+impl<S, B> Transform<S, ServiceRequest> for ResponseCaching
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
-    B: 'static,
+    B: MessageBody + 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<BoxBody>;
     type Error = Error;
     type InitError = ();
-    type Transform = CacheControlMiddleware<S>;
+    type Transform = ResponseCachingMiddleware<S>;
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(CacheControlMiddleware {
-            service,
-            max_age: self.max_age,
+        ready(Ok(ResponseCachingMiddleware {
+            service: Rc::new(service),
+            cache: self.cache.clone(),
         }))
     }
 }
 
-#[allow(dead_code)]
-pub struct CacheControlMiddleware<S> {
-    service: S,
-    max_age: u32,
+pub struct ResponseCachingMiddleware<S> {
+    service: Rc<S>,
+    cache: Arc<ResponseCache>,
 }
 
-impl<S, B> Service<ServiceRequest> for CacheControlMiddleware<S>
+/// Build a response from a cache entry, or a 304 if the client already has it
+fn cached_response(req: &HttpRequest, entry: &CachedResponse, max_age: u64, hit: bool) -> HttpResponse {
+    let not_modified = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| if_none_match(v, &entry.etag))
+        .unwrap_or(false);
+
+    let mut builder = if not_modified {
+        HttpResponse::NotModified()
+    } else {
+        HttpResponse::Ok()
+    };
+
+    builder
+        .insert_header((header::ETAG, entry.etag.as_str()))
+        .insert_header((header::CACHE_CONTROL, format!("public, max-age={}", max_age)))
+        .insert_header(("X-Cache", if hit { "HIT" } else { "MISS" }));
+
+    if not_modified {
+        return builder.finish();
+    }
+
+    if let Some(content_type) = &entry.content_type {
+        builder.insert_header((header::CONTENT_TYPE, content_type.as_str()));
+    }
+
+    builder.body(entry.body.clone())
+}
+
+impl<S, B> Service<ServiceRequest> for ResponseCachingMiddleware<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
-    B: 'static,
+    B: MessageBody + 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<BoxBody>;
     type Error = Error;
     type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
 
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let max_age = self.max_age;
-        let fut = self.service.call(req);
+        let service = Rc::clone(&self.service);
+        let cache = self.cache.clone();
 
         Box::pin(async move {
-            let mut res = fut.await?;
-
-            // Add cache control header to successful GET requests
-            if res.request().method() == actix_web::http::Method::GET
-                && res.status().is_success()
-            {
-                res.headers_mut().insert(
-                    actix_web::http::header::CACHE_CONTROL,
-                    actix_web::http::header::HeaderValue::from_str(&format!(
-                        "public, max-age={}",
-                        max_age
-                    ))
-                    .unwrap(),
-                );
+            let cacheable = req.method() == Method::GET
+                && !UNCACHED_PATHS.contains(&req.path())
+                && !UNCACHED_PREFIXES.iter().any(|prefix| req.path().starts_with(prefix))
+                && req.extensions().get::<Principal>().is_none();
+
+            if !cacheable {
+                return Ok(service.call(req).await?.map_into_boxed_body());
+            }
+
+            let key = match req.query_string() {
+                "" => req.path().to_string(),
+                query => format!("{}?{}", req.path(), query),
+            };
+            let max_age = cache.ttl_secs();
+
+            if let Some(entry) = cache.get(&key) {
+                let response = cached_response(req.request(), &entry, max_age, true);
+                return Ok(req.into_response(response));
             }
 
-            Ok(res)
+            let res = service.call(req).await?;
+
+            if res.status() != StatusCode::OK {
+                return Ok(res.map_into_boxed_body());
+            }
+
+            let (request, response) = res.into_parts();
+            let content_type = response
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+
+            let bytes = body::to_bytes(response.into_body())
+                .await
+                .map_err(|e| actix_web::error::ErrorInternalServerError(e.into()))?;
+
+            let entry = CachedResponse::new(bytes, content_type);
+            let response = cached_response(&request, &entry, max_age, false);
+            cache.insert(key, entry);
+
+            Ok(ServiceResponse::new(request, response))
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, web::Bytes, App};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[actix_web::test]
+    async fn test_repeated_requests_are_served_from_cache() {
+        let calls = web::Data::new(AtomicUsize::new(0));
+        let cache = Arc::new(ResponseCache::new(10, 60));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(calls.clone())
+                .wrap(ResponseCaching::new(cache))
+                .route(
+                    "/meta",
+                    web::get().to(|calls: web::Data<AtomicUsize>| async move {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        HttpResponse::Ok().json(serde_json::json!({ "total": 1 }))
+                    }),
+                ),
+        )
+        .await;
+
+        let res = test::call_service(&app, test::TestRequest::get().uri("/meta").to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let etag = res.headers().get(header::ETAG).unwrap().to_str().unwrap().to_string();
+
+        let res = test::call_service(&app, test::TestRequest::get().uri("/meta").to_request()).await;
+        assert_eq!(res.headers().get("X-Cache").unwrap(), "HIT");
+        assert_eq!(test::read_body(res).await, Bytes::from_static(b"{\"total\":1}"));
+
+        let req = test::TestRequest::get()
+            .uri("/meta")
+            .insert_header((header::IF_NONE_MATCH, etag))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,4 +1,11 @@
+pub mod auth;
 pub mod cors;
 pub mod cache;
+pub mod rate_limit;
+pub mod request_id;
 
+pub use auth::{Authentication, RequireRole};
+pub use cache::ResponseCaching;
+pub use rate_limit::RateLimiting;
+pub use request_id::RequestId;
 pub use cors::configure_cors;
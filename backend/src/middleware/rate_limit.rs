@@ -0,0 +1,192 @@
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderMap, HeaderName, HeaderValue},
+    web, Error, HttpMessage,
+};
+use futures::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Arc;
+use crate::error::ApiError;
+use crate::rate_limit::{RateLimitDecision, RateLimits};
+use crate::services::auth::{hash_api_key, AuthService, Principal, PrincipalKind};
+use super::auth::{credentials, Credentials};
+
+/// Routes that scan whole collections per request and get a stricter bucket
+const EXPENSIVE_PREFIXES: &[&str] = &[
+    "/api/meta/",
+    "/api/cards/performance",
+    "/api/ratings/leaderboard",
+    "/api/graphql",
+    "/api/export/",
+];
+
+/// Collections whose single-item routes (`/api/cards/{slug}`,
+/// `/api/players/{player_id}`) aggregate over whole collections
+const EXPENSIVE_ITEM_PREFIXES: &[&str] = &["/api/cards/", "/api/players/"];
+
+/// Fixed routes under `EXPENSIVE_ITEM_PREFIXES` that are not item lookups
+const CHEAP_ITEM_ROUTES: &[&str] = &["sets"];
+
+fn is_expensive(path: &str) -> bool {
+    EXPENSIVE_PREFIXES.iter().any(|prefix| path.starts_with(prefix))
+        || (path.starts_with("/api/champions/") && (path.ends_with("/overview") || path.ends_with("/materials")))
+        || EXPENSIVE_ITEM_PREFIXES.iter().any(|prefix| {
+            path.strip_prefix(prefix)
+                .is_some_and(|item| !item.is_empty() && !item.contains('/') && !CHEAP_ITEM_ROUTES.contains(&item))
+        })
+}
+
+fn set_limit_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    let values = [
+        ("x-ratelimit-limit", decision.limit as u64),
+        ("x-ratelimit-remaining", decision.remaining as u64),
+        ("x-ratelimit-reset", decision.reset_secs),
+    ];
+
+    for (name, value) in values {
+        headers.insert(HeaderName::from_static(name), HeaderValue::from(value));
+    }
+}
+
+/// Bucket and limit for a request, decided before `Authentication` runs.
+/// Bearer tokens are identified by their signature and API keys only once
+/// they have authenticated before; everything else, including invalid
+/// credentials, is limited per IP. Returns the hash of an API key not yet known.
+fn client(req: &ServiceRequest, limits: &RateLimits) -> (String, u32, Option<String>) {
+    let mut unknown_key = None;
+
+    match credentials(req) {
+        Credentials::Bearer(token) => {
+            let principal = req
+                .app_data::<web::Data<AuthService>>()
+                .and_then(|auth| auth.verify_token(&token).ok());
+            if let Some(principal) = principal {
+                return (format!("user:{}", principal.subject), limits.authenticated, None);
+            }
+        }
+        Credentials::ApiKey(key) => {
+            let hash = hash_api_key(&key);
+            if limits.is_known_api_key(&hash) {
+                return (format!("key:{}", hash), limits.authenticated, None);
+            }
+            unknown_key = Some(hash);
+        }
+        Credentials::None => {}
+    }
+
+    let info = req.connection_info();
+    let ip = if limits.trust_proxy {
+        info.realip_remote_addr()
+    } else {
+        info.peer_addr()
+    };
+    (format!("ip:{}", ip.unwrap_or("unknown")), limits.anonymous, unknown_key)
+}
+
+/// Rate limiting middleware. Authenticated callers are limited per API key or
+/// user, anonymous callers and invalid credentials per IP, with a separate
+/// stricter bucket for expensive routes. Must run outside `Authentication`,
+/// so failed authentication attempts are limited before any lookups.
+pub struct RateLimiting {
+    limits: Arc<RateLimits>,
+}
+
+impl RateLimiting {
+    pub fn new(limits: Arc<RateLimits>) -> Self {
+        Self { limits }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimiting
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitingMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitingMiddleware {
+            service: Rc::new(service),
+            limits: self.limits.clone(),
+        }))
+    }
+}
+
+pub struct RateLimitingMiddleware<S> {
+    service: Rc<S>,
+    limits: Arc<RateLimits>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitingMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let limits = self.limits.clone();
+
+        Box::pin(async move {
+            if !limits.enabled {
+                return service.call(req).await;
+            }
+
+            let (client, limit, unknown_key) = client(&req, &limits);
+
+            let decision = limits.check(&client, limit, is_expensive(req.path()));
+            if !decision.allowed {
+                return Err(ApiError::RateLimited(decision).into());
+            }
+
+            let mut res = service.call(req).await?;
+            set_limit_headers(res.headers_mut(), &decision);
+
+            // Authentication accepted the key, so later requests get its own bucket
+            if let Some(hash) = unknown_key {
+                let authenticated = res
+                    .request()
+                    .extensions()
+                    .get::<Principal>()
+                    .is_some_and(|p| p.kind == PrincipalKind::ApiKey);
+                if authenticated {
+                    limits.remember_api_key(hash);
+                }
+            }
+
+            Ok(res)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expensive_routes() {
+        assert!(is_expensive("/api/meta/breakdown"));
+        assert!(is_expensive("/api/cards/performance"));
+        assert!(is_expensive("/api/champions/lorraine/overview"));
+        assert!(is_expensive("/api/champions/lorraine/materials"));
+        assert!(is_expensive("/api/cards/fireball"));
+        assert!(is_expensive("/api/players/p123"));
+        assert!(!is_expensive("/api/champions/lorraine"));
+        assert!(!is_expensive("/api/cards"));
+        assert!(!is_expensive("/api/cards/sets"));
+        assert!(!is_expensive("/api/cards/fireball/history"));
+        assert!(!is_expensive("/api/players"));
+    }
+}
@@ -0,0 +1,141 @@
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
+    http::header::{HeaderMap, HeaderName, HeaderValue},
+    Error,
+};
+use futures::future::LocalBoxFuture;
+use rand::RngCore;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use crate::error::{RequestContext, REQUEST_CONTEXT};
+
+/// Header carrying the request ID
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Accept client-supplied request IDs that are short and printable
+fn valid_request_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 128 && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+fn set_request_id(headers: &mut HeaderMap, request_id: &str) {
+    if let Ok(value) = HeaderValue::from_str(request_id) {
+        headers.insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+}
+
+fn generate_request_id() -> String {
+    let mut bytes = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Request ID middleware. Assigns every request an ID (reusing a valid
+/// incoming `X-Request-Id`), makes it available to error responses and echoes
+/// it in the response headers. Errors from inner middleware are rendered here
+/// so they carry the request ID as well.
+pub struct RequestId;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestId
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestIdMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RequestIdMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|id| valid_request_id(id))
+            .map(str::to_string)
+            .unwrap_or_else(generate_request_id);
+
+        let context = RequestContext {
+            request_id: request_id.clone(),
+            path: req.path().to_string(),
+        };
+
+        Box::pin(REQUEST_CONTEXT.scope(context, async move {
+            match service.call(req).await {
+                Ok(mut res) => {
+                    set_request_id(res.headers_mut(), &request_id);
+                    Ok(res)
+                }
+                Err(e) => {
+                    // Render the error while the request context is still in scope
+                    let mut response = e.error_response();
+                    set_request_id(response.headers_mut(), &request_id);
+                    Err(InternalError::from_response(e, response).into())
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ApiError;
+    use actix_web::{http::StatusCode, test, web, App, HttpResponse};
+
+    #[actix_web::test]
+    async fn test_errors_carry_request_id() {
+        let app = test::init_service(App::new().wrap(RequestId).route(
+            "/missing",
+            web::get().to(|| async {
+                Err::<HttpResponse, _>(ApiError::NotFound("Event not found".to_string()))
+            }),
+        ))
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/missing")
+            .insert_header((REQUEST_ID_HEADER, "req-42"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(res.headers().get(REQUEST_ID_HEADER).unwrap(), "req-42");
+        assert_eq!(
+            res.headers().get("content-type").unwrap(),
+            crate::error::PROBLEM_JSON
+        );
+
+        let body: serde_json::Value = test::read_body_json(res).await;
+        assert_eq!(body["code"], "not_found");
+        assert_eq!(body["request_id"], "req-42");
+        assert_eq!(body["instance"], "/missing");
+    }
+}
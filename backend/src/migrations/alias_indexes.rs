@@ -0,0 +1,27 @@
+//! Migration 5: indexes for champion aliases and unresolved champion strings
+
+use super::indexes::{self, unique};
+use super::MigrationResult;
+use crate::services::champion_aliases::{ALIASES_COLLECTION, UNRESOLVED_COLLECTION};
+use futures::future::BoxFuture;
+use mongodb::bson::doc;
+use mongodb::{Database, IndexModel};
+
+pub(super) fn definitions() -> Vec<(&'static str, Vec<IndexModel>)> {
+    vec![
+        (ALIASES_COLLECTION, vec![
+            unique(doc! { "alias": 1 }),
+        ]),
+        (UNRESOLVED_COLLECTION, vec![
+            unique(doc! { "value": 1 }),
+        ]),
+    ]
+}
+
+pub(super) fn up(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move { indexes::create(&database, definitions()).await })
+}
+
+pub(super) fn down(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move { indexes::drop(&database, definitions()).await })
+}
@@ -0,0 +1,19 @@
+//! Migration 9: count material deck cards in decklist card frequencies
+
+use super::MigrationResult;
+use crate::services::DeckStatsService;
+use futures::future::BoxFuture;
+use mongodb::Database;
+
+pub(super) fn up(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move {
+        DeckStatsService::new(database).recount_frequencies().await?;
+        Ok(())
+    })
+}
+
+/// Counts that include the material deck are what ingestion writes, so
+/// reverting leaves them as they are
+pub(super) fn down(_: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async { Ok(()) })
+}
@@ -0,0 +1,24 @@
+//! Migration 6: index for card history lookups
+
+use super::indexes::{self, index};
+use super::MigrationResult;
+use crate::services::card_sync::CARD_REVISIONS_COLLECTION;
+use futures::future::BoxFuture;
+use mongodb::bson::doc;
+use mongodb::{Database, IndexModel};
+
+pub(super) fn definitions() -> Vec<(&'static str, Vec<IndexModel>)> {
+    vec![
+        (CARD_REVISIONS_COLLECTION, vec![
+            index(doc! { "slug": 1, "recorded_at": -1 }),
+        ]),
+    ]
+}
+
+pub(super) fn up(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move { indexes::create(&database, definitions()).await })
+}
+
+pub(super) fn down(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move { indexes::drop(&database, definitions()).await })
+}
@@ -0,0 +1,22 @@
+//! Migration 2: deck statistics and schema version on decklists
+
+use super::MigrationResult;
+use crate::services::DeckStatsService;
+use futures::future::BoxFuture;
+use log::info;
+use mongodb::Database;
+
+pub(super) fn up(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move {
+        let upgraded = DeckStatsService::new(database).backfill(1).await?;
+        info!("Computed deck statistics for {} decklists", upgraded);
+        Ok(())
+    })
+}
+
+pub(super) fn down(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move {
+        DeckStatsService::new(database).clear().await?;
+        Ok(())
+    })
+}
@@ -0,0 +1,59 @@
+//! Migration 8: store event `start_date`/`end_date` as BSON datetimes. They
+//! were written as RFC 3339 strings, which date range filters never match.
+
+use super::{MigrationError, MigrationResult};
+use futures::future::BoxFuture;
+use mongodb::bson::{doc, Document};
+use mongodb::options::UpdateModifications;
+use mongodb::{Collection, Database};
+
+const DATE_FIELDS: &[&str] = &["start_date", "end_date"];
+
+/// Convert RFC 3339 strings in `field` to BSON datetimes. Unparseable
+/// strings are left as they are.
+pub(super) async fn strings_to_dates(collection: &Collection<Document>, field: &str) -> Result<(), MigrationError> {
+    let value = format!("${}", field);
+    let pipeline = vec![doc! {
+        "$set": {
+            field: { "$dateFromString": { "dateString": &value, "onError": &value } }
+        }
+    }];
+    collection
+        .update_many(doc! { field: { "$type": "string" } }, UpdateModifications::Pipeline(pipeline), None)
+        .await?;
+    Ok(())
+}
+
+/// Convert BSON datetimes in `field` back to RFC 3339 strings
+pub(super) async fn dates_to_strings(collection: &Collection<Document>, field: &str) -> Result<(), MigrationError> {
+    let value = format!("${}", field);
+    let pipeline = vec![doc! {
+        "$set": {
+            field: { "$dateToString": { "date": &value, "format": "%Y-%m-%dT%H:%M:%S.%LZ" } }
+        }
+    }];
+    collection
+        .update_many(doc! { field: { "$type": "date" } }, UpdateModifications::Pipeline(pipeline), None)
+        .await?;
+    Ok(())
+}
+
+pub(super) fn up(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move {
+        let events = database.collection::<Document>("events");
+        for &field in DATE_FIELDS {
+            strings_to_dates(&events, field).await?;
+        }
+        Ok(())
+    })
+}
+
+pub(super) fn down(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move {
+        let events = database.collection::<Document>("events");
+        for &field in DATE_FIELDS {
+            dates_to_strings(&events, field).await?;
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,131 @@
+//! Migration 1: the indexes the backend queries rely on
+
+use super::MigrationResult;
+use futures::future::BoxFuture;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::error::{Error, ErrorKind};
+use mongodb::options::IndexOptions;
+use mongodb::{Database, IndexModel};
+
+pub(super) fn index(keys: Document) -> IndexModel {
+    IndexModel::builder().keys(keys).build()
+}
+
+pub(super) fn unique(keys: Document) -> IndexModel {
+    IndexModel::builder()
+        .keys(keys)
+        .options(IndexOptions::builder().unique(true).build())
+        .build()
+}
+
+/// Indexes by collection
+pub(super) fn definitions() -> Vec<(&'static str, Vec<IndexModel>)> {
+    vec![
+        ("events", vec![
+            unique(doc! { "event_id": 1 }),
+            index(doc! { "status": 1, "start_date": -1 }),
+            index(doc! { "format": 1, "start_date": -1 }),
+        ]),
+        ("standings", vec![
+            unique(doc! { "event_id": 1, "player_id": 1 }),
+            index(doc! { "event_id": 1, "rank": 1 }),
+            index(doc! { "champion": 1 }),
+            index(doc! { "player_id": 1 }),
+            index(doc! { "player_name": 1 }),
+        ]),
+        ("decklists", vec![
+            unique(doc! { "event_id": 1, "player_id": 1 }),
+            index(doc! { "champion": 1 }),
+            index(doc! { "player_id": 1 }),
+        ]),
+        ("champions", vec![
+            unique(doc! { "slug": 1 }),
+        ]),
+        ("cards", vec![
+            unique(doc! { "slug": 1 }),
+            index(doc! { "name": 1 }),
+            IndexModel::builder()
+                .keys(doc! { "name": "text", "card_text": "text" })
+                .options(
+                    IndexOptions::builder()
+                        .weights(doc! { "name": 10, "card_text": 1 })
+                        .build(),
+                )
+                .build(),
+            index(doc! { "element": 1, "card_type": 1 }),
+        ]),
+        ("player_ratings", vec![
+            unique(doc! { "pool": 1, "player_id": 1 }),
+            index(doc! { "pool": 1, "rating": -1 }),
+        ]),
+        ("rating_history", vec![
+            index(doc! { "player_id": 1, "pool": 1, "event_date": 1 }),
+            index(doc! { "pool": 1, "event_id": 1 }),
+        ]),
+        ("users", vec![
+            unique(doc! { "email": 1 }),
+            unique(doc! { "username": 1 }),
+        ]),
+        ("api_keys", vec![
+            unique(doc! { "key_id": 1 }),
+            index(doc! { "owner_id": 1 }),
+        ]),
+        ("saved_decklists", vec![
+            index(doc! { "user_id": 1, "updated_at": -1 }),
+            index(doc! { "user_id": 1, "deck_hash": 1 }),
+        ]),
+        ("crawler_state", vec![
+            index(doc! { "last_crawl": -1 }),
+        ]),
+    ]
+}
+
+/// The name MongoDB gives an index created without one, e.g. `event_id_1_rank_1`
+pub(super) fn default_name(keys: &Document) -> String {
+    keys.iter()
+        .map(|(field, value)| match value {
+            Bson::String(kind) => format!("{}_{}", field, kind),
+            other => format!("{}_{}", field, other),
+        })
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Whether an error only says the index or collection is already gone
+pub(super) fn is_missing(error: &Error) -> bool {
+    // 26: NamespaceNotFound, 27: IndexNotFound
+    matches!(*error.kind, ErrorKind::Command(ref e) if e.code == 26 || e.code == 27)
+}
+
+/// Create every index in `definitions`
+pub(super) async fn create(database: &Database, definitions: Vec<(&'static str, Vec<IndexModel>)>) -> MigrationResult {
+    for (collection, models) in definitions {
+        database
+            .collection::<Document>(collection)
+            .create_indexes(models, None)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Drop every index in `definitions`, ignoring ones already gone
+pub(super) async fn drop(database: &Database, definitions: Vec<(&'static str, Vec<IndexModel>)>) -> MigrationResult {
+    for (collection, models) in definitions {
+        let collection = database.collection::<Document>(collection);
+        for model in models {
+            match collection.drop_index(default_name(&model.keys), None).await {
+                Err(e) if !is_missing(&e) => return Err(e.into()),
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn up(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move { create(&database, definitions()).await })
+}
+
+pub(super) fn down(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move { drop(&database, definitions()).await })
+}
@@ -0,0 +1,26 @@
+//! Migration 4: indexes for champion lineages
+
+use super::indexes::{self, index, unique};
+use super::MigrationResult;
+use futures::future::BoxFuture;
+use mongodb::bson::doc;
+use mongodb::{Database, IndexModel};
+
+pub(super) fn definitions() -> Vec<(&'static str, Vec<IndexModel>)> {
+    vec![
+        ("champion_lineages", vec![
+            unique(doc! { "family": 1 }),
+        ]),
+        ("champions", vec![
+            index(doc! { "family": 1 }),
+        ]),
+    ]
+}
+
+pub(super) fn up(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move { indexes::create(&database, definitions()).await })
+}
+
+pub(super) fn down(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move { indexes::drop(&database, definitions()).await })
+}
@@ -0,0 +1,16 @@
+//! Migration 3: move material cards out of the sideboard into the material
+//! deck and record the champion lineage (decklist schema version 2)
+
+use super::MigrationResult;
+use crate::services::DeckStatsService;
+use futures::future::BoxFuture;
+use log::info;
+use mongodb::Database;
+
+pub(super) fn up(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move {
+        let upgraded = DeckStatsService::new(database).backfill(2).await?;
+        info!("Separated the material deck of {} decklists", upgraded);
+        Ok(())
+    })
+}
@@ -0,0 +1,421 @@
+//! Versioned schema migrations. Each applied version is recorded in the
+//! `schema_migrations` collection. `serve` applies pending migrations on
+//! startup (unless `MIGRATE_ON_STARTUP=false`) and the `migrate` command
+//! applies or reverts them explicitly.
+//!
+//! A migration is recorded only after it completes, so one that fails
+//! halfway runs again in full: write every step to be safe to repeat.
+
+mod alias_indexes;
+mod card_frequencies;
+mod card_revision_indexes;
+mod decklist_stats;
+mod event_dates;
+mod indexes;
+mod lineage_indexes;
+mod material_deck;
+mod printings;
+mod saved_material_deck;
+mod set_release_dates;
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use futures::stream::TryStreamExt;
+use log::{info, warn};
+use mongodb::bson::doc;
+use mongodb::options::{FindOptions, IndexOptions};
+use mongodb::{Collection, Database, IndexModel};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::time::Instant;
+use thiserror::Error;
+
+/// Collection recording applied migrations
+pub const MIGRATIONS_COLLECTION: &str = "schema_migrations";
+
+pub type MigrationResult = Result<(), MigrationError>;
+
+type Step = fn(Database) -> BoxFuture<'static, MigrationResult>;
+
+/// A single schema or data change
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    up: Step,
+    down: Option<Step>,
+}
+
+/// Every migration in version order. Append new ones at the end; never
+/// renumber or change a migration once it has been released.
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_indexes",
+        up: indexes::up,
+        down: Some(indexes::down),
+    },
+    Migration {
+        version: 2,
+        name: "decklist_deck_stats",
+        up: decklist_stats::up,
+        down: Some(decklist_stats::down),
+    },
+    Migration {
+        version: 3,
+        name: "decklist_material_deck",
+        up: material_deck::up,
+        // Material cards cannot be told apart again once merged back into the sideboard
+        down: None,
+    },
+    Migration {
+        version: 4,
+        name: "champion_lineage_indexes",
+        up: lineage_indexes::up,
+        down: Some(lineage_indexes::down),
+    },
+    Migration {
+        version: 5,
+        name: "champion_alias_indexes",
+        up: alias_indexes::up,
+        down: Some(alias_indexes::down),
+    },
+    Migration {
+        version: 6,
+        name: "card_revision_indexes",
+        up: card_revision_indexes::up,
+        down: Some(card_revision_indexes::down),
+    },
+    Migration {
+        version: 7,
+        name: "card_printings",
+        up: printings::up,
+        down: Some(printings::down),
+    },
+    Migration {
+        version: 8,
+        name: "event_dates_as_datetimes",
+        up: event_dates::up,
+        down: Some(event_dates::down),
+    },
+    Migration {
+        version: 9,
+        name: "decklist_material_card_frequencies",
+        up: card_frequencies::up,
+        down: Some(card_frequencies::down),
+    },
+    Migration {
+        version: 10,
+        name: "set_release_dates_as_datetimes",
+        up: set_release_dates::up,
+        down: Some(set_release_dates::down),
+    },
+    Migration {
+        version: 11,
+        name: "saved_decklist_material_deck",
+        up: saved_material_deck::up,
+        down: Some(saved_material_deck::down),
+    },
+];
+
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error("Database error: {0}")]
+    Database(#[from] mongodb::error::Error),
+
+    #[error("Migration {0} ({1}) cannot be reverted")]
+    Irreversible(i32, &'static str),
+
+    #[error("Applied migration {0} is not known to this build")]
+    Unknown(i32),
+
+    #[error("There is no migration {0}")]
+    UnknownTarget(i32),
+}
+
+/// An applied migration as stored in `schema_migrations`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationRecord {
+    pub version: i32,
+    pub name: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub applied_at: DateTime<Utc>,
+    pub duration_ms: i64,
+}
+
+/// A known or applied migration and when it was applied
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub version: i32,
+    pub name: String,
+    pub applied_at: Option<DateTime<Utc>>,
+    /// Applied, but missing from this build
+    pub unknown: bool,
+}
+
+fn check_target(migrations: &[Migration], target: i32) -> MigrationResult {
+    if target != 0 && !migrations.iter().any(|m| m.version == target) {
+        return Err(MigrationError::UnknownTarget(target));
+    }
+    Ok(())
+}
+
+/// Migrations to apply, in order, to reach `target` (default: the latest)
+fn pending<'a>(
+    migrations: &'a [Migration],
+    applied: &BTreeSet<i32>,
+    target: Option<i32>,
+) -> Result<Vec<&'a Migration>, MigrationError> {
+    if let Some(target) = target {
+        check_target(migrations, target)?;
+    }
+
+    Ok(migrations
+        .iter()
+        .filter(|m| !applied.contains(&m.version))
+        .filter(|m| target.is_none_or(|target| m.version <= target))
+        .collect())
+}
+
+/// Migrations to revert, newest first, to go back to `target` (0 reverts everything)
+fn to_revert<'a>(
+    migrations: &'a [Migration],
+    applied: &BTreeSet<i32>,
+    target: i32,
+) -> Result<Vec<&'a Migration>, MigrationError> {
+    check_target(migrations, target)?;
+
+    applied
+        .iter()
+        .rev()
+        .filter(|&&version| version > target)
+        .map(|&version| {
+            let migration = migrations
+                .iter()
+                .find(|m| m.version == version)
+                .ok_or(MigrationError::Unknown(version))?;
+            match migration.down {
+                Some(_) => Ok(migration),
+                None => Err(MigrationError::Irreversible(migration.version, migration.name)),
+            }
+        })
+        .collect()
+}
+
+/// Every index defined by the index migrations, in version order
+fn index_definitions() -> Vec<(&'static str, Vec<IndexModel>)> {
+    [
+        indexes::definitions(),
+        lineage_indexes::definitions(),
+        alias_indexes::definitions(),
+        card_revision_indexes::definitions(),
+        printings::definitions(),
+    ]
+    .concat()
+}
+
+/// Applies and reverts migrations against a database
+pub struct Migrator {
+    database: Database,
+    migrations: &'static [Migration],
+}
+
+impl Migrator {
+    pub fn new(database: Database) -> Self {
+        Self {
+            database,
+            migrations: MIGRATIONS,
+        }
+    }
+
+    fn records(&self) -> Collection<MigrationRecord> {
+        self.database.collection(MIGRATIONS_COLLECTION)
+    }
+
+    /// Applied migrations in version order
+    pub async fn applied(&self) -> Result<Vec<MigrationRecord>, MigrationError> {
+        let options = FindOptions::builder().sort(doc! { "version": 1 }).build();
+        let records = self.records().find(None, options).await?.try_collect().await?;
+        Ok(records)
+    }
+
+    async fn applied_versions(&self) -> Result<BTreeSet<i32>, MigrationError> {
+        Ok(self.applied().await?.into_iter().map(|r| r.version).collect())
+    }
+
+    /// Every known migration plus any applied one this build does not know
+    pub async fn status(&self) -> Result<Vec<MigrationStatus>, MigrationError> {
+        let applied = self.applied().await?;
+
+        let mut status: Vec<MigrationStatus> = self
+            .migrations
+            .iter()
+            .map(|m| MigrationStatus {
+                version: m.version,
+                name: m.name.to_string(),
+                applied_at: applied.iter().find(|r| r.version == m.version).map(|r| r.applied_at),
+                unknown: false,
+            })
+            .collect();
+
+        for record in applied {
+            if !self.migrations.iter().any(|m| m.version == record.version) {
+                status.push(MigrationStatus {
+                    version: record.version,
+                    name: record.name,
+                    applied_at: Some(record.applied_at),
+                    unknown: true,
+                });
+            }
+        }
+
+        status.sort_by_key(|s| s.version);
+        Ok(status)
+    }
+
+    /// Apply pending migrations up to `target` (default: the latest).
+    /// With `dry_run` nothing is changed; either way the migrations are returned.
+    pub async fn up(&self, target: Option<i32>, dry_run: bool) -> Result<Vec<&'static Migration>, MigrationError> {
+        let applied = self.applied_versions().await?;
+
+        let latest = self.migrations.last().map_or(0, |m| m.version);
+        for version in applied.iter().filter(|&&v| v > latest) {
+            warn!("Migration {} is applied but not known to this build", version);
+        }
+
+        let plan = pending(self.migrations, &applied, target)?;
+        if dry_run || plan.is_empty() {
+            return Ok(plan);
+        }
+
+        // Guards against two processes recording the same migration
+        self.records()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "version": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+                None,
+            )
+            .await?;
+
+        for migration in &plan {
+            info!("Applying migration {} ({})", migration.version, migration.name);
+            let started = Instant::now();
+
+            (migration.up)(self.database.clone()).await?;
+
+            self.records()
+                .insert_one(
+                    MigrationRecord {
+                        version: migration.version,
+                        name: migration.name.to_string(),
+                        applied_at: Utc::now(),
+                        duration_ms: started.elapsed().as_millis() as i64,
+                    },
+                    None,
+                )
+                .await?;
+        }
+
+        Ok(plan)
+    }
+
+    /// Revert applied migrations newer than `target`, newest first. Nothing
+    /// is reverted if any of them cannot be.
+    pub async fn down(&self, target: i32, dry_run: bool) -> Result<Vec<&'static Migration>, MigrationError> {
+        let applied = self.applied_versions().await?;
+
+        let plan = to_revert(self.migrations, &applied, target)?;
+        if dry_run {
+            return Ok(plan);
+        }
+
+        for migration in &plan {
+            info!("Reverting migration {} ({})", migration.version, migration.name);
+
+            if let Some(down) = migration.down {
+                down(self.database.clone()).await?;
+            }
+            self.records().delete_one(doc! { "version": migration.version }, None).await?;
+        }
+
+        Ok(plan)
+    }
+
+    /// Create every index the migrations define, whether or not they are
+    /// applied, without recording anything. Existing indexes are left as they
+    /// are. Returns the number of indexes ensured.
+    pub async fn ensure_indexes(&self) -> Result<usize, MigrationError> {
+        let definitions = index_definitions();
+        let count = definitions.iter().map(|(_, models)| models.len()).sum();
+        indexes::create(&self.database, definitions).await?;
+        Ok(count)
+    }
+
+    /// Target version that reverts the newest `steps` applied migrations
+    pub async fn steps_target(&self, steps: usize) -> Result<i32, MigrationError> {
+        let applied = self.applied_versions().await?;
+        Ok(applied.iter().rev().nth(steps).copied().unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noop(_: Database) -> BoxFuture<'static, MigrationResult> {
+        Box::pin(async { Ok(()) })
+    }
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration { version: 1, name: "one", up: noop, down: Some(noop) },
+        Migration { version: 2, name: "two", up: noop, down: None },
+        Migration { version: 3, name: "three", up: noop, down: Some(noop) },
+    ];
+
+    fn versions(plan: &[&Migration]) -> Vec<i32> {
+        plan.iter().map(|m| m.version).collect()
+    }
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+        assert!(MIGRATIONS.iter().all(|m| m.version > 0));
+    }
+
+    #[test]
+    fn test_pending() {
+        let applied = BTreeSet::from([1]);
+        assert_eq!(versions(&pending(TEST_MIGRATIONS, &applied, None).unwrap()), [2, 3]);
+        assert_eq!(versions(&pending(TEST_MIGRATIONS, &applied, Some(2)).unwrap()), [2]);
+        assert!(pending(TEST_MIGRATIONS, &applied, Some(9)).is_err());
+    }
+
+    #[test]
+    fn test_to_revert() {
+        let applied = BTreeSet::from([1, 2, 3]);
+        assert_eq!(versions(&to_revert(TEST_MIGRATIONS, &applied, 2).unwrap()), [3]);
+        assert!(matches!(
+            to_revert(TEST_MIGRATIONS, &applied, 0),
+            Err(MigrationError::Irreversible(2, "two"))
+        ));
+        assert!(matches!(
+            to_revert(TEST_MIGRATIONS, &BTreeSet::from([1, 4]), 1),
+            Err(MigrationError::Unknown(4))
+        ));
+    }
+
+    #[test]
+    fn test_index_definitions() {
+        let collections: BTreeSet<&str> = index_definitions().iter().map(|(collection, _)| *collection).collect();
+        assert!(collections.contains("events"));
+        assert!(collections.contains("champion_lineages"));
+        assert!(collections.contains(crate::services::card_sync::PRINTINGS_COLLECTION));
+    }
+
+    #[test]
+    fn test_default_index_name() {
+        assert_eq!(indexes::default_name(&doc! { "event_id": 1, "rank": -1 }), "event_id_1_rank_-1");
+        assert_eq!(indexes::default_name(&doc! { "name": "text", "card_text": "text" }), "name_text_card_text_text");
+    }
+}
@@ -0,0 +1,38 @@
+//! Migration 7: printings and sets. Catalogue pages synced before printings
+//! were stored would answer `304`, so their validators are dropped and the
+//! next catalogue sync refetches every page.
+
+use super::indexes::{self, index, unique};
+use super::MigrationResult;
+use crate::services::card_sync::{CATALOGUE_PAGES_COLLECTION, PRINTINGS_COLLECTION, SETS_COLLECTION};
+use futures::future::BoxFuture;
+use mongodb::bson::{doc, Document};
+use mongodb::{Database, IndexModel};
+
+pub(super) fn definitions() -> Vec<(&'static str, Vec<IndexModel>)> {
+    vec![
+        (PRINTINGS_COLLECTION, vec![
+            unique(doc! { "slug": 1 }),
+            index(doc! { "card_slug": 1 }),
+            index(doc! { "set_prefix": 1 }),
+        ]),
+        (SETS_COLLECTION, vec![
+            unique(doc! { "prefix": 1 }),
+        ]),
+    ]
+}
+
+pub(super) fn up(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move {
+        indexes::create(&database, definitions()).await?;
+        database
+            .collection::<Document>(CATALOGUE_PAGES_COLLECTION)
+            .delete_many(doc! {}, None)
+            .await?;
+        Ok(())
+    })
+}
+
+pub(super) fn down(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move { indexes::drop(&database, definitions()).await })
+}
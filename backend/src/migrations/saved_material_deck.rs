@@ -0,0 +1,39 @@
+//! Migration 11: give saved decklists a `material_deck`. Saved decks without
+//! one keep their `deck_hash`, since an empty material deck is not hashed.
+
+use super::MigrationResult;
+use futures::future::BoxFuture;
+use log::info;
+use mongodb::bson::{doc, Document};
+use mongodb::Database;
+
+pub(super) fn up(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move {
+        let result = database
+            .collection::<Document>("saved_decklists")
+            .update_many(
+                doc! { "material_deck": { "$exists": false } },
+                doc! { "$set": { "material_deck": [] } },
+                None,
+            )
+            .await?;
+        info!("Added an empty material deck to {} saved decklists", result.modified_count);
+        Ok(())
+    })
+}
+
+/// Material decks saved since cannot be moved anywhere, so only empty ones
+/// are removed
+pub(super) fn down(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move {
+        database
+            .collection::<Document>("saved_decklists")
+            .update_many(
+                doc! { "material_deck": { "$size": 0 } },
+                doc! { "$unset": { "material_deck": "" } },
+                None,
+            )
+            .await?;
+        Ok(())
+    })
+}
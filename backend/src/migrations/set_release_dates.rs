@@ -0,0 +1,17 @@
+//! Migration 10: store set `release_date` as a BSON datetime. The card sync
+//! wrote it as a string, which the set model cannot read.
+
+use super::event_dates::{dates_to_strings, strings_to_dates};
+use super::MigrationResult;
+use crate::services::card_sync::SETS_COLLECTION;
+use futures::future::BoxFuture;
+use mongodb::bson::Document;
+use mongodb::Database;
+
+pub(super) fn up(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move { strings_to_dates(&database.collection::<Document>(SETS_COLLECTION), "release_date").await })
+}
+
+pub(super) fn down(database: Database) -> BoxFuture<'static, MigrationResult> {
+    Box::pin(async move { dates_to_strings(&database.collection::<Document>(SETS_COLLECTION), "release_date").await })
+}
@@ -1,11 +1,17 @@
+use async_graphql::SimpleObject;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use bson::oid::ObjectId;
 use chrono::{DateTime, Utc};
+use super::PageMeta;
+use sha2::{Digest, Sha256};
 
 /// Represents a Grand Archive card
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct Card {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[graphql(skip)]
+    #[schema(value_type = Option<String>)]
     pub id: Option<ObjectId>,
 
     /// Unique slug identifier
@@ -42,6 +48,10 @@ pub struct Card {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub life_modifier: Option<i32>,
 
+    /// Champion level
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<i32>,
+
     /// Card text
     #[serde(skip_serializing_if = "Option::is_none")]
     pub card_text: Option<String>,
@@ -82,6 +92,12 @@ pub struct Card {
     #[serde(default)]
     pub banned_limited: bool,
 
+    /// Hash of the synced content, used to skip unchanged cards
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[graphql(skip)]
+    #[schema(ignore)]
+    pub content_hash: Option<String>,
+
     /// Last updated timestamp
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub updated_at: DateTime<Utc>,
@@ -102,6 +118,7 @@ impl Card {
             reserve_cost: None,
             power: None,
             life_modifier: None,
+            level: None,
             card_text: None,
             flavor_text: None,
             image_url: None,
@@ -112,13 +129,99 @@ impl Card {
             subtypes: None,
             banned_standard: false,
             banned_limited: false,
+            content_hash: None,
             updated_at: Utc::now(),
         }
     }
+
+    /// The synced fields as JSON, without the ID, hash and timestamp
+    fn content(&self) -> serde_json::Map<String, serde_json::Value> {
+        let content = Card {
+            id: None,
+            content_hash: None,
+            ..self.clone()
+        };
+        match serde_json::to_value(&content).expect("cards serialize to JSON") {
+            serde_json::Value::Object(mut fields) => {
+                fields.remove("updated_at");
+                fields
+            }
+            _ => unreachable!("cards serialize to JSON objects"),
+        }
+    }
+
+    /// SHA-256 of the card's content, ignoring its ID and timestamps
+    pub fn compute_content_hash(&self) -> String {
+        let bytes = serde_json::to_vec(&self.content()).expect("cards serialize to JSON");
+        hex::encode(Sha256::digest(bytes))
+    }
+
+    /// Fields whose content differs from `previous`, in field order
+    pub fn changes_from(&self, previous: &Card) -> Vec<CardFieldChange> {
+        let before = previous.content();
+        let after = self.content();
+
+        let mut fields: Vec<&String> = before.keys().chain(after.keys()).collect();
+        fields.sort();
+        fields.dedup();
+
+        fields
+            .into_iter()
+            .filter(|field| before.get(*field) != after.get(*field))
+            .map(|field| CardFieldChange {
+                field: field.clone(),
+                before: before.get(field).cloned(),
+                after: after.get(field).cloned(),
+            })
+            .collect()
+    }
+}
+
+/// A field changed by a card sync. Absent values were not set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CardFieldChange {
+    pub field: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<serde_json::Value>,
+}
+
+/// A change to a card's synced content, such as errata or a ban
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CardRevision {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<ObjectId>,
+
+    pub slug: String,
+
+    pub changes: Vec<CardFieldChange>,
+
+    /// Content hash before the change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_hash: Option<String>,
+
+    /// Content hash after the change
+    pub content_hash: String,
+
+    /// When the sync detected the change
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Response structure for card history queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CardHistoryResponse {
+    pub slug: String,
+    /// Newest first
+    pub revisions: Vec<CardRevision>,
+    #[serde(flatten)]
+    pub pagination: PageMeta,
 }
 
 /// Card performance metrics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CardPerformance {
     /// Card slug
     pub slug: String,
@@ -145,8 +248,98 @@ pub struct CardPerformance {
 }
 
 /// Response structure for card performance queries
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CardPerformanceResponse {
     pub cards: Vec<CardPerformance>,
     pub total: usize,
 }
+
+/// Usage of a card by a single champion
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChampionCardUsage {
+    /// Champion slug
+    pub champion: String,
+
+    /// Number of that champion's decklists including the card
+    pub deck_count: i32,
+}
+
+/// Tournament usage statistics for a single card
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CardUsage {
+    /// Number of decklists including this card
+    pub deck_count: i32,
+
+    /// Percentage of all decklists including this card
+    pub meta_percentage: f64,
+
+    /// Average quantity per deck that includes it
+    pub avg_quantity: f64,
+
+    /// Average placement of decks including this card
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_placement: Option<f64>,
+
+    /// Champions playing this card, most frequent first
+    pub champions: Vec<ChampionCardUsage>,
+}
+
+/// Response structure for card list/search queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CardListResponse {
+    pub cards: Vec<Card>,
+    #[serde(flatten)]
+    pub pagination: PageMeta,
+}
+
+/// Response structure for single card queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CardResponse {
+    pub card: Card,
+    pub usage: CardUsage,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hash_ignores_timestamps() {
+        let card = Card::new("zephyr".to_string(), "Zephyr".to_string());
+        let mut later = card.clone();
+        later.updated_at = Utc::now() + chrono::Duration::days(1);
+        later.content_hash = Some(card.compute_content_hash());
+        assert_eq!(card.compute_content_hash(), later.compute_content_hash());
+
+        later.card_text = Some("Errata".to_string());
+        assert_ne!(card.compute_content_hash(), later.compute_content_hash());
+    }
+
+    #[test]
+    fn test_changes_from() {
+        let mut before = Card::new("zephyr".to_string(), "Zephyr".to_string());
+        before.cost = Some(3);
+        let mut after = before.clone();
+        after.cost = Some(2);
+        after.card_text = Some("Errata".to_string());
+        after.updated_at = Utc::now() + chrono::Duration::days(1);
+
+        let changes = after.changes_from(&before);
+        assert_eq!(
+            changes,
+            [
+                CardFieldChange {
+                    field: "card_text".to_string(),
+                    before: None,
+                    after: Some(serde_json::json!("Errata")),
+                },
+                CardFieldChange {
+                    field: "cost".to_string(),
+                    before: Some(serde_json::json!(3)),
+                    after: Some(serde_json::json!(2)),
+                },
+            ]
+        );
+        assert!(before.changes_from(&before).is_empty());
+    }
+}
@@ -1,11 +1,17 @@
+use async_graphql::SimpleObject;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use bson::oid::ObjectId;
 use chrono::{DateTime, Utc};
+use super::PageMeta;
 
 /// Represents a champion/class in Grand Archive
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(complex)]
 pub struct Champion {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[graphql(skip)]
+    #[schema(value_type = Option<String>)]
     pub id: Option<ObjectId>,
 
     /// Unique slug identifier (e.g., "lorraine")
@@ -38,6 +44,14 @@ pub struct Champion {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub intellect: Option<i32>,
 
+    /// Champion level (0 for spirits)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<i32>,
+
+    /// Slug of the lineage family this champion belongs to (e.g. "lorraine")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+
     /// Last updated timestamp
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub updated_at: DateTime<Utc>,
@@ -57,20 +71,185 @@ impl Champion {
             ability_text: None,
             life: None,
             intellect: None,
+            level: None,
+            family: None,
             updated_at: Utc::now(),
         }
     }
 }
 
+/// Lowercase `text` and join its alphanumeric runs with `-`, so
+/// "Lorraine, Crux Knight" and "LORRAINE crux-knight" both become
+/// "lorraine-crux-knight"
+pub fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Lineage family of a champion card as `(slug, name)`: the part of the
+/// name before the comma, so "Lorraine, Crux Knight" belongs to "Lorraine".
+/// Level 0 spirits are shared by every lineage and belong to none.
+pub fn champion_family(name: &str, level: Option<i32>) -> Option<(String, String)> {
+    if level == Some(0) {
+        return None;
+    }
+
+    let family = name.split(',').next()?.trim();
+    if family.is_empty() {
+        return None;
+    }
+
+    Some((slugify(family), family.to_string()))
+}
+
+/// A champion card within a lineage
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct LineageMember {
+    pub slug: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<i32>,
+}
+
+/// Champion cards of one family across levels
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct ChampionLineage {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[graphql(skip)]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<ObjectId>,
+
+    /// Family slug (e.g. "lorraine")
+    pub family: String,
+
+    /// Family name (e.g. "Lorraine")
+    pub name: String,
+
+    /// Champion cards of the family, lowest level first
+    pub members: Vec<LineageMember>,
+
+    /// Last updated timestamp
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ChampionLineage {
+    /// Build a lineage from the champions of one family
+    pub fn from_champions(family: String, name: String, champions: &[Champion]) -> Self {
+        let mut members: Vec<LineageMember> = champions
+            .iter()
+            .map(|c| LineageMember {
+                slug: c.slug.clone(),
+                name: c.name.clone(),
+                level: c.level,
+            })
+            .collect();
+        members.sort_by(|a, b| a.level.unwrap_or(i32::MAX).cmp(&b.level.unwrap_or(i32::MAX)).then_with(|| a.slug.cmp(&b.slug)));
+
+        Self {
+            id: None,
+            family,
+            name,
+            members,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Maps an upstream champion string to a canonical champion slug
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChampionAlias {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<ObjectId>,
+
+    /// Slugified upstream string (e.g. "lorraine-cruxknight")
+    pub alias: String,
+
+    /// Canonical champion slug (e.g. "lorraine-crux-knight")
+    pub slug: String,
+
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// An upstream champion string that matched no champion or alias
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UnresolvedChampion {
+    /// The string as stored in standings or decklists
+    pub value: String,
+
+    /// Distinct standing and decklist rows (event and player) it was seen in
+    pub occurrences: i64,
+
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Response structure for alias list queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChampionAliasListResponse {
+    pub aliases: Vec<ChampionAlias>,
+    pub unresolved: Vec<UnresolvedChampion>,
+}
+
+/// Response structure for lineage list queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChampionLineageListResponse {
+    pub lineages: Vec<ChampionLineage>,
+    pub total: usize,
+}
+
 /// Response structure for champion list queries
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ChampionListResponse {
     pub champions: Vec<Champion>,
-    pub total: usize,
+    #[serde(flatten)]
+    pub pagination: PageMeta,
 }
 
 /// Response structure for single champion queries
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ChampionResponse {
     pub champion: Champion,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_champion_family() {
+        assert_eq!(
+            champion_family("Lorraine, Crux Knight", Some(3)),
+            Some(("lorraine".to_string(), "Lorraine".to_string()))
+        );
+        assert_eq!(
+            champion_family("Tristan, Hired Blade", None).map(|(slug, _)| slug),
+            Some("tristan".to_string())
+        );
+        assert_eq!(champion_family("Spirit of Fire", Some(0)), None);
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Lorraine, Crux Knight"), "lorraine-crux-knight");
+        assert_eq!(slugify("  LORRAINE_crux--knight "), "lorraine-crux-knight");
+        assert_eq!(slugify("lorraine"), "lorraine");
+    }
+
+    #[test]
+    fn test_lineage_members_ordered_by_level() {
+        let mut crux = Champion::new("lorraine-crux-knight".to_string(), "Lorraine, Crux Knight".to_string());
+        crux.level = Some(3);
+        let mut wandering = Champion::new("lorraine-wandering".to_string(), "Lorraine, Wandering".to_string());
+        wandering.level = Some(1);
+
+        let lineage = ChampionLineage::from_champions("lorraine".to_string(), "Lorraine".to_string(), &[crux, wandering]);
+        let slugs: Vec<&str> = lineage.members.iter().map(|m| m.slug.as_str()).collect();
+        assert_eq!(slugs, ["lorraine-wandering", "lorraine-crux-knight"]);
+    }
+}
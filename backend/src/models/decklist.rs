@@ -1,10 +1,22 @@
+use async_graphql::SimpleObject;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use bson::oid::ObjectId;
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use super::{Card, PageMeta};
+use std::collections::{BTreeMap, HashMap};
+
+/// Current layout version of decklist documents. Documents with a lower
+/// `schema_version` are upgraded by a backfill migration: version 1 added
+/// deck statistics, version 2 the separate material deck.
+pub const DECKLIST_SCHEMA_VERSION: i32 = 2;
+
+/// Card types that belong in the material deck rather than the main deck
+const MATERIAL_CARD_TYPES: &[&str] = &["CHAMPION", "REGALIA"];
 
 /// Card entry in a decklist
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(complex)]
 pub struct DecklistCard {
     /// Card slug
     pub slug: String,
@@ -26,12 +38,101 @@ pub struct DecklistCard {
     /// Card cost
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cost: Option<i32>,
+
+    /// Champion level, for champion cards in the material deck
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<i32>,
+}
+
+impl DecklistCard {
+    /// Whether the card is a champion card
+    pub fn is_champion(&self) -> bool {
+        self.card_type.as_deref().is_some_and(|t| t.eq_ignore_ascii_case("CHAMPION"))
+    }
+
+    /// Whether the card belongs in the material deck (champions and regalia)
+    pub fn is_material(&self) -> bool {
+        self.card_type
+            .as_deref()
+            .is_some_and(|t| MATERIAL_CARD_TYPES.iter().any(|m| t.eq_ignore_ascii_case(m)))
+    }
+}
+
+/// Main deck statistics computed from the card catalogue
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct DeckStats {
+    /// Total main deck cards
+    pub total_cards: i32,
+
+    /// Average memory cost of the cards that have one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_cost: Option<f64>,
+
+    /// Card count per element
+    pub element_counts: BTreeMap<String, i32>,
+
+    /// Card count per card type
+    pub type_counts: BTreeMap<String, i32>,
+
+    /// Card count per memory cost, indexed by cost
+    pub cost_curve: Vec<i32>,
+
+    /// Cards missing from the catalogue, counted from their decklist entry instead
+    pub uncatalogued_cards: i32,
+}
+
+impl DeckStats {
+    /// Compute statistics of `cards`, preferring catalogue data over the
+    /// card details stored in the decklist
+    pub fn compute(cards: &[DecklistCard], catalogue: &HashMap<String, Card>) -> Self {
+        let mut stats = DeckStats::default();
+        let mut cost_total = 0;
+        let mut costed_cards = 0;
+
+        for entry in cards {
+            let quantity = entry.quantity.max(0);
+            let card = catalogue.get(&entry.slug);
+            if card.is_none() {
+                stats.uncatalogued_cards += quantity;
+            }
+
+            let element = card.and_then(|c| c.element.as_ref()).or(entry.element.as_ref());
+            let card_type = card.and_then(|c| c.card_type.as_ref()).or(entry.card_type.as_ref());
+            let cost = card.and_then(|c| c.cost).or(entry.cost);
+
+            stats.total_cards += quantity;
+            if let Some(element) = element {
+                *stats.element_counts.entry(element.clone()).or_insert(0) += quantity;
+            }
+            if let Some(card_type) = card_type {
+                *stats.type_counts.entry(card_type.clone()).or_insert(0) += quantity;
+            }
+            if let Some(cost) = cost.filter(|&cost| cost >= 0) {
+                let index = cost as usize;
+                if stats.cost_curve.len() <= index {
+                    stats.cost_curve.resize(index + 1, 0);
+                }
+                stats.cost_curve[index] += quantity;
+                cost_total += cost * quantity;
+                costed_cards += quantity;
+            }
+        }
+
+        if costed_cards > 0 {
+            stats.average_cost = Some(f64::from(cost_total) / f64::from(costed_cards));
+        }
+
+        stats
+    }
 }
 
 /// Represents a player's decklist for an event
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(complex)]
 pub struct Decklist {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[graphql(skip)]
+    #[schema(value_type = Option<String>)]
     pub id: Option<ObjectId>,
 
     /// Reference to the event
@@ -52,9 +153,17 @@ pub struct Decklist {
     /// Main deck cards (60 cards)
     pub main_deck: Vec<DecklistCard>,
 
-    /// Sideboard/Materialdeck cards (up to 15 cards)
+    /// Sideboard cards (up to 15 cards)
     pub sideboard: Vec<DecklistCard>,
 
+    /// Material deck cards: champions and regalia
+    #[serde(default)]
+    pub material_deck: Vec<DecklistCard>,
+
+    /// Champion card slugs in the material deck, lowest level first
+    #[serde(default)]
+    pub champion_lineage: Vec<String>,
+
     /// Total cards in main deck
     pub main_deck_count: i32,
 
@@ -63,8 +172,18 @@ pub struct Decklist {
 
     /// Card frequency map for analysis (slug -> quantity)
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[graphql(skip)]
     pub card_frequencies: Option<HashMap<String, i32>>,
 
+    /// Main deck statistics
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deck_stats: Option<DeckStats>,
+
+    /// Document layout version; 0 until `upgrade` has run, including
+    /// documents written before versioning
+    #[serde(default)]
+    pub schema_version: i32,
+
     /// Last updated timestamp
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub updated_at: DateTime<Utc>,
@@ -89,9 +208,13 @@ impl Decklist {
             rank,
             main_deck: Vec::new(),
             sideboard: Vec::new(),
+            material_deck: Vec::new(),
+            champion_lineage: Vec::new(),
             main_deck_count: 0,
             sideboard_count: 0,
             card_frequencies: None,
+            deck_stats: None,
+            schema_version: 0,
             updated_at: Utc::now(),
         }
     }
@@ -108,9 +231,54 @@ impl Decklist {
             *frequencies.entry(card.slug.clone()).or_insert(0) += card.quantity;
         }
 
+        for card in &self.material_deck {
+            *frequencies.entry(card.slug.clone()).or_insert(0) += card.quantity;
+        }
+
         self.card_frequencies = Some(frequencies);
     }
 
+    /// Move material cards listed in the sideboard into the material deck
+    /// and derive the champion lineage. Card types missing from the entries
+    /// are taken from the catalogue.
+    pub fn separate_material_deck(&mut self, catalogue: &HashMap<String, Card>) {
+        for card in self.sideboard.iter_mut().chain(self.material_deck.iter_mut()) {
+            if card.card_type.is_none() {
+                card.card_type = catalogue.get(&card.slug).and_then(|c| c.card_type.clone());
+            }
+        }
+
+        let (material, sideboard): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.sideboard).into_iter().partition(DecklistCard::is_material);
+        self.sideboard = sideboard;
+        self.material_deck.extend(material);
+        self.sideboard_count = self.sideboard.iter().map(|c| c.quantity).sum();
+
+        // Stable sort keeps the listed order for champions without a level
+        let mut champions: Vec<&DecklistCard> = self.material_deck.iter().filter(|c| c.is_champion()).collect();
+        champions.sort_by_key(|c| c.level.unwrap_or(i32::MAX));
+        self.champion_lineage = champions.into_iter().map(|c| c.slug.clone()).collect();
+    }
+
+    /// Compute deck statistics
+    pub fn calculate_stats(&mut self, catalogue: &HashMap<String, Card>) {
+        self.deck_stats = Some(DeckStats::compute(&self.main_deck, catalogue));
+    }
+
+    /// Bring the document up to the current schema version
+    pub fn upgrade(&mut self, catalogue: &HashMap<String, Card>) {
+        self.upgrade_to(DECKLIST_SCHEMA_VERSION, catalogue);
+    }
+
+    /// Bring the document up to schema `version`
+    pub fn upgrade_to(&mut self, version: i32, catalogue: &HashMap<String, Card>) {
+        if version >= 2 {
+            self.separate_material_deck(catalogue);
+        }
+        self.calculate_stats(catalogue);
+        self.schema_version = version;
+    }
+
     /// Validate deck size constraints
     pub fn is_valid(&self) -> bool {
         self.main_deck_count >= 60 && self.sideboard_count <= 15
@@ -118,14 +286,95 @@ impl Decklist {
 }
 
 /// Response structure for decklist queries
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DecklistListResponse {
     pub decklists: Vec<Decklist>,
-    pub total: usize,
+    #[serde(flatten)]
+    pub pagination: PageMeta,
 }
 
 /// Response structure for single decklist queries
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DecklistResponse {
     pub decklist: Decklist,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(slug: &str, quantity: i32, cost: Option<i32>) -> DecklistCard {
+        DecklistCard {
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            quantity,
+            card_type: Some("ACTION".to_string()),
+            element: Some("NORM".to_string()),
+            cost,
+            level: None,
+        }
+    }
+
+    #[test]
+    fn test_deck_stats_prefer_catalogue() {
+        let mut fireball = Card::new("fireball".to_string(), "Fireball".to_string());
+        fireball.element = Some("FIRE".to_string());
+        fireball.card_type = Some("ATTACK".to_string());
+        fireball.cost = Some(3);
+        let catalogue = HashMap::from([("fireball".to_string(), fireball)]);
+
+        let stats = DeckStats::compute(&[entry("fireball", 4, Some(1)), entry("spark", 2, Some(1))], &catalogue);
+
+        assert_eq!(stats.total_cards, 6);
+        assert_eq!(stats.element_counts["FIRE"], 4);
+        assert_eq!(stats.element_counts["NORM"], 2);
+        assert_eq!(stats.type_counts["ATTACK"], 4);
+        assert_eq!(stats.cost_curve, [0, 2, 0, 4]);
+        assert_eq!(stats.average_cost, Some(14.0 / 6.0));
+        assert_eq!(stats.uncatalogued_cards, 2);
+    }
+
+    #[test]
+    fn test_separate_material_deck() {
+        let mut decklist = Decklist::new(1, "p1".to_string(), "Jane".to_string(), "lorraine-crux-knight".to_string(), 1);
+        let champion = |slug: &str, level| DecklistCard {
+            card_type: Some("Champion".to_string()),
+            level,
+            ..entry(slug, 1, None)
+        };
+        let mut regalia = entry("crux-sight", 1, None);
+        regalia.card_type = None;
+        let mut catalogue_regalia = Card::new("crux-sight".to_string(), "Crux Sight".to_string());
+        catalogue_regalia.card_type = Some("REGALIA".to_string());
+        let catalogue = HashMap::from([("crux-sight".to_string(), catalogue_regalia)]);
+
+        decklist.sideboard = vec![champion("lorraine-crux-knight", Some(3)), entry("ward", 2, Some(1)), regalia];
+        decklist.material_deck = vec![champion("spirit-of-fire", Some(0)), champion("lorraine-wandering", Some(1))];
+        decklist.separate_material_deck(&catalogue);
+
+        assert_eq!(decklist.sideboard.len(), 1);
+        assert_eq!(decklist.sideboard_count, 2);
+        assert_eq!(decklist.material_deck.len(), 4);
+        assert_eq!(decklist.champion_lineage, ["spirit-of-fire", "lorraine-wandering", "lorraine-crux-knight"]);
+    }
+
+    #[test]
+    fn test_upgrade_to_version() {
+        let mut decklist = Decklist::new(1, "p1".to_string(), "Jane".to_string(), "lorraine".to_string(), 1);
+        decklist.sideboard = vec![DecklistCard {
+            card_type: Some("CHAMPION".to_string()),
+            ..entry("lorraine-wandering", 1, None)
+        }];
+
+        // Version 1 only adds deck statistics
+        decklist.upgrade_to(1, &HashMap::new());
+        assert_eq!(decklist.schema_version, 1);
+        assert!(decklist.deck_stats.is_some());
+        assert_eq!(decklist.sideboard.len(), 1);
+
+        decklist.upgrade(&HashMap::new());
+        assert_eq!(decklist.schema_version, DECKLIST_SCHEMA_VERSION);
+        assert!(decklist.sideboard.is_empty());
+        assert_eq!(decklist.champion_lineage, ["lorraine-wandering"]);
+    }
+}
@@ -1,9 +1,35 @@
+use async_graphql::{Enum, SimpleObject};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use bson::oid::ObjectId;
-use chrono::{DateTime, Utc};
+use bson::{doc, Bson, Document};
+use chrono::{DateTime, Duration, Utc};
+use super::PageMeta;
+
+/// Event dates are stored as BSON datetimes so range filters compare dates,
+/// while API responses render them as RFC 3339 strings. Events stored before
+/// the switch hold strings, which are still read.
+mod stored_date {
+    use super::*;
+    use serde::de::{Deserializer, Error};
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<Bson>::deserialize(deserializer)? {
+            None | Some(Bson::Null) => Ok(None),
+            Some(Bson::DateTime(date)) => Ok(Some(date.to_chrono())),
+            Some(Bson::String(value)) => DateTime::parse_from_rfc3339(&value)
+                .map(|date| Some(date.with_timezone(&Utc)))
+                .map_err(D::Error::custom),
+            Some(other) => Err(D::Error::custom(format!("expected a date, found {}", other))),
+        }
+    }
+}
 
 /// Event format type
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema, Enum)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum EventFormat {
     Standard,
@@ -15,21 +41,78 @@ pub enum EventFormat {
 }
 
 impl EventFormat {
+    /// Lenient conversion for upstream data; unrecognised formats become `Unknown`
     pub fn from_str(s: &str) -> Self {
-        match s.to_uppercase().as_str() {
-            "STANDARD" => EventFormat::Standard,
-            "LIMITED" => EventFormat::Limited,
-            "SEALED" => EventFormat::Sealed,
-            "DRAFT" => EventFormat::Draft,
-            _ => EventFormat::Unknown,
+        Self::parse(s).unwrap_or(EventFormat::Unknown)
+    }
+
+    /// Strict conversion for user input; `None` for unrecognised formats
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "STANDARD" => Some(EventFormat::Standard),
+            "LIMITED" => Some(EventFormat::Limited),
+            "SEALED" => Some(EventFormat::Sealed),
+            "DRAFT" => Some(EventFormat::Draft),
+            _ => None,
         }
     }
+
+    /// Serialized name, e.g. `STANDARD`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventFormat::Standard => "STANDARD",
+            EventFormat::Limited => "LIMITED",
+            EventFormat::Sealed => "SEALED",
+            EventFormat::Draft => "DRAFT",
+            EventFormat::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+/// Range of event start dates, inclusive at both ends; either bound may be open
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl DateRange {
+    /// Events that started within the last `days` days
+    pub fn last_days(days: i64) -> Self {
+        Self {
+            from: Some(Utc::now() - Duration::days(days)),
+            to: None,
+        }
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.from.is_none() && self.to.is_none()
+    }
+
+    /// MongoDB condition for `start_date`, or `None` when unbounded
+    pub fn condition(&self) -> Option<Document> {
+        if self.is_unbounded() {
+            return None;
+        }
+
+        let mut condition = doc! {};
+        if let Some(from) = self.from {
+            condition.insert("$gte", bson::DateTime::from_chrono(from));
+        }
+        if let Some(to) = self.to {
+            condition.insert("$lte", bson::DateTime::from_chrono(to));
+        }
+        Some(condition)
+    }
 }
 
 /// Represents a tournament event
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(complex)]
 pub struct Event {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[graphql(skip)]
+    #[schema(value_type = Option<String>)]
     pub id: Option<ObjectId>,
 
     /// Event ID from the Omnidex API
@@ -53,6 +136,7 @@ pub struct Event {
     /// Event start date
     #[serde(
         default,
+        deserialize_with = "stored_date::deserialize",
         skip_serializing_if = "Option::is_none"
     )]
     pub start_date: Option<DateTime<Utc>>,
@@ -60,6 +144,7 @@ pub struct Event {
     /// Event end date
     #[serde(
         default,
+        deserialize_with = "stored_date::deserialize",
         skip_serializing_if = "Option::is_none"
     )]
     pub end_date: Option<DateTime<Utc>>,
@@ -116,6 +201,18 @@ impl Event {
         }
     }
 
+    /// Document to store, with `start_date` and `end_date` as BSON datetimes
+    /// so `DateRange` conditions can match them
+    pub fn to_document(&self) -> bson::ser::Result<Document> {
+        let mut document = bson::to_document(self)?;
+        for (field, date) in [("start_date", self.start_date), ("end_date", self.end_date)] {
+            if let Some(date) = date {
+                document.insert(field, bson::DateTime::from_chrono(date));
+            }
+        }
+        Ok(document)
+    }
+
     /// Check if event is "interesting" for meta analysis
     /// Criteria: completed, ranked, and either has decklists or >60 players
     pub fn is_interesting(&self) -> bool {
@@ -126,14 +223,15 @@ impl Event {
 }
 
 /// Response structure for event list queries
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct EventListResponse {
     pub events: Vec<Event>,
-    pub total: usize,
+    #[serde(flatten)]
+    pub pagination: PageMeta,
 }
 
 /// Response structure for single event queries
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct EventResponse {
     pub event: Event,
 }
@@ -157,3 +255,51 @@ pub struct CrawlerState {
     /// Crawl type (e.g., "incremental", "full")
     pub crawl_type: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event_on(date: &str) -> Event {
+        let mut event = Event::new(1, "Test".to_string(), EventFormat::Standard, "complete".to_string());
+        event.start_date = Some(date.parse().unwrap());
+        event
+    }
+
+    #[test]
+    fn test_stored_dates_match_range_conditions() {
+        let document = event_on("2024-01-15T10:00:00Z").to_document().unwrap();
+        let stored = document.get("start_date").unwrap();
+
+        let range = DateRange {
+            from: Some("2024-01-01T00:00:00Z".parse().unwrap()),
+            to: Some("2024-01-31T23:59:59Z".parse().unwrap()),
+        };
+        let condition = range.condition().unwrap();
+
+        // MongoDB only compares values of the same type
+        for bound in ["$gte", "$lte"] {
+            assert_eq!(condition.get(bound).unwrap().element_type(), stored.element_type());
+        }
+        let stored = stored.as_datetime().unwrap();
+        assert!(condition.get_datetime("$gte").unwrap() <= stored);
+        assert!(stored <= condition.get_datetime("$lte").unwrap());
+        assert!(document.get("end_date").is_none());
+    }
+
+    #[test]
+    fn test_reads_stored_and_legacy_dates() {
+        let event = event_on("2024-01-15T10:00:00Z");
+
+        let stored: Event = bson::from_document(event.to_document().unwrap()).unwrap();
+        assert_eq!(stored.start_date, event.start_date);
+
+        // Written with bson::to_document before dates were stored as datetimes
+        let legacy: Event = bson::from_document(bson::to_document(&event).unwrap()).unwrap();
+        assert_eq!(legacy.start_date, event.start_date);
+
+        // API responses keep RFC 3339 strings
+        let json = serde_json::to_value(&stored).unwrap();
+        assert_eq!(json["start_date"], "2024-01-15T10:00:00Z");
+    }
+}
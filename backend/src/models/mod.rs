@@ -3,12 +3,44 @@ pub mod event;
 pub mod standing;
 pub mod decklist;
 pub mod card;
+pub mod pagination;
+pub mod player;
+pub mod printing;
+pub mod rating;
+pub mod saved_decklist;
+pub mod user;
 
-pub use champion::{Champion, ChampionListResponse, ChampionResponse};
-pub use event::{Event, EventFormat, EventListResponse, EventResponse, CrawlerState};
+pub use champion::{
+    champion_family, slugify, Champion, ChampionAlias, ChampionAliasListResponse, ChampionLineage,
+    ChampionLineageListResponse, ChampionListResponse, ChampionResponse, UnresolvedChampion,
+};
+pub use event::{DateRange, Event, EventFormat, EventListResponse, EventResponse, CrawlerState};
 pub use standing::{Standing, StandingListResponse};
-pub use decklist::{Decklist, DecklistCard, DecklistListResponse, DecklistResponse};
-pub use card::{Card, CardPerformance, CardPerformanceResponse};
+pub use decklist::{
+    Decklist, DecklistCard, DecklistListResponse, DecklistResponse, DECKLIST_SCHEMA_VERSION,
+};
+pub use card::{
+    Card, CardHistoryResponse, CardListResponse, CardPerformance, CardPerformanceResponse, CardResponse,
+    CardRevision, CardUsage, ChampionCardUsage,
+};
+pub use pagination::PageMeta;
+pub use printing::{parse_release_date, CardPrintingsResponse, CardSet, Printing, SetListResponse};
+pub use player::{
+    PlayerChampionUsage, PlayerEventResult, PlayerListResponse, PlayerProfile, PlayerRecord,
+    PlayerResponse, PlayerSummary,
+};
+pub use rating::{
+    rating_pool, LeaderboardResponse, PlayerRating, PlayerRatingResponse,
+    RatingHistoryEntry, OVERALL_RATING_POOL,
+};
+pub use saved_decklist::{
+    deck_hash, DecklistSource, SavedDecklist, SavedDecklistInput, SavedDecklistListResponse,
+    SavedDecklistResponse,
+};
+pub use user::{
+    ApiKey, ApiKeyCreatedResponse, ApiKeyInfo, ApiKeyListResponse, Role, TokenResponse, User,
+    UserProfile, UserResponse,
+};
 
 use thiserror::Error;
 
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Pagination metadata shared by all list responses
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PageMeta {
+    /// Total number of documents matching the query
+    pub total: u64,
+
+    /// Current page (1-based)
+    pub page: u64,
+
+    /// Number of items per page
+    pub page_size: u64,
+
+    /// Link to the next page, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+
+    /// Link to the previous page, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev: Option<String>,
+}
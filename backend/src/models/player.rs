@@ -0,0 +1,107 @@
+use async_graphql::SimpleObject;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use chrono::{DateTime, Utc};
+use super::{EventFormat, PageMeta};
+
+/// Lifetime match record for a player
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct PlayerRecord {
+    pub wins: i32,
+    pub losses: i32,
+    pub draws: i32,
+
+    /// Match win percentage across all events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_win_rate: Option<f64>,
+}
+
+/// A single tournament result in a player's history
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(complex)]
+pub struct PlayerEventResult {
+    pub event_id: i32,
+
+    /// Event name, if the event document exists
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<EventFormat>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_count: Option<i32>,
+
+    /// Champion slug played
+    pub champion: String,
+
+    /// Final placement
+    pub rank: i32,
+
+    pub wins: i32,
+    pub losses: i32,
+    pub draws: i32,
+
+    /// Link to the decklist, if one was published
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decklist_url: Option<String>,
+}
+
+/// How often and when a player used a champion
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct PlayerChampionUsage {
+    pub champion: String,
+    pub events: i32,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_played: Option<DateTime<Utc>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_played: Option<DateTime<Utc>>,
+}
+
+/// Aggregated player profile across all events
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct PlayerProfile {
+    pub player_id: String,
+
+    /// Most recently used player name
+    pub player_name: String,
+
+    pub events_played: i32,
+    pub record: PlayerRecord,
+
+    /// Champions played, most frequent first
+    pub champions: Vec<PlayerChampionUsage>,
+
+    /// Best placements, best first
+    pub best_finishes: Vec<PlayerEventResult>,
+
+    /// Full tournament history, most recent first
+    pub history: Vec<PlayerEventResult>,
+}
+
+/// Player search result entry
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PlayerSummary {
+    pub player_id: String,
+    pub player_name: String,
+    pub events_played: i32,
+}
+
+/// Response structure for player search queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PlayerListResponse {
+    pub players: Vec<PlayerSummary>,
+    #[serde(flatten)]
+    pub pagination: PageMeta,
+}
+
+/// Response structure for single player queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PlayerResponse {
+    pub player: PlayerProfile,
+}
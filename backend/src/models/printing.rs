@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// One printing of a card: its edition in a set, with the finishes it was
+/// produced in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Printing {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<ObjectId>,
+
+    /// Unique edition identifier
+    pub slug: String,
+
+    /// Slug of the printed card
+    pub card_slug: String,
+
+    /// Set prefix (e.g. "DOA")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set_prefix: Option<String>,
+
+    /// Set name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set_name: Option<String>,
+
+    /// Card number in set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collector_number: Option<String>,
+
+    /// Rarity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rarity: Option<String>,
+
+    /// Illustrator
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub illustrator: Option<String>,
+
+    /// Image of this printing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
+
+    /// Finishes the printing exists in (e.g. "nonfoil", "foil")
+    #[serde(default)]
+    pub finishes: Vec<String>,
+}
+
+/// A card set
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CardSet {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<ObjectId>,
+
+    /// Unique set prefix (e.g. "DOA")
+    pub prefix: String,
+
+    /// Set name (e.g. "Dawn of Ashes")
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// Release date, if announced
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub release_date: Option<DateTime<Utc>>,
+}
+
+/// Parse a release date given as a date ("2024-03-01") or an RFC 3339 timestamp
+pub fn parse_release_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc())
+}
+
+/// Response structure for set list queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SetListResponse {
+    /// Newest first; sets without a release date last
+    pub sets: Vec<CardSet>,
+    pub total: usize,
+}
+
+/// Response structure for card printing queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CardPrintingsResponse {
+    pub slug: String,
+    pub printings: Vec<Printing>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_release_date() {
+        let expected = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        assert_eq!(parse_release_date("2024-03-01"), Some(expected));
+        assert_eq!(parse_release_date("2024-03-01T00:00:00Z"), Some(expected));
+        assert_eq!(parse_release_date("soon"), None);
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use super::{EventFormat, PageMeta};
+
+/// Rating pool covering events of every format
+pub const OVERALL_RATING_POOL: &str = "ALL";
+
+/// Rating pool name for a format filter (`None` means all formats)
+pub fn rating_pool(format: Option<&EventFormat>) -> String {
+    match format {
+        Some(format) => mongodb::bson::to_bson(format)
+            .ok()
+            .and_then(|b| b.as_str().map(str::to_string))
+            .unwrap_or_else(|| OVERALL_RATING_POOL.to_string()),
+        None => OVERALL_RATING_POOL.to_string(),
+    }
+}
+
+/// Current Glicko-2 rating of a player within a rating pool
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PlayerRating {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<ObjectId>,
+
+    pub player_id: String,
+
+    /// Most recently seen player name
+    pub player_name: String,
+
+    /// Rating pool: "ALL" or an event format (e.g. "STANDARD")
+    pub pool: String,
+
+    /// Glicko-2 rating on the Glicko scale (starts at 1500)
+    pub rating: f64,
+
+    /// Rating deviation
+    pub rating_deviation: f64,
+
+    /// Rating volatility
+    pub volatility: f64,
+
+    /// Number of rated events
+    pub events_played: i32,
+
+    /// Number of rated games
+    pub games_played: i32,
+
+    /// Last rated event
+    pub last_event_id: i32,
+
+    /// Date of the last rated event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_played: Option<DateTime<Utc>>,
+
+    /// Last updated timestamp
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Rating change for a player caused by a single event
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RatingHistoryEntry {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<ObjectId>,
+
+    pub player_id: String,
+    pub pool: String,
+    pub event_id: i32,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_date: Option<DateTime<Utc>>,
+
+    /// Rating entering the event
+    pub rating_before: f64,
+
+    /// Rating deviation entering the event
+    pub deviation_before: f64,
+
+    pub rating_after: f64,
+    pub deviation_after: f64,
+    pub volatility_after: f64,
+
+    /// Games rated for this event
+    pub games: i32,
+
+    /// Points scored (win = 1, draw = 0.5)
+    pub score: f64,
+}
+
+/// Response structure for rating leaderboard queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LeaderboardResponse {
+    pub ratings: Vec<PlayerRating>,
+    #[serde(flatten)]
+    pub pagination: PageMeta,
+}
+
+/// Response structure for a player's rating and history
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PlayerRatingResponse {
+    pub rating: PlayerRating,
+    pub history: Vec<RatingHistoryEntry>,
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use super::{DecklistCard, PageMeta};
+
+/// Tournament decklist a saved deck was forked from
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DecklistSource {
+    pub event_id: i32,
+    pub player_id: String,
+}
+
+/// User-owned decklist
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SavedDecklist {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub id: Option<ObjectId>,
+
+    /// Owning user
+    #[schema(value_type = String)]
+    pub user_id: ObjectId,
+
+    /// Custom deck name
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Champion slug
+    pub champion_slug: String,
+
+    pub main_deck: Vec<DecklistCard>,
+
+    pub sideboard: Vec<DecklistCard>,
+
+    /// Champions and regalia, separate from the main deck and sideboard
+    #[serde(default)]
+    pub material_deck: Vec<DecklistCard>,
+
+    /// User-defined tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Hash of champion and card contents for duplicate detection
+    pub deck_hash: String,
+
+    /// Tournament decklist this deck was forked from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<DecklistSource>,
+
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Editable fields of a saved decklist, used for create and full update
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SavedDecklistInput {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub champion_slug: String,
+    pub main_deck: Vec<DecklistCard>,
+    #[serde(default)]
+    pub sideboard: Vec<DecklistCard>,
+    #[serde(default)]
+    pub material_deck: Vec<DecklistCard>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Compute the deck hash of a champion and card list. Card order, slug casing and
+/// split entries of the same card do not affect the hash. An empty material
+/// deck is left out, so decks saved before it existed keep their hash.
+pub fn deck_hash(
+    champion_slug: &str,
+    main_deck: &[DecklistCard],
+    sideboard: &[DecklistCard],
+    material_deck: &[DecklistCard],
+) -> String {
+    fn section(cards: &[DecklistCard]) -> BTreeMap<String, i32> {
+        let mut counts = BTreeMap::new();
+        for card in cards {
+            *counts.entry(card.slug.to_lowercase()).or_insert(0) += card.quantity;
+        }
+        counts.retain(|_, quantity| *quantity > 0);
+        counts
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(champion_slug.to_lowercase().as_bytes());
+
+    for (label, cards) in [("main", main_deck), ("side", sideboard), ("material", material_deck)] {
+        let counts = section(cards);
+        if label == "material" && counts.is_empty() {
+            continue;
+        }
+        hasher.update(format!("|{}", label).as_bytes());
+        for (slug, quantity) in counts {
+            hasher.update(format!("|{}:{}", slug, quantity).as_bytes());
+        }
+    }
+
+    hex::encode(hasher.finalize())
+}
+
+/// Response structure for saved decklist list queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SavedDecklistListResponse {
+    pub decklists: Vec<SavedDecklist>,
+    #[serde(flatten)]
+    pub pagination: PageMeta,
+}
+
+/// Response structure for single saved decklist queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SavedDecklistResponse {
+    pub decklist: SavedDecklist,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(slug: &str, quantity: i32) -> DecklistCard {
+        DecklistCard {
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            quantity,
+            card_type: None,
+            element: None,
+            cost: None,
+            level: None,
+        }
+    }
+
+    #[test]
+    fn test_deck_hash_ignores_order_and_splits() {
+        let a = deck_hash("lorraine", &[card("a", 4), card("b", 2)], &[card("c", 1)], &[]);
+        let b = deck_hash("lorraine", &[card("b", 1), card("a", 4), card("b", 1)], &[card("c", 1)], &[]);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_deck_hash_distinguishes_sections() {
+        let main = deck_hash("lorraine", &[card("a", 4), card("c", 1)], &[], &[]);
+        let side = deck_hash("lorraine", &[card("a", 4)], &[card("c", 1)], &[]);
+        let material = deck_hash("lorraine", &[card("a", 4)], &[], &[card("c", 1)]);
+        assert_ne!(main, side);
+        assert_ne!(side, material);
+        assert_ne!(main, material);
+    }
+
+    #[test]
+    fn test_deck_hash_without_material_deck_is_unchanged() {
+        // Hash of a deck saved before material decks were stored
+        assert_eq!(
+            deck_hash("lorraine", &[card("a", 4)], &[card("c", 1)], &[]),
+            deck_hash("lorraine", &[card("a", 4)], &[card("c", 1)], &[card("m", 0)]),
+        );
+        assert_eq!(
+            deck_hash("lorraine", &[card("a", 4)], &[card("c", 1)], &[]),
+            "772565ea0c0e50140113243ca7832548cbc94c259d5d78ad493a1b68508baaea"
+        );
+    }
+}
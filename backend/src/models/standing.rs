@@ -1,11 +1,17 @@
+use async_graphql::SimpleObject;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use bson::oid::ObjectId;
 use chrono::{DateTime, Utc};
+use super::PageMeta;
 
 /// Represents a player's standing in an event
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(complex)]
 pub struct Standing {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[graphql(skip)]
+    #[schema(value_type = Option<String>)]
     pub id: Option<ObjectId>,
 
     /// Reference to the event
@@ -79,8 +85,9 @@ impl Standing {
 }
 
 /// Response structure for standings list queries
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct StandingListResponse {
     pub standings: Vec<Standing>,
-    pub total: usize,
+    #[serde(flatten)]
+    pub pagination: PageMeta,
 }
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+
+/// Access role, ordered from least to most privileged
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Contributor,
+    Admin,
+}
+
+impl Role {
+    /// Whether this role grants at least the privileges of `required`
+    pub fn satisfies(&self, required: Role) -> bool {
+        *self >= required
+    }
+}
+
+/// Registered user account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Unique email address (stored lowercase)
+    pub email: String,
+
+    /// Unique display username
+    pub username: String,
+
+    /// Argon2 PHC-format password hash
+    pub password_hash: String,
+
+    pub role: Role,
+
+    /// Linked tournament player ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_id: Option<String>,
+
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_login_at: Option<DateTime<Utc>>,
+}
+
+/// API key issued to a service client. Only a hash of the secret is stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Public key identifier embedded in the key string
+    pub key_id: String,
+
+    /// SHA-256 hex digest of the full key
+    pub key_hash: String,
+
+    /// Human-readable label
+    pub name: String,
+
+    pub role: Role,
+
+    /// User that created the key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<ObjectId>,
+
+    #[serde(default)]
+    pub revoked: bool,
+
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+/// User fields that are safe to return from the API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserProfile {
+    pub id: String,
+    pub email: String,
+    pub username: String,
+    pub role: Role,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&User> for UserProfile {
+    fn from(user: &User) -> Self {
+        Self {
+            id: user.id.map(|id| id.to_hex()).unwrap_or_default(),
+            email: user.email.clone(),
+            username: user.username.clone(),
+            role: user.role,
+            player_id: user.player_id.clone(),
+            created_at: user.created_at,
+        }
+    }
+}
+
+/// API key fields that are safe to return from the API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyInfo {
+    pub key_id: String,
+    pub name: String,
+    pub role: Role,
+    pub revoked: bool,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+impl From<&ApiKey> for ApiKeyInfo {
+    fn from(key: &ApiKey) -> Self {
+        Self {
+            key_id: key.key_id.clone(),
+            name: key.name.clone(),
+            role: key.role,
+            revoked: key.revoked,
+            created_at: key.created_at,
+            last_used: key.last_used,
+        }
+    }
+}
+
+/// Response structure for login and registration
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: u64,
+    pub user: UserProfile,
+}
+
+/// Response structure for single user queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserResponse {
+    pub user: UserProfile,
+}
+
+/// Response structure for a newly created API key; the key is only shown once
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyCreatedResponse {
+    pub key: String,
+    pub api_key: ApiKeyInfo,
+}
+
+/// Response structure for API key list queries
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyListResponse {
+    pub api_keys: Vec<ApiKeyInfo>,
+    pub total: usize,
+}
@@ -0,0 +1,109 @@
+use crate::controllers;
+use crate::middleware::auth::API_KEY_HEADER;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+/// Path of the generated OpenAPI document
+pub const SPEC_PATH: &str = "/api/openapi.json";
+
+/// Registers the bearer token and API key security schemes
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+        );
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Grand Archive Meta API",
+        description = "Tournament results, meta analysis, card statistics and player ratings for Grand Archive TCG."
+    ),
+    tags(
+        (name = "health", description = "Service status"),
+        (name = "events", description = "Tournament events and standings"),
+        (name = "decklists", description = "Tournament decklists"),
+        (name = "champions", description = "Champions and champion overviews"),
+        (name = "meta", description = "Meta analysis"),
+        (name = "cards", description = "Card catalogue and card statistics"),
+        (name = "players", description = "Player profiles"),
+        (name = "ratings", description = "Glicko-2 player ratings"),
+        (name = "saved-decklists", description = "User-owned decklists"),
+        (name = "auth", description = "Accounts, tokens and API keys"),
+        (name = "admin", description = "Administration"),
+        (name = "graphql", description = "GraphQL endpoint over events, decklists, cards and meta"),
+        (name = "export", description = "Bulk data export"),
+    ),
+    modifiers(&SecuritySchemes)
+)]
+struct ApiDoc;
+
+/// Build the OpenAPI document from the controller annotations
+pub fn spec() -> utoipa::openapi::OpenApi {
+    let mut spec = ApiDoc::openapi();
+
+    for module in [
+        controllers::health::ApiDoc::openapi(),
+        controllers::events::ApiDoc::openapi(),
+        controllers::decklists::ApiDoc::openapi(),
+        controllers::champions::ApiDoc::openapi(),
+        controllers::meta::ApiDoc::openapi(),
+        controllers::cards::ApiDoc::openapi(),
+        controllers::players::ApiDoc::openapi(),
+        controllers::ratings::ApiDoc::openapi(),
+        controllers::saved_decklists::ApiDoc::openapi(),
+        controllers::auth::ApiDoc::openapi(),
+        controllers::admin::ApiDoc::openapi(),
+        controllers::graphql::ApiDoc::openapi(),
+        controllers::export::ApiDoc::openapi(),
+    ] {
+        spec.merge(module);
+    }
+
+    spec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_covers_routes_and_models() {
+        let spec = spec();
+
+        for path in [
+            "/api/events",
+            "/api/events/{event_id}/standings",
+            "/api/meta/breakdown",
+            "/api/champions/{slug}/overview",
+            "/api/saved-decklists/{id}",
+        ] {
+            assert!(spec.paths.paths.contains_key(path), "missing {}", path);
+        }
+
+        let schemas = &spec.components.as_ref().unwrap().schemas;
+        for schema in ["EventListResponse", "PageMeta", "Problem", "ChampionOverview"] {
+            assert!(schemas.contains_key(schema), "missing {}", schema);
+        }
+
+        let json = serde_json::to_value(&spec).unwrap();
+        assert!(json["paths"]["/api/meta/snapshot"].is_null());
+        assert_eq!(json["openapi"], "3.1.0");
+    }
+}
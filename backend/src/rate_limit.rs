@@ -0,0 +1,317 @@
+use crate::config::Config;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Number of tracked clients above which stale counters are pruned
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// How long an authenticated API key keeps its own bucket before it must
+/// authenticate again, so revoked keys fall back to per-IP limits
+const KNOWN_API_KEY_TTL: Duration = Duration::from_secs(300);
+
+/// Most API keys remembered at once; further keys are limited per IP until
+/// older entries expire
+const MAX_KNOWN_API_KEYS: usize = 10_000;
+
+/// Request counts for one client in the current and previous fixed windows
+#[derive(Debug, Clone, Copy)]
+struct WindowCounter {
+    window: u64,
+    current: u32,
+    previous: u32,
+}
+
+/// Outcome of a rate limit check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    /// Seconds until the current window ends
+    pub reset_secs: u64,
+    /// Seconds to wait before retrying, when rejected
+    pub retry_after_secs: u64,
+}
+
+/// Sliding-window rate limiter. Counts requests in fixed windows and estimates
+/// the rolling rate as the current count plus the previous window's count
+/// weighted by how much of it still overlaps the sliding window.
+pub struct RateLimiter {
+    window: Duration,
+    started: Instant,
+    counters: Mutex<HashMap<String, WindowCounter>>,
+}
+
+impl RateLimiter {
+    pub fn new(window_secs: u64) -> Self {
+        Self {
+            window: Duration::from_secs(window_secs.max(1)),
+            started: Instant::now(),
+            counters: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a request for `key` against `limit` requests per window
+    pub fn check(&self, key: &str, limit: u32) -> RateLimitDecision {
+        self.check_at(key, limit, Instant::now(), true)
+    }
+
+    /// Whether a request for `key` would be allowed, without recording it
+    pub fn peek(&self, key: &str, limit: u32) -> RateLimitDecision {
+        self.check_at(key, limit, Instant::now(), false)
+    }
+
+    fn check_at(&self, key: &str, limit: u32, now: Instant, record: bool) -> RateLimitDecision {
+        let window_secs = self.window.as_secs_f64();
+        let elapsed = now.saturating_duration_since(self.started).as_secs_f64();
+        let window = (elapsed / window_secs) as u64;
+        let into_window = elapsed - window as f64 * window_secs;
+        let overlap = 1.0 - into_window / window_secs;
+        let reset_secs = (window_secs - into_window).ceil() as u64;
+
+        let mut counters = self.counters.lock().unwrap();
+
+        if counters.len() > PRUNE_THRESHOLD {
+            counters.retain(|_, counter| counter.window + 1 >= window);
+        }
+
+        let counter = counters.entry(key.to_string()).or_insert(WindowCounter {
+            window,
+            current: 0,
+            previous: 0,
+        });
+
+        if counter.window != window {
+            counter.previous = if counter.window + 1 == window { counter.current } else { 0 };
+            counter.current = 0;
+            counter.window = window;
+        }
+
+        let estimate = counter.previous as f64 * overlap + counter.current as f64;
+
+        if estimate + 1.0 > limit as f64 {
+            // Wait until the previous window's weight decays enough for one more request,
+            // or until the window rolls over if the current window alone is full
+            let retry_after = if counter.current + 1 > limit || counter.previous == 0 {
+                window_secs - into_window
+            } else {
+                let needed_overlap = (limit - counter.current - 1) as f64 / counter.previous as f64;
+                window_secs * (1.0 - needed_overlap) - into_window
+            };
+
+            return RateLimitDecision {
+                allowed: false,
+                limit,
+                remaining: 0,
+                reset_secs,
+                retry_after_secs: retry_after.ceil().max(1.0) as u64,
+            };
+        }
+
+        if record {
+            counter.current += 1;
+        }
+        let remaining = (limit as f64 - estimate - 1.0).floor().max(0.0) as u32;
+
+        RateLimitDecision {
+            allowed: true,
+            limit,
+            remaining,
+            reset_secs,
+            retry_after_secs: 0,
+        }
+    }
+}
+
+/// Rate limits applied by the HTTP layer, shared across workers
+pub struct RateLimits {
+    pub enabled: bool,
+    /// Requests per window for anonymous clients, per IP
+    pub anonymous: u32,
+    /// Requests per window for authenticated clients, per API key or user
+    pub authenticated: u32,
+    /// Requests per window to expensive routes, per client
+    pub expensive: u32,
+    /// Use `X-Forwarded-For`/`Forwarded` for client IPs (only behind a trusted proxy)
+    pub trust_proxy: bool,
+    pub general: RateLimiter,
+    pub expensive_routes: RateLimiter,
+    /// Hashes of API keys that recently authenticated and when they did.
+    /// Requests with any other key are limited per IP, so guessed keys do
+    /// not get buckets of their own.
+    known_api_keys: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimits {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            enabled: config.rate_limit_enabled,
+            anonymous: config.rate_limit_requests,
+            authenticated: config.rate_limit_api_key_requests,
+            expensive: config.rate_limit_expensive_requests,
+            trust_proxy: config.trust_proxy_headers,
+            general: RateLimiter::new(config.rate_limit_window_secs),
+            expensive_routes: RateLimiter::new(config.rate_limit_window_secs),
+            known_api_keys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a request from `client` against the general bucket and, for
+    /// expensive routes, the expensive bucket. A request rejected by either
+    /// bucket is counted in neither. Returns the decision of the stricter bucket.
+    pub fn check(&self, client: &str, limit: u32, expensive: bool) -> RateLimitDecision {
+        if !expensive {
+            return self.general.check(client, limit);
+        }
+
+        let general = self.general.peek(client, limit);
+        if !general.allowed {
+            return general;
+        }
+
+        let decision = self.expensive_routes.check(client, self.expensive);
+        if decision.allowed {
+            self.general.check(client, limit);
+        }
+        decision
+    }
+
+    /// Whether the API key authenticated within `KNOWN_API_KEY_TTL`
+    pub fn is_known_api_key(&self, key_hash: &str) -> bool {
+        self.is_known_api_key_at(key_hash, Instant::now())
+    }
+
+    fn is_known_api_key_at(&self, key_hash: &str, now: Instant) -> bool {
+        self.known_api_keys
+            .lock()
+            .unwrap()
+            .get(key_hash)
+            .is_some_and(|&seen| now.saturating_duration_since(seen) < KNOWN_API_KEY_TTL)
+    }
+
+    /// Remember an API key that has just authenticated
+    pub fn remember_api_key(&self, key_hash: String) {
+        self.remember_api_key_at(key_hash, Instant::now());
+    }
+
+    fn remember_api_key_at(&self, key_hash: String, now: Instant) {
+        let mut known = self.known_api_keys.lock().unwrap();
+        if known.len() >= MAX_KNOWN_API_KEYS && !known.contains_key(&key_hash) {
+            known.retain(|_, &mut seen| now.saturating_duration_since(seen) < KNOWN_API_KEY_TTL);
+            if known.len() >= MAX_KNOWN_API_KEYS {
+                return;
+            }
+        }
+        known.insert(key_hash, now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_over_limit_within_window() {
+        let limiter = RateLimiter::new(60);
+        let now = limiter.started;
+
+        for _ in 0..3 {
+            assert!(limiter.check_at("ip:1", 3, now, true).allowed);
+        }
+
+        let decision = limiter.check_at("ip:1", 3, now, true);
+        assert!(!decision.allowed);
+        assert_eq!(decision.retry_after_secs, 60);
+
+        // Other clients have their own counters
+        assert!(limiter.check_at("ip:2", 3, now, true).allowed);
+    }
+
+    #[test]
+    fn test_previous_window_decays() {
+        let limiter = RateLimiter::new(60);
+        let start = limiter.started;
+
+        for _ in 0..4 {
+            assert!(limiter.check_at("ip:1", 4, start, true).allowed);
+        }
+
+        // Halfway through the next window, half of the previous count still applies
+        let halfway = start + Duration::from_secs(90);
+        assert!(limiter.check_at("ip:1", 4, halfway, true).allowed);
+        assert!(limiter.check_at("ip:1", 4, halfway, true).allowed);
+        assert!(!limiter.check_at("ip:1", 4, halfway, true).allowed);
+
+        // Two windows later the old counts no longer apply
+        let later = start + Duration::from_secs(180);
+        assert_eq!(limiter.check_at("ip:1", 4, later, true).remaining, 3);
+    }
+
+    #[test]
+    fn test_peek_does_not_count() {
+        let limiter = RateLimiter::new(60);
+        let now = limiter.started;
+
+        for _ in 0..5 {
+            assert!(limiter.check_at("ip:1", 1, now, false).allowed);
+        }
+        assert!(limiter.check_at("ip:1", 1, now, true).allowed);
+        assert!(!limiter.check_at("ip:1", 1, now, false).allowed);
+    }
+
+    fn limits(expensive: u32) -> RateLimits {
+        RateLimits {
+            enabled: true,
+            anonymous: 3,
+            authenticated: 3,
+            expensive,
+            trust_proxy: false,
+            general: RateLimiter::new(60),
+            expensive_routes: RateLimiter::new(60),
+            known_api_keys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[test]
+    fn test_expensive_rejection_is_not_counted() {
+        let limits = limits(1);
+
+        assert!(limits.check("ip:1", 3, true).allowed);
+        assert!(!limits.check("ip:1", 3, true).allowed);
+        assert!(!limits.check("ip:1", 3, true).allowed);
+
+        // Only the admitted expensive request used the general bucket
+        assert_eq!(limits.check("ip:1", 3, false).remaining, 1);
+    }
+
+    #[test]
+    fn test_known_api_keys_expire() {
+        let limits = limits(1);
+        let now = Instant::now();
+
+        limits.remember_api_key_at("a".to_string(), now);
+        assert!(limits.is_known_api_key_at("a", now + Duration::from_secs(60)));
+        // A revoked key stops authenticating, so it is not remembered again
+        assert!(!limits.is_known_api_key_at("a", now + KNOWN_API_KEY_TTL));
+        assert!(!limits.is_known_api_key_at("b", now));
+    }
+
+    #[test]
+    fn test_known_api_keys_are_bounded() {
+        let limits = limits(1);
+        let now = Instant::now();
+
+        for i in 0..MAX_KNOWN_API_KEYS {
+            limits.remember_api_key_at(i.to_string(), now);
+        }
+        limits.remember_api_key_at("new".to_string(), now);
+        assert!(!limits.is_known_api_key_at("new", now));
+
+        // Once older entries expire they make room
+        let later = now + KNOWN_API_KEY_TTL;
+        limits.remember_api_key_at("new".to_string(), later);
+        assert!(limits.is_known_api_key_at("new", later));
+        assert_eq!(limits.known_api_keys.lock().unwrap().len(), 1);
+    }
+}
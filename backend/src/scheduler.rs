@@ -1,15 +1,20 @@
+use crate::cache::ResponseCache;
 use crate::clients::{GatcgApiClient, OmnidexApiClient, OmniWebApiClient};
 use crate::config::Config;
-use crate::services::{EventCrawler, CardSyncService, MetaAnalysisService};
+use crate::models::DateRange;
+use crate::services::meta_analysis::MetaGrouping;
+use crate::services::{EventCrawler, CardSyncService, ExportService, MetaAnalysisService, RatingService};
 use mongodb::Database;
 use tokio_cron_scheduler::{Job, JobScheduler};
 use log::{info, error};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 /// Initialize and configure the job scheduler
 pub async fn setup_scheduler(
     config: Config,
     database: Database,
+    cache: Arc<ResponseCache>,
 ) -> Result<JobScheduler, anyhow::Error> {
     info!("Setting up job scheduler");
 
@@ -40,11 +45,13 @@ pub async fn setup_scheduler(
         let omnidex = omnidex_client.clone();
         let omni_web = omni_web_client.clone();
         let max_404s = config.crawler_max_404s;
+        let cache = cache.clone();
 
         let job = Job::new_async("0 0 2 * * *", move |_uuid, _lock| {
             let db = db.clone();
             let omnidex = omnidex.clone();
             let omni_web = omni_web.clone();
+            let cache = cache.clone();
 
             Box::pin(async move {
                 info!("Starting scheduled incremental event crawl");
@@ -54,6 +61,7 @@ pub async fn setup_scheduler(
                 match crawler.crawl_incremental().await {
                     Ok(last_id) => {
                         info!("Incremental event crawl completed successfully. Last ID: {}", last_id);
+                        cache.invalidate_all();
                     }
                     Err(e) => {
                         error!("Incremental event crawl failed: {}", e);
@@ -70,10 +78,12 @@ pub async fn setup_scheduler(
     {
         let db = database.clone();
         let gatcg = gatcg_client.clone();
+        let cache = cache.clone();
 
         let job = Job::new_async("0 0 3 * * *", move |_uuid, _lock| {
             let db = db.clone();
             let gatcg = gatcg.clone();
+            let cache = cache.clone();
 
             Box::pin(async move {
                 info!("Starting scheduled card sync");
@@ -86,6 +96,7 @@ pub async fn setup_scheduler(
                             "Card sync completed successfully. Champions: {}, Cards: {}",
                             champions, cards
                         );
+                        cache.invalidate_all();
                     }
                     Err(e) => {
                         error!("Card sync failed: {}", e);
@@ -98,12 +109,44 @@ pub async fn setup_scheduler(
         info!("Scheduled: Card sync at 03:00 UTC daily");
     }
 
+    // Daily player rating recompute at 05:00 UTC
+    {
+        let db = database.clone();
+        let cache = cache.clone();
+
+        let job = Job::new_async("0 0 5 * * *", move |_uuid, _lock| {
+            let db = db.clone();
+            let cache = cache.clone();
+
+            Box::pin(async move {
+                info!("Starting scheduled rating recompute");
+
+                let rating_service = RatingService::new(db);
+
+                match rating_service.recompute_all().await {
+                    Ok(events) => {
+                        info!("Rating recompute completed successfully. Events rated: {}", events);
+                        cache.invalidate_all();
+                    }
+                    Err(e) => {
+                        error!("Rating recompute failed: {}", e);
+                    }
+                }
+            })
+        })?;
+
+        scheduler.add(job).await?;
+        info!("Scheduled: Rating recompute at 05:00 UTC daily");
+    }
+
     // Daily meta analysis at 06:00 UTC
     {
         let db = database.clone();
+        let cache = cache.clone();
 
         let job = Job::new_async("0 0 6 * * *", move |_uuid, _lock| {
             let db = db.clone();
+            let cache = cache.clone();
 
             Box::pin(async move {
                 info!("Starting scheduled meta analysis");
@@ -111,7 +154,7 @@ pub async fn setup_scheduler(
                 let meta_service = MetaAnalysisService::new(db);
 
                 // Calculate various meta statistics
-                match meta_service.calculate_meta_breakdown(None, Some(30)).await {
+                match meta_service.calculate_meta_breakdown(None, DateRange::last_days(30), MetaGrouping::Champion).await {
                     Ok(breakdown) => {
                         info!(
                             "Meta breakdown calculated: {} champions in last 30 days",
@@ -123,7 +166,7 @@ pub async fn setup_scheduler(
                     }
                 }
 
-                match meta_service.calculate_champion_performance(Some(30)).await {
+                match meta_service.calculate_champion_performance(None, DateRange::last_days(30)).await {
                     Ok(performance) => {
                         info!(
                             "Champion performance calculated: {} champions",
@@ -134,6 +177,8 @@ pub async fn setup_scheduler(
                         error!("Champion performance calculation failed: {}", e);
                     }
                 }
+
+                cache.invalidate_all();
             })
         })?;
 
@@ -141,6 +186,36 @@ pub async fn setup_scheduler(
         info!("Scheduled: Meta analysis at 06:00 UTC daily");
     }
 
+    // Nightly data dump at 07:00 UTC, when an export directory is configured
+    if let Some(export_dir) = &config.export_dir {
+        let db = database.clone();
+        let dir = PathBuf::from(export_dir);
+        let keep = config.export_keep_dumps;
+
+        let job = Job::new_async("0 0 7 * * *", move |_uuid, _lock| {
+            let db = db.clone();
+            let dir = dir.clone();
+
+            Box::pin(async move {
+                info!("Starting scheduled data dump");
+
+                let export_service = ExportService::new(db);
+
+                match export_service.write_dump(&dir, keep).await {
+                    Ok(manifest) => {
+                        info!("Data dump completed successfully. Files: {}", manifest.files.len());
+                    }
+                    Err(e) => {
+                        error!("Data dump failed: {}", e);
+                    }
+                }
+            })
+        })?;
+
+        scheduler.add(job).await?;
+        info!("Scheduled: Data dump to {} at 07:00 UTC daily", export_dir);
+    }
+
     Ok(scheduler)
 }
 
@@ -0,0 +1,569 @@
+use crate::config::Config;
+use crate::models::{ApiKey, Role, User};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use mongodb::{Database, Collection};
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::error::{ErrorKind, WriteFailure};
+use log::{info, warn};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use chrono::Utc;
+use std::sync::LazyLock;
+use thiserror::Error;
+use utoipa::ToSchema;
+
+/// Prefix of every issued API key
+const API_KEY_PREFIX: &str = "gam";
+
+/// Minimum accepted password length
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Hash checked when no user has the given email, so logins for unknown
+/// emails take as long to reject as wrong passwords
+static DUMMY_PASSWORD_HASH: LazyLock<String> =
+    LazyLock::new(|| hash_password(&hex::encode(random_bytes(16))).unwrap_or_default());
+
+#[derive(Error, Debug)]
+pub enum AuthError {
+    #[error("Invalid email or password")]
+    InvalidCredentials,
+
+    #[error("Invalid or expired token")]
+    InvalidToken,
+
+    #[error("Invalid API key")]
+    InvalidApiKey,
+
+    #[error("{0} is already registered")]
+    Conflict(&'static str),
+
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] mongodb::error::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] bson::ser::Error),
+
+    #[error("Password hashing error: {0}")]
+    Hashing(String),
+
+    #[error("Token error: {0}")]
+    Token(#[from] jsonwebtoken::errors::Error),
+}
+
+/// How a principal authenticated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PrincipalKind {
+    User,
+    ApiKey,
+}
+
+/// Authenticated caller attached to a request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Principal {
+    /// User ID (hex) or API key ID
+    pub subject: String,
+
+    /// Username or API key name
+    pub name: String,
+
+    pub role: Role,
+    pub kind: PrincipalKind,
+}
+
+impl Principal {
+    /// User ID for user principals
+    pub fn user_id(&self) -> Option<ObjectId> {
+        match self.kind {
+            PrincipalKind::User => ObjectId::parse_str(&self.subject).ok(),
+            PrincipalKind::ApiKey => None,
+        }
+    }
+}
+
+/// JWT claims issued to users
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    name: String,
+    role: Role,
+    iat: i64,
+    exp: i64,
+}
+
+/// Service for user accounts, password hashing, JWTs and API keys
+pub struct AuthService {
+    database: Database,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    token_ttl_secs: u64,
+    admin_api_key: Option<String>,
+}
+
+impl AuthService {
+    /// Create a new auth service from configuration
+    pub fn new(database: Database, config: &Config) -> Self {
+        let secret = match &config.jwt_secret {
+            Some(secret) => secret.as_bytes().to_vec(),
+            None => {
+                warn!("JWT_SECRET is not set; using a random secret, tokens will not survive restarts");
+                random_bytes(32)
+            }
+        };
+
+        Self {
+            database,
+            encoding_key: EncodingKey::from_secret(&secret),
+            decoding_key: DecodingKey::from_secret(&secret),
+            token_ttl_secs: config.jwt_ttl_secs,
+            admin_api_key: config.admin_api_key.clone(),
+        }
+    }
+
+    /// Lifetime of issued access tokens
+    pub fn token_ttl_secs(&self) -> u64 {
+        self.token_ttl_secs
+    }
+
+    /// Register a new user with the default role
+    pub async fn register(&self, email: &str, username: &str, password: &str) -> Result<User, AuthError> {
+        let email = email.trim().to_lowercase();
+        let username = username.trim().to_string();
+
+        if !email.contains('@') {
+            return Err(AuthError::InvalidInput("email must be a valid address".to_string()));
+        }
+        if username.is_empty() {
+            return Err(AuthError::InvalidInput("username must not be empty".to_string()));
+        }
+        if password.len() < MIN_PASSWORD_LENGTH {
+            return Err(AuthError::InvalidInput(format!(
+                "password must be at least {} characters",
+                MIN_PASSWORD_LENGTH
+            )));
+        }
+
+        let users: Collection<User> = self.database.collection("users");
+
+        if users.find_one(doc! { "email": &email }, None).await?.is_some() {
+            return Err(AuthError::Conflict("email"));
+        }
+        if users.find_one(doc! { "username": &username }, None).await?.is_some() {
+            return Err(AuthError::Conflict("username"));
+        }
+
+        let mut user = User {
+            id: None,
+            email,
+            username,
+            password_hash: hash_password_blocking(password).await?,
+            role: Role::User,
+            player_id: None,
+            created_at: Utc::now(),
+            last_login_at: None,
+        };
+
+        // The checks above race with concurrent registrations; the unique indexes decide
+        let result = users.insert_one(&user, None).await.map_err(|e| match duplicate_key(&e) {
+            Some(message) if message.contains("username") => AuthError::Conflict("username"),
+            Some(_) => AuthError::Conflict("email"),
+            None => AuthError::Database(e),
+        })?;
+        user.id = result.inserted_id.as_object_id();
+
+        info!("Registered user '{}'", user.username);
+
+        Ok(user)
+    }
+
+    /// Verify email/password and record the login
+    pub async fn login(&self, email: &str, password: &str) -> Result<User, AuthError> {
+        let users: Collection<User> = self.database.collection("users");
+
+        let user = users
+            .find_one(doc! { "email": email.trim().to_lowercase() }, None)
+            .await?;
+
+        let hash = user.as_ref().map(|u| u.password_hash.clone());
+        let valid = verify_password_blocking(password, hash).await?;
+
+        let Some(mut user) = user.filter(|_| valid) else {
+            return Err(AuthError::InvalidCredentials);
+        };
+
+        let now = Utc::now();
+        users
+            .update_one(
+                doc! { "_id": user.id },
+                doc! { "$set": { "last_login_at": mongodb::bson::DateTime::from_chrono(now) } },
+                None,
+            )
+            .await?;
+        user.last_login_at = Some(now);
+
+        Ok(user)
+    }
+
+    /// Look up a user by ID
+    pub async fn get_user(&self, user_id: ObjectId) -> Result<User, AuthError> {
+        let users: Collection<User> = self.database.collection("users");
+
+        users
+            .find_one(doc! { "_id": user_id }, None)
+            .await?
+            .ok_or_else(|| AuthError::NotFound("user".to_string()))
+    }
+
+    /// Change a user's role
+    pub async fn set_role(&self, user_id: ObjectId, role: Role) -> Result<User, AuthError> {
+        let users: Collection<User> = self.database.collection("users");
+
+        let result = users
+            .update_one(
+                doc! { "_id": user_id },
+                doc! { "$set": { "role": mongodb::bson::to_bson(&role)? } },
+                None,
+            )
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(AuthError::NotFound("user".to_string()));
+        }
+
+        info!("Set role of user {} to {:?}", user_id, role);
+
+        self.get_user(user_id).await
+    }
+
+    /// Issue a signed access token for a user
+    pub fn issue_token(&self, user: &User) -> Result<String, AuthError> {
+        let now = Utc::now().timestamp();
+        let claims = Claims {
+            sub: user.id.map(|id| id.to_hex()).unwrap_or_default(),
+            name: user.username.clone(),
+            role: user.role,
+            iat: now,
+            exp: now + self.token_ttl_secs as i64,
+        };
+
+        Ok(encode(&Header::default(), &claims, &self.encoding_key)?)
+    }
+
+    /// Validate a bearer token and return its principal. Tokens claiming a
+    /// role above `User` are checked against the stored account, so demotions
+    /// apply immediately instead of when the token expires.
+    pub async fn authenticate_token(&self, token: &str) -> Result<Principal, AuthError> {
+        let mut principal = self.verify_token(token)?;
+
+        if principal.role.satisfies(Role::Contributor) {
+            let user_id = principal.user_id().ok_or(AuthError::InvalidToken)?;
+            let user = self
+                .database
+                .collection::<User>("users")
+                .find_one(doc! { "_id": user_id }, None)
+                .await?
+                .ok_or(AuthError::InvalidToken)?;
+            principal.role = user.role;
+        }
+
+        Ok(principal)
+    }
+
+    /// Validate a bearer token's signature and expiry and return the principal it claims
+    pub fn verify_token(&self, token: &str) -> Result<Principal, AuthError> {
+        let data = decode::<Claims>(token, &self.decoding_key, &Validation::default())
+            .map_err(|_| AuthError::InvalidToken)?;
+
+        Ok(Principal {
+            subject: data.claims.sub,
+            name: data.claims.name,
+            role: data.claims.role,
+            kind: PrincipalKind::User,
+        })
+    }
+
+    /// Create an API key. Returns the stored record and the full key, which is
+    /// not kept. A key cannot have a higher role than its owner.
+    pub async fn create_api_key(
+        &self,
+        name: &str,
+        role: Role,
+        owner_id: Option<ObjectId>,
+    ) -> Result<(ApiKey, String), AuthError> {
+        if let Some(owner_id) = owner_id {
+            check_api_key_role(role, self.get_user(owner_id).await?.role)?;
+        }
+
+        let key_id = hex::encode(random_bytes(6));
+        let key = format!("{}_{}_{}", API_KEY_PREFIX, key_id, hex::encode(random_bytes(24)));
+
+        let mut api_key = ApiKey {
+            id: None,
+            key_id,
+            key_hash: hash_api_key(&key),
+            name: name.trim().to_string(),
+            role,
+            owner_id,
+            revoked: false,
+            created_at: Utc::now(),
+            last_used: None,
+        };
+
+        let collection: Collection<ApiKey> = self.database.collection("api_keys");
+        let result = collection.insert_one(&api_key, None).await?;
+        api_key.id = result.inserted_id.as_object_id();
+
+        info!("Created API key '{}' ({})", api_key.name, api_key.key_id);
+
+        Ok((api_key, key))
+    }
+
+    /// List API keys, optionally restricted to one owner
+    pub async fn list_api_keys(&self, owner_id: Option<ObjectId>) -> Result<Vec<ApiKey>, AuthError> {
+        let collection: Collection<ApiKey> = self.database.collection("api_keys");
+
+        let filter = match owner_id {
+            Some(owner_id) => doc! { "owner_id": owner_id },
+            None => doc! {},
+        };
+
+        let mut cursor = collection.find(filter, None).await?;
+
+        use futures::stream::StreamExt;
+
+        let mut keys = Vec::new();
+        while let Some(result) = cursor.next().await {
+            keys.push(result?);
+        }
+
+        Ok(keys)
+    }
+
+    /// Revoke an API key, optionally only if owned by `owner_id`
+    pub async fn revoke_api_key(&self, key_id: &str, owner_id: Option<ObjectId>) -> Result<(), AuthError> {
+        let collection: Collection<ApiKey> = self.database.collection("api_keys");
+
+        let mut filter = doc! { "key_id": key_id };
+        if let Some(owner_id) = owner_id {
+            filter.insert("owner_id", owner_id);
+        }
+
+        let result = collection
+            .update_one(filter, doc! { "$set": { "revoked": true } }, None)
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(AuthError::NotFound("API key".to_string()));
+        }
+
+        info!("Revoked API key {}", key_id);
+
+        Ok(())
+    }
+
+    /// Validate an API key and return its principal. Keys of a user act with
+    /// at most the user's current role, so demotions apply to their keys too;
+    /// keys whose owner no longer exists are rejected.
+    pub async fn verify_api_key(&self, key: &str) -> Result<Principal, AuthError> {
+        if let Some(admin_key) = &self.admin_api_key {
+            if hash_api_key(key) == hash_api_key(admin_key) {
+                return Ok(Principal {
+                    subject: "admin".to_string(),
+                    name: "bootstrap admin key".to_string(),
+                    role: Role::Admin,
+                    kind: PrincipalKind::ApiKey,
+                });
+            }
+        }
+
+        let key_id = parse_key_id(key).ok_or(AuthError::InvalidApiKey)?;
+        let collection: Collection<ApiKey> = self.database.collection("api_keys");
+
+        let api_key = collection
+            .find_one(doc! { "key_id": key_id, "revoked": false }, None)
+            .await?
+            .ok_or(AuthError::InvalidApiKey)?;
+
+        if api_key.key_hash != hash_api_key(key) {
+            return Err(AuthError::InvalidApiKey);
+        }
+
+        let mut role = api_key.role;
+        if let Some(owner_id) = api_key.owner_id {
+            let owner = self
+                .database
+                .collection::<User>("users")
+                .find_one(doc! { "_id": owner_id }, None)
+                .await?
+                .ok_or(AuthError::InvalidApiKey)?;
+            role = api_key_role(api_key.role, owner.role);
+        }
+
+        collection
+            .update_one(
+                doc! { "key_id": &api_key.key_id },
+                doc! { "$set": { "last_used": mongodb::bson::DateTime::from_chrono(Utc::now()) } },
+                None,
+            )
+            .await?;
+
+        Ok(Principal {
+            subject: api_key.key_id,
+            name: api_key.name,
+            role,
+            kind: PrincipalKind::ApiKey,
+        })
+    }
+}
+
+/// Role an owned API key acts with: its own, capped at its owner's
+fn api_key_role(key_role: Role, owner_role: Role) -> Role {
+    key_role.min(owner_role)
+}
+
+fn check_api_key_role(role: Role, owner_role: Role) -> Result<(), AuthError> {
+    if !owner_role.satisfies(role) {
+        return Err(AuthError::Forbidden(
+            "Cannot create an API key with a higher role than its owner".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Hash a password with Argon2id and a random salt
+pub fn hash_password(password: &str) -> Result<String, AuthError> {
+    let salt = SaltString::encode_b64(&random_bytes(16)).map_err(|e| AuthError::Hashing(e.to_string()))?;
+
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AuthError::Hashing(e.to_string()))
+}
+
+/// Check a password against a stored Argon2 hash
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+        .unwrap_or(false)
+}
+
+/// `hash_password` on the blocking pool; Argon2 is too slow for a worker thread
+async fn hash_password_blocking(password: &str) -> Result<String, AuthError> {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || hash_password(&password))
+        .await
+        .map_err(|e| AuthError::Hashing(e.to_string()))?
+}
+
+/// `verify_password` on the blocking pool. Without a stored hash the
+/// password is checked against a dummy one and always rejected.
+async fn verify_password_blocking(password: &str, hash: Option<String>) -> Result<bool, AuthError> {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || match hash {
+        Some(hash) => verify_password(&password, &hash),
+        None => {
+            verify_password(&password, &DUMMY_PASSWORD_HASH);
+            false
+        }
+    })
+    .await
+    .map_err(|e| AuthError::Hashing(e.to_string()))
+}
+
+/// Message of a duplicate key (11000) write error, which names the violated index
+fn duplicate_key(error: &mongodb::error::Error) -> Option<&str> {
+    match &*error.kind {
+        ErrorKind::Write(WriteFailure::WriteError(e)) if e.code == 11000 => Some(&e.message),
+        _ => None,
+    }
+}
+
+/// SHA-256 of an API key, as stored
+pub fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// Extract the key ID from a `gam_<key_id>_<secret>` key
+fn parse_key_id(key: &str) -> Option<&str> {
+    let mut parts = key.splitn(3, '_');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(API_KEY_PREFIX), Some(key_id), Some(secret)) if !key_id.is_empty() && !secret.is_empty() => {
+            Some(key_id)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_hashing() {
+        let hash = hash_password("correct horse battery").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify_password("correct horse battery", &hash));
+        assert!(!verify_password("wrong", &hash));
+        assert!(!verify_password("anything", "not-a-hash"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_user_is_checked_against_dummy_hash() {
+        assert!(DUMMY_PASSWORD_HASH.starts_with("$argon2id$"));
+        assert!(!verify_password_blocking("correct horse battery", None).await.unwrap());
+
+        let hash = hash_password_blocking("correct horse battery").await.unwrap();
+        assert!(verify_password_blocking("correct horse battery", Some(hash)).await.unwrap());
+    }
+
+    #[test]
+    fn test_parse_key_id() {
+        assert_eq!(parse_key_id("gam_abc123_secret"), Some("abc123"));
+        assert_eq!(parse_key_id("gam_abc123_"), None);
+        assert_eq!(parse_key_id("other_abc123_secret"), None);
+    }
+
+    #[test]
+    fn test_api_key_role_is_capped_by_owner() {
+        // An admin demoted to user keeps the key, but not its privileges
+        assert_eq!(api_key_role(Role::Admin, Role::User), Role::User);
+        assert_eq!(api_key_role(Role::Contributor, Role::Admin), Role::Contributor);
+        assert_eq!(api_key_role(Role::User, Role::User), Role::User);
+    }
+
+    #[test]
+    fn test_api_key_role_may_not_exceed_owner() {
+        assert!(check_api_key_role(Role::Contributor, Role::Admin).is_ok());
+        assert!(check_api_key_role(Role::User, Role::User).is_ok());
+        assert!(matches!(
+            check_api_key_role(Role::Admin, Role::Contributor),
+            Err(AuthError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn test_role_ordering() {
+        assert!(Role::Admin.satisfies(Role::Contributor));
+        assert!(Role::Contributor.satisfies(Role::User));
+        assert!(!Role::User.satisfies(Role::Admin));
+    }
+}
@@ -1,10 +1,117 @@
+use crate::clients::gatcg_api::{CatalogueFetch, FetchedCard, PageValidators};
 use crate::clients::GatcgApiClient;
-use crate::models::Card;
+use crate::models::{champion_family, slugify, Card, CardRevision, CardSet, Champion, ChampionLineage, Printing};
+use crate::services::{ChampionAliasService, DeckStatsService};
 use mongodb::{Database, Collection};
-use mongodb::bson::doc;
+use mongodb::bson::{doc, Document};
 use log::{info, warn, debug};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Collection of cache validators for each catalogue page
+pub const CATALOGUE_PAGES_COLLECTION: &str = "catalogue_pages";
+
+/// Collection of card content changes
+pub const CARD_REVISIONS_COLLECTION: &str = "card_revisions";
+
+/// Collection of card printings
+pub const PRINTINGS_COLLECTION: &str = "printings";
+
+/// Collection of card sets
+pub const SETS_COLLECTION: &str = "sets";
+
+/// Stop paging after this many pages in case the API never reports the end
+const MAX_CATALOGUE_PAGES: u32 = 1000;
+
+/// Validators and continuation of a fetched catalogue page
+#[derive(Debug, Serialize, Deserialize)]
+struct CataloguePageState {
+    page: u32,
+    #[serde(flatten)]
+    validators: PageValidators,
+    has_more: bool,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    fetched_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// How a synced card compared with the stored one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardChange {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+/// Update storing `card` over `existing`. Fields the stored card has but
+/// `card` no longer sets are unset rather than left behind.
+fn card_update(card: &Card, existing: Option<&Card>) -> bson::ser::Result<Document> {
+    let fields = mongodb::bson::to_document(card)?;
+    let mut update = doc! {};
+    if let Some(existing) = existing {
+        let removed: Document = mongodb::bson::to_document(existing)?
+            .into_iter()
+            .filter(|(field, _)| field != "_id" && !fields.contains_key(field))
+            .map(|(field, _)| (field, "".into()))
+            .collect();
+        if !removed.is_empty() {
+            update.insert("$unset", removed);
+        }
+    }
+    update.insert("$set", fields);
+    Ok(update)
+}
+
+/// Fields of a set to store. Cards of a set may not all report its language
+/// or release date, so missing ones leave the stored values in place.
+fn set_fields(set: &CardSet) -> Document {
+    let mut fields = doc! { "name": &set.name };
+    if let Some(language) = &set.language {
+        fields.insert("language", language);
+    }
+    if let Some(release_date) = set.release_date {
+        fields.insert("release_date", mongodb::bson::DateTime::from_chrono(release_date));
+    }
+    fields
+}
+
+/// Fetched printings that are new or differ from the stored ones, and the
+/// slugs of stored printings no longer fetched
+fn printing_changes<'a>(stored: &[Printing], fetched: &'a [Printing]) -> (Vec<&'a Printing>, Vec<String>) {
+    let changed = fetched
+        .iter()
+        // Stored printings differ from fetched ones only by their ID
+        .filter(|printing| !stored.iter().any(|s| *s == Printing { id: s.id, ..(*printing).clone() }))
+        .collect();
+
+    let removed = stored
+        .iter()
+        .filter(|s| !fetched.iter().any(|printing| printing.slug == s.slug))
+        .map(|s| s.slug.clone())
+        .collect();
+
+    (changed, removed)
+}
+
+/// Outcome of a catalogue sync
+#[derive(Debug, Default)]
+pub struct CatalogueSyncReport {
+    pub pages: u32,
+    /// Pages the API reported unchanged since the last sync
+    pub pages_unchanged: u32,
+    pub cards_created: usize,
+    pub cards_updated: usize,
+    pub cards_unchanged: usize,
+    /// Slugs of the cards created or updated
+    pub changed_slugs: Vec<String>,
+}
+
+impl CatalogueSyncReport {
+    /// Cards written to the database
+    pub fn cards_synced(&self) -> usize {
+        self.cards_created + self.cards_updated
+    }
+}
 
 /// Service for synchronizing card data from the GATCG API
 pub struct CardSyncService {
@@ -21,13 +128,279 @@ impl CardSyncService {
         }
     }
 
+    /// Page through the full GATCG catalogue and store new and changed
+    /// cards. Pages are requested conditionally with the validators from the
+    /// last sync, and cards whose content hash is unchanged are not written.
+    pub async fn sync_catalogue(&self) -> Result<CatalogueSyncReport, Box<dyn std::error::Error>> {
+        use futures::stream::TryStreamExt;
+        use mongodb::options::{FindOptions, ReplaceOptions};
+
+        info!("Starting catalogue card sync");
+
+        let pages_collection: Collection<CataloguePageState> = self.database.collection(CATALOGUE_PAGES_COLLECTION);
+
+        let hash_options = FindOptions::builder().projection(doc! { "slug": 1, "content_hash": 1 }).build();
+        let stored: HashMap<String, Option<String>> = self
+            .database
+            .collection::<mongodb::bson::Document>("cards")
+            .find(None, hash_options)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .filter_map(|d| {
+                let slug = d.get_str("slug").ok()?.to_string();
+                Some((slug, d.get_str("content_hash").ok().map(str::to_string)))
+            })
+            .collect();
+
+        let mut known: HashMap<u32, CataloguePageState> = pages_collection
+            .find(None, None)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .map(|state| (state.page, state))
+            .collect();
+
+        let mut report = CatalogueSyncReport::default();
+        let mut page = 1;
+
+        loop {
+            let previous = known.remove(&page);
+            let validators = previous.as_ref().map(|p| p.validators.clone()).unwrap_or_default();
+            report.pages += 1;
+
+            let has_more = match self.gatcg_client.fetch_catalogue_page(page, &validators).await? {
+                CatalogueFetch::NotModified => {
+                    debug!("Catalogue page {} unchanged", page);
+                    report.pages_unchanged += 1;
+                    previous.is_some_and(|p| p.has_more)
+                }
+                CatalogueFetch::Page(fetched) => {
+                    for card in fetched.cards {
+                        let slug = card.card.slug.clone();
+                        let known_hash = stored.get(&slug).and_then(Option::as_deref);
+                        match self.store_fetched(card, known_hash).await? {
+                            CardChange::Created => report.cards_created += 1,
+                            CardChange::Updated => report.cards_updated += 1,
+                            CardChange::Unchanged => {
+                                report.cards_unchanged += 1;
+                                continue;
+                            }
+                        }
+                        report.changed_slugs.push(slug);
+                    }
+
+                    let state = CataloguePageState {
+                        page,
+                        validators: fetched.validators,
+                        has_more: fetched.has_more,
+                        fetched_at: chrono::Utc::now(),
+                    };
+                    pages_collection
+                        .replace_one(doc! { "page": page }, &state, ReplaceOptions::builder().upsert(true).build())
+                        .await?;
+
+                    fetched.has_more
+                }
+            };
+
+            if !has_more {
+                break;
+            }
+            if page >= MAX_CATALOGUE_PAGES {
+                warn!("Catalogue sync stopped after {} pages", page);
+                break;
+            }
+            page += 1;
+        }
+
+        // Forget pages past the end so a catalogue that grows back is fetched in full
+        pages_collection
+            .delete_many(doc! { "page": { "$gt": page } }, None)
+            .await?;
+
+        info!(
+            "Catalogue sync completed. {} pages ({} unchanged), {} cards created, {} updated, {} unchanged",
+            report.pages, report.pages_unchanged, report.cards_created, report.cards_updated, report.cards_unchanged
+        );
+
+        Ok(report)
+    }
+
+    /// Store a fetched card with its printings and sets. Printings are
+    /// compared separately, since the content hash leaves them out and an
+    /// unchanged card may gain a reprint.
+    pub async fn store_fetched(
+        &self,
+        fetched: FetchedCard,
+        known_hash: Option<&str>,
+    ) -> Result<CardChange, Box<dyn std::error::Error>> {
+        let FetchedCard { card, printings, sets } = fetched;
+        let slug = card.slug.clone();
+
+        let change = self.store_card(card, known_hash).await?;
+        self.store_printings(&slug, &printings, &sets).await?;
+
+        Ok(change)
+    }
+
+    /// Upsert a card's printings and their sets. Printings the API no longer
+    /// lists for the card are removed; unchanged documents are left as they are.
+    async fn store_printings(
+        &self,
+        card_slug: &str,
+        printings: &[Printing],
+        sets: &[CardSet],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        use mongodb::options::{ReplaceOptions, UpdateOptions};
+
+        // A response without editions says nothing about the printings
+        if printings.is_empty() {
+            return Ok(());
+        }
+
+        use futures::stream::TryStreamExt;
+
+        let printings_collection: Collection<Printing> = self.database.collection(PRINTINGS_COLLECTION);
+        let stored: Vec<Printing> = printings_collection
+            .find(doc! { "card_slug": card_slug }, None)
+            .await?
+            .try_collect()
+            .await?;
+
+        let (changed, removed) = printing_changes(&stored, printings);
+        for printing in changed {
+            printings_collection
+                .replace_one(
+                    doc! { "slug": &printing.slug },
+                    printing,
+                    ReplaceOptions::builder().upsert(true).build(),
+                )
+                .await?;
+        }
+
+        if !removed.is_empty() {
+            printings_collection
+                .delete_many(doc! { "card_slug": card_slug, "slug": { "$in": removed } }, None)
+                .await?;
+        }
+
+        let sets_collection = self.database.collection::<Document>(SETS_COLLECTION);
+        for set in sets {
+            sets_collection
+                .update_one(
+                    doc! { "prefix": &set.prefix },
+                    doc! { "$set": set_fields(set) },
+                    UpdateOptions::builder().upsert(true).build(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Store a fetched card unless its content is unchanged, recording a
+    /// revision when stored content changes. `known_hash` is the stored
+    /// content hash if the caller already has it, saving a lookup for
+    /// unchanged cards.
+    pub async fn store_card(
+        &self,
+        mut card: Card,
+        known_hash: Option<&str>,
+    ) -> Result<CardChange, Box<dyn std::error::Error>> {
+        let cards_collection: Collection<Card> = self.database.collection("cards");
+
+        let hash = card.compute_content_hash();
+        if known_hash == Some(hash.as_str()) {
+            return Ok(CardChange::Unchanged);
+        }
+
+        let existing = cards_collection.find_one(doc! { "slug": &card.slug }, None).await?;
+
+        if let Some(existing) = &existing {
+            // The stored hash may be missing or computed differently
+            if existing.compute_content_hash() == hash {
+                if existing.content_hash.as_deref() != Some(hash.as_str()) {
+                    cards_collection
+                        .update_one(doc! { "slug": &card.slug }, doc! { "$set": { "content_hash": &hash } }, None)
+                        .await?;
+                }
+                return Ok(CardChange::Unchanged);
+            }
+        }
+
+        debug!("Syncing card: {}", card.name);
+        card.content_hash = Some(hash.clone());
+        let update = card_update(&card, existing.as_ref())?;
+        cards_collection
+            .update_one(
+                doc! { "slug": &card.slug },
+                update,
+                mongodb::options::UpdateOptions::builder().upsert(true).build(),
+            )
+            .await?;
+
+        let Some(existing) = existing else {
+            return Ok(CardChange::Created);
+        };
+
+        let revision = CardRevision {
+            id: None,
+            slug: card.slug.clone(),
+            changes: card.changes_from(&existing),
+            previous_hash: existing.content_hash.clone(),
+            content_hash: hash,
+            recorded_at: chrono::Utc::now(),
+        };
+        info!(
+            "Card '{}' changed: {}",
+            card.slug,
+            revision.changes.iter().map(|c| c.field.as_str()).collect::<Vec<_>>().join(", ")
+        );
+        self.database
+            .collection::<CardRevision>(CARD_REVISIONS_COLLECTION)
+            .insert_one(&revision, None)
+            .await?;
+
+        Ok(CardChange::Updated)
+    }
+
     /// Sync all cards from known card slugs
     /// Card slugs are discovered from decklists
     pub async fn sync_cards_from_decklists(&self) -> Result<usize, Box<dyn std::error::Error>> {
         info!("Starting card sync from decklists");
+        let slugs = self.get_decklist_card_slugs().await?;
+        self.sync_card_slugs(&slugs).await
+    }
 
+    /// Sync cards that appear in a decklist but are not stored, such as
+    /// promos missing from the catalogue listing
+    pub async fn sync_missing_cards(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        let stored: HashSet<String> = self
+            .database
+            .collection::<mongodb::bson::Document>("cards")
+            .distinct("slug", None, None)
+            .await?
+            .into_iter()
+            .filter_map(|slug| slug.as_str().map(str::to_string))
+            .collect();
+
+        let missing: Vec<String> = self
+            .get_decklist_card_slugs()
+            .await?
+            .into_iter()
+            .filter(|slug| !stored.contains(slug))
+            .collect();
+
+        info!("Found {} decklist cards missing from the catalogue", missing.len());
+        self.sync_card_slugs(&missing).await
+    }
+
+    /// Unique card slugs from every decklist section
+    async fn get_decklist_card_slugs(&self) -> Result<Vec<String>, mongodb::error::Error> {
         let decklists_collection = self.database.collection::<mongodb::bson::Document>("decklists");
-        let cards_collection: Collection<Card> = self.database.collection("cards");
 
         // Extract unique card slugs from all decklists
         let mut card_slugs = HashSet::new();
@@ -39,23 +412,14 @@ impl CardSyncService {
         while let Some(result) = cursor.next().await {
             match result {
                 Ok(doc) => {
-                    // Extract slugs from main_deck
-                    if let Ok(main_deck) = doc.get_array("main_deck") {
-                        for card_doc in main_deck {
-                            if let Some(card) = card_doc.as_document() {
-                                if let Ok(slug) = card.get_str("slug") {
-                                    card_slugs.insert(slug.to_string());
-                                }
-                            }
-                        }
-                    }
-
-                    // Extract slugs from sideboard
-                    if let Ok(sideboard) = doc.get_array("sideboard") {
-                        for card_doc in sideboard {
-                            if let Some(card) = card_doc.as_document() {
-                                if let Ok(slug) = card.get_str("slug") {
-                                    card_slugs.insert(slug.to_string());
+                    // Extract slugs from every deck section
+                    for section in ["main_deck", "sideboard", "material_deck"] {
+                        if let Ok(cards) = doc.get_array(section) {
+                            for card_doc in cards {
+                                if let Some(card) = card_doc.as_document() {
+                                    if let Ok(slug) = card.get_str("slug") {
+                                        card_slugs.insert(slug.to_string());
+                                    }
                                 }
                             }
                         }
@@ -69,28 +433,24 @@ impl CardSyncService {
 
         info!("Found {} unique card slugs from decklists", card_slugs.len());
 
+        let mut slugs: Vec<String> = card_slugs.into_iter().collect();
+        slugs.sort();
+        Ok(slugs)
+    }
+
+    /// Fetch cards one request at a time and store the new and changed ones,
+    /// returning how many were written
+    async fn sync_card_slugs(&self, slugs: &[String]) -> Result<usize, Box<dyn std::error::Error>> {
         // Fetch card data for each slug
         let mut synced_count = 0;
-        let slugs: Vec<String> = card_slugs.into_iter().collect();
+        let mut unchanged_count = 0;
 
-        for slug in &slugs {
+        for slug in slugs {
             match self.gatcg_client.fetch_card(slug).await {
-                Ok(Some(card)) => {
-                    debug!("Syncing card: {}", card.name);
-
-                    let filter = doc! { "slug": &card.slug };
-                    let update = doc! {
-                        "$set": mongodb::bson::to_document(&card)?
-                    };
-
-                    use mongodb::options::UpdateOptions;
-                    let options = UpdateOptions::builder().upsert(true).build();
-                    cards_collection
-                        .update_one(filter, update, options)
-                        .await?;
-
-                    synced_count += 1;
-                }
+                Ok(Some(card)) => match self.store_fetched(card, None).await? {
+                    CardChange::Unchanged => unchanged_count += 1,
+                    _ => synced_count += 1,
+                },
                 Ok(None) => {
                     debug!("Card '{}' not found in API", slug);
                 }
@@ -100,36 +460,37 @@ impl CardSyncService {
             }
         }
 
-        info!("Card sync completed. Synced {} cards", synced_count);
+        info!("Card sync completed. Synced {} cards, {} unchanged", synced_count, unchanged_count);
 
         Ok(synced_count)
     }
 
-    /// Sync champion cards specifically
+    /// Sync champion cards specifically, then rebuild the lineages of
+    /// every family they belong to
     pub async fn sync_champions(&self, champion_slugs: &[String]) -> Result<usize, Box<dyn std::error::Error>> {
         info!("Starting champion card sync for {} champions", champion_slugs.len());
 
-        let cards_collection: Collection<Card> = self.database.collection("cards");
         let champions_collection = self.database.collection::<mongodb::bson::Document>("champions");
 
         let mut synced_count = 0;
+        let mut families = BTreeMap::new();
+
+        let aliases = ChampionAliasService::new(self.database.clone());
 
         for slug in champion_slugs {
-            match self.gatcg_client.fetch_card(slug).await {
-                Ok(Some(card)) => {
+            // Upstream strings may be display names or differently cased slugs
+            match self.gatcg_client.fetch_card(&slugify(slug)).await {
+                Ok(Some(fetched)) => {
+                    let card = fetched.card.clone();
                     info!("Syncing champion card: {}", card.name);
 
                     // Save to cards collection
-                    let card_filter = doc! { "slug": &card.slug };
-                    let card_update = doc! {
-                        "$set": mongodb::bson::to_document(&card)?
-                    };
+                    self.store_fetched(fetched, None).await?;
 
-                    use mongodb::options::UpdateOptions;
-                    let options = UpdateOptions::builder().upsert(true).build();
-                    cards_collection
-                        .update_one(card_filter, card_update, options)
-                        .await?;
+                    let family = champion_family(&card.name, card.level);
+                    if let Some((family_slug, family_name)) = &family {
+                        families.insert(family_slug.clone(), family_name.clone());
+                    }
 
                     // Update champion collection with card details
                     let champion_filter = doc! { "slug": &card.slug };
@@ -141,15 +502,23 @@ impl CardSyncService {
                             "image_url": &card.image_url,
                             "ability_text": &card.card_text,
                             "life": card.life_modifier,
+                            "level": card.level,
+                            "family": family.map(|(slug, _)| slug),
                             "updated_at": mongodb::bson::DateTime::from_chrono(chrono::Utc::now())
                         }
                     };
 
-                    let champion_options = UpdateOptions::builder().upsert(true).build();
+                    let champion_options = mongodb::options::UpdateOptions::builder().upsert(true).build();
                     champions_collection
                         .update_one(champion_filter, champion_update, champion_options)
                         .await?;
 
+                    // Remember how the champion was spelled so stored strings can be normalized
+                    if slugify(slug) != card.slug {
+                        info!("Adding champion alias '{}' -> '{}'", slug, card.slug);
+                        aliases.set_alias(slug, &card.slug).await?;
+                    }
+
                     synced_count += 1;
                 }
                 Ok(None) => {
@@ -163,9 +532,52 @@ impl CardSyncService {
 
         info!("Champion sync completed. Synced {} champions", synced_count);
 
+        for (family, name) in families {
+            self.rebuild_lineage(&family, &name).await?;
+        }
+
         Ok(synced_count)
     }
 
+    /// Rebuild a family's lineage from the champions currently assigned to it
+    async fn rebuild_lineage(&self, family: &str, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        use futures::stream::TryStreamExt;
+        use mongodb::options::ReplaceOptions;
+
+        let champions_collection: Collection<Champion> = self.database.collection("champions");
+        let lineages_collection: Collection<ChampionLineage> = self.database.collection("champion_lineages");
+
+        let champions: Vec<Champion> = champions_collection
+            .find(doc! { "family": family }, None)
+            .await?
+            .try_collect()
+            .await?;
+
+        let lineage = ChampionLineage::from_champions(family.to_string(), name.to_string(), &champions);
+        debug!("Lineage '{}' has {} champions", family, lineage.members.len());
+
+        let options = ReplaceOptions::builder().upsert(true).build();
+        lineages_collection
+            .replace_one(doc! { "family": family }, &lineage, options)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Champion slugs played at any level: final champions from standings
+    /// plus the lower levels recorded in decklist material decks
+    pub async fn get_champion_slugs(&self) -> Result<Vec<String>, mongodb::error::Error> {
+        let decklists_collection = self.database.collection::<mongodb::bson::Document>("decklists");
+
+        let mut slugs: HashSet<String> = self.get_champion_slugs_from_standings().await?.into_iter().collect();
+        let lineage_slugs = decklists_collection.distinct("champion_lineage", None, None).await?;
+        slugs.extend(lineage_slugs.into_iter().filter_map(|slug| slug.as_str().map(str::to_string)));
+
+        let mut slugs: Vec<String> = slugs.into_iter().collect();
+        slugs.sort();
+        Ok(slugs)
+    }
+
     /// Get all unique champion slugs from standings
     pub async fn get_champion_slugs_from_standings(&self) -> Result<Vec<String>, mongodb::error::Error> {
         let standings_collection = self.database.collection::<mongodb::bson::Document>("standings");
@@ -191,16 +603,26 @@ impl CardSyncService {
         Ok(champions)
     }
 
-    /// Full sync: champions from standings and cards from decklists
+    /// Full sync: the card catalogue plus decklist cards it does not list,
+    /// champions from standings, then normalize stored champion strings
+    /// against the synced champions and refresh the deck statistics the
+    /// synced cards affect
     pub async fn full_sync(&self) -> Result<(usize, usize), Box<dyn std::error::Error>> {
         info!("Starting full card sync");
 
         // Sync champions first
-        let champion_slugs = self.get_champion_slugs_from_standings().await?;
+        let champion_slugs = self.get_champion_slugs().await?;
         let champions_synced = self.sync_champions(&champion_slugs).await?;
+        ChampionAliasService::new(self.database.clone()).normalize_stored().await?;
+
+        // Sync the catalogue, then cards only known from decklists
+        let catalogue = self.sync_catalogue().await?;
+        let cards_synced = catalogue.cards_synced() + self.sync_missing_cards().await?;
 
-        // Sync all cards from decklists
-        let cards_synced = self.sync_cards_from_decklists().await?;
+        // Decklists with a newly stored card still count it as uncatalogued
+        DeckStatsService::new(self.database.clone())
+            .refresh(&catalogue.changed_slugs)
+            .await?;
 
         info!("Full sync completed. Champions: {}, Cards: {}", champions_synced, cards_synced);
 
@@ -212,5 +634,78 @@ impl CardSyncService {
 mod tests {
     use super::*;
 
-    // Integration tests would require a test database
+    #[test]
+    fn test_card_update_unsets_removed_fields() {
+        let mut existing = Card::new("fireball".to_string(), "Fireball".to_string());
+        existing.id = Some(bson::oid::ObjectId::new());
+        existing.card_text = Some("Deal 3 damage.".to_string());
+        existing.cost = Some(2);
+
+        let mut card = Card::new("fireball".to_string(), "Fireball".to_string());
+        card.cost = Some(2);
+
+        let update = card_update(&card, Some(&existing)).unwrap();
+        assert_eq!(update.get_document("$unset").unwrap(), &doc! { "card_text": "" });
+        assert_eq!(update.get_document("$set").unwrap().get_i32("cost"), Ok(2));
+
+        // A new card has nothing to unset
+        let update = card_update(&card, None).unwrap();
+        assert!(!update.contains_key("$unset"));
+    }
+
+    #[test]
+    fn test_set_release_date_stored_as_datetime() {
+        let set = CardSet {
+            id: None,
+            prefix: "DOA".to_string(),
+            name: "Dawn of Ashes".to_string(),
+            language: None,
+            release_date: Some("2024-03-01T00:00:00Z".parse().unwrap()),
+        };
+
+        let fields = set_fields(&set);
+        assert!(fields.get_datetime("release_date").is_ok());
+        assert!(!fields.contains_key("language"));
+
+        // Reads back through the model the way stored sets are read
+        let mut stored = fields.clone();
+        stored.insert("prefix", "DOA");
+        let stored: CardSet = mongodb::bson::from_document(stored).unwrap();
+        assert_eq!(stored.release_date, set.release_date);
+        assert_eq!(mongodb::bson::to_document(&set).unwrap().get("release_date"), fields.get("release_date"));
+    }
+
+    fn printing(slug: &str, set_prefix: &str) -> Printing {
+        Printing {
+            id: None,
+            slug: slug.to_string(),
+            card_slug: "fireball".to_string(),
+            set_prefix: Some(set_prefix.to_string()),
+            set_name: None,
+            collector_number: None,
+            rarity: None,
+            illustrator: None,
+            image_url: None,
+            finishes: vec!["nonfoil".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_unchanged_card_gains_printing() {
+        let mut stored = printing("fireball-doa", "DOA");
+        stored.id = Some(bson::oid::ObjectId::new());
+        let fetched = [printing("fireball-doa", "DOA"), printing("fireball-alc", "ALC")];
+
+        let (changed, removed) = printing_changes(&[stored.clone()], &fetched);
+        assert_eq!(changed, [&fetched[1]]);
+        assert!(removed.is_empty());
+
+        // A changed printing is rewritten and one no longer listed is removed
+        let mut foiled = printing("fireball-doa", "DOA");
+        foiled.finishes.push("foil".to_string());
+        let fetched = [foiled];
+        let (changed, removed) = printing_changes(&[stored, printing("fireball-p24", "P24")], &fetched);
+        assert_eq!(changed, [&fetched[0]]);
+        assert_eq!(removed, ["fireball-p24"]);
+    }
 }
@@ -0,0 +1,340 @@
+use crate::models::{slugify, Champion, ChampionAlias, ChampionAliasListResponse, UnresolvedChampion};
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use log::{info, warn};
+use mongodb::bson::{doc, Document};
+use mongodb::options::{FindOptions, ReplaceOptions, UpdateModifications, UpdateOptions};
+use mongodb::{Collection, Database};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use utoipa::ToSchema;
+
+/// Collection of explicit upstream string to champion slug mappings
+pub const ALIASES_COLLECTION: &str = "champion_aliases";
+
+/// Collection of upstream champion strings that could not be matched
+pub const UNRESOLVED_COLLECTION: &str = "unresolved_champions";
+
+/// Collections whose `champion` field holds a champion slug
+const CHAMPION_COLLECTIONS: &[&str] = &["standings", "decklists"];
+
+/// Maps upstream champion strings to canonical champion slugs. Strings are
+/// compared slugified, so casing, punctuation and display names all match.
+#[derive(Debug, Default)]
+pub struct ChampionResolver {
+    index: HashMap<String, String>,
+}
+
+impl ChampionResolver {
+    /// Index champions by slug and display name. Explicit aliases take
+    /// precedence over names and slugs.
+    pub fn new(champions: &[Champion], aliases: &[ChampionAlias]) -> Self {
+        let mut index = HashMap::new();
+        for champion in champions {
+            index.entry(slugify(&champion.name)).or_insert_with(|| champion.slug.clone());
+        }
+        for champion in champions {
+            index.insert(slugify(&champion.slug), champion.slug.clone());
+        }
+        for alias in aliases {
+            index.insert(slugify(&alias.alias), alias.slug.clone());
+        }
+        Self { index }
+    }
+
+    /// Canonical slug for an upstream string, if it matches a champion or alias
+    pub fn resolve(&self, value: &str) -> Option<&str> {
+        self.index.get(&slugify(value)).map(String::as_str)
+    }
+}
+
+/// Event ID and player ID of a standing or decklist row
+type RowKey = (i32, String);
+
+/// Rewrite resolvable champion strings to their canonical slug and collect
+/// the rows each unmatched string was seen in
+fn resolve_rows<'a>(
+    resolver: &ChampionResolver,
+    rows: impl IntoIterator<Item = (i32, &'a str, &'a mut String)>,
+) -> BTreeMap<String, BTreeSet<RowKey>> {
+    let mut unresolved: BTreeMap<String, BTreeSet<RowKey>> = BTreeMap::new();
+    for (event_id, player_id, champion) in rows {
+        match resolver.resolve(champion) {
+            Some(slug) => {
+                if champion != slug {
+                    *champion = slug.to_string();
+                }
+            }
+            None => {
+                unresolved.entry(champion.clone()).or_default().insert((event_id, player_id.to_string()));
+            }
+        }
+    }
+    unresolved
+}
+
+fn row_documents(rows: &BTreeSet<RowKey>) -> Vec<Document> {
+    rows.iter()
+        .map(|(event_id, player_id)| doc! { "event_id": event_id, "player_id": player_id })
+        .collect()
+}
+
+/// Update adding `rows` to the distinct rows an unresolved string was seen
+/// in and counting them, so re-ingesting an event does not inflate
+/// `occurrences`
+fn record_rows(rows: &BTreeSet<RowKey>) -> UpdateModifications {
+    UpdateModifications::Pipeline(vec![
+        doc! {
+            "$set": {
+                "rows": { "$setUnion": [{ "$ifNull": ["$rows", []] }, { "$literal": row_documents(rows) }] },
+                "last_seen": mongodb::bson::DateTime::from_chrono(Utc::now()),
+            }
+        },
+        doc! { "$set": { "occurrences": { "$size": "$rows" } } },
+    ])
+}
+
+/// Outcome of normalizing stored champion strings
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct NormalizeReport {
+    pub standings_updated: u64,
+    pub decklists_updated: u64,
+    /// Strings still unmatched, most frequent first
+    pub unresolved: Vec<UnresolvedChampion>,
+}
+
+/// Resolves upstream champion strings to canonical slugs and manages the
+/// alias table
+pub struct ChampionAliasService {
+    database: Database,
+}
+
+impl ChampionAliasService {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    fn aliases_collection(&self) -> Collection<ChampionAlias> {
+        self.database.collection(ALIASES_COLLECTION)
+    }
+
+    fn unresolved_collection(&self) -> Collection<UnresolvedChampion> {
+        self.database.collection(UNRESOLVED_COLLECTION)
+    }
+
+    /// Build a resolver from the current champions and aliases
+    pub async fn resolver(&self) -> Result<ChampionResolver, mongodb::error::Error> {
+        let champions: Vec<Champion> = self
+            .database
+            .collection::<Champion>("champions")
+            .find(None, None)
+            .await?
+            .try_collect()
+            .await?;
+        let aliases: Vec<ChampionAlias> = self.aliases_collection().find(None, None).await?.try_collect().await?;
+
+        Ok(ChampionResolver::new(&champions, &aliases))
+    }
+
+    /// Replace resolvable champion strings with their canonical slug and
+    /// record the rest, which are left as they are. Rows are given as event
+    /// ID, player ID and champion string. Returns the unmatched strings.
+    pub async fn normalize<'a>(
+        &self,
+        resolver: &ChampionResolver,
+        rows: impl IntoIterator<Item = (i32, &'a str, &'a mut String)>,
+    ) -> Result<Vec<String>, mongodb::error::Error> {
+        let unresolved = resolve_rows(resolver, rows);
+
+        let options = UpdateOptions::builder().upsert(true).build();
+        for (value, rows) in &unresolved {
+            warn!("Unresolved champion '{}'", value);
+            self.unresolved_collection()
+                .update_one(doc! { "value": value }, record_rows(rows), options.clone())
+                .await?;
+        }
+
+        Ok(unresolved.into_keys().collect())
+    }
+
+    /// Rewrite stored standings and decklists to canonical slugs and rebuild
+    /// the unresolved list from what remains
+    pub async fn normalize_stored(&self) -> Result<NormalizeReport, mongodb::error::Error> {
+        let resolver = self.resolver().await?;
+        let mut report = NormalizeReport::default();
+        let mut unresolved: BTreeMap<String, BTreeSet<RowKey>> = BTreeMap::new();
+
+        for &name in CHAMPION_COLLECTIONS {
+            let collection: Collection<Document> = self.database.collection(name);
+            let mut updated = 0;
+
+            for value in collection.distinct("champion", None, None).await? {
+                let Some(value) = value.as_str() else { continue };
+                match resolver.resolve(value) {
+                    Some(slug) if slug == value => {}
+                    Some(slug) => {
+                        updated += collection
+                            .update_many(doc! { "champion": value }, doc! { "$set": { "champion": slug } }, None)
+                            .await?
+                            .modified_count;
+                    }
+                    None => {
+                        let options = FindOptions::builder()
+                            .projection(doc! { "event_id": 1, "player_id": 1 })
+                            .build();
+                        let mut cursor = collection.find(doc! { "champion": value }, options).await?;
+                        let rows = unresolved.entry(value.to_string()).or_default();
+                        while let Some(row) = cursor.try_next().await? {
+                            if let (Ok(event_id), Ok(player_id)) = (row.get_i32("event_id"), row.get_str("player_id")) {
+                                rows.insert((event_id, player_id.to_string()));
+                            }
+                        }
+                    }
+                }
+            }
+
+            info!("Normalized {} champion strings in {}", updated, name);
+            match name {
+                "standings" => report.standings_updated = updated,
+                _ => report.decklists_updated = updated,
+            }
+        }
+
+        let values: Vec<&str> = unresolved.keys().map(String::as_str).collect();
+        self.unresolved_collection()
+            .delete_many(doc! { "value": { "$nin": values } }, None)
+            .await?;
+
+        let now = Utc::now();
+        let options = UpdateOptions::builder().upsert(true).build();
+        for (value, rows) in unresolved {
+            let entry = UnresolvedChampion { value, occurrences: rows.len() as i64, last_seen: now };
+            let update = doc! {
+                "$set": {
+                    "rows": row_documents(&rows),
+                    "occurrences": entry.occurrences,
+                    "last_seen": mongodb::bson::DateTime::from_chrono(now),
+                }
+            };
+            self.unresolved_collection()
+                .update_one(doc! { "value": &entry.value }, update, options.clone())
+                .await?;
+            report.unresolved.push(entry);
+        }
+        report.unresolved.sort_by(|a, b| b.occurrences.cmp(&a.occurrences).then_with(|| a.value.cmp(&b.value)));
+
+        Ok(report)
+    }
+
+    /// All aliases and unresolved strings, most frequent first
+    pub async fn list(&self) -> Result<ChampionAliasListResponse, mongodb::error::Error> {
+        let aliases = self
+            .aliases_collection()
+            .find(None, FindOptions::builder().sort(doc! { "alias": 1 }).build())
+            .await?
+            .try_collect()
+            .await?;
+        let unresolved = self
+            .unresolved_collection()
+            .find(None, FindOptions::builder().sort(doc! { "occurrences": -1, "value": 1 }).build())
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(ChampionAliasListResponse { aliases, unresolved })
+    }
+
+    /// Map `alias` to a champion. Returns `None` if there is no champion with `slug`.
+    pub async fn set_alias(&self, alias: &str, slug: &str) -> Result<Option<ChampionAlias>, mongodb::error::Error> {
+        let exists = self
+            .database
+            .collection::<Document>("champions")
+            .count_documents(doc! { "slug": slug }, None)
+            .await?
+            > 0;
+        if !exists {
+            return Ok(None);
+        }
+
+        let alias = ChampionAlias {
+            id: None,
+            alias: slugify(alias),
+            slug: slug.to_string(),
+            created_at: Utc::now(),
+        };
+        self.aliases_collection()
+            .replace_one(
+                doc! { "alias": &alias.alias },
+                &alias,
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await?;
+
+        Ok(Some(alias))
+    }
+
+    /// Remove an alias, returning whether it existed
+    pub async fn remove_alias(&self, alias: &str) -> Result<bool, mongodb::error::Error> {
+        let result = self
+            .aliases_collection()
+            .delete_one(doc! { "alias": slugify(alias) }, None)
+            .await?;
+        Ok(result.deleted_count > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(alias: &str, slug: &str) -> ChampionAlias {
+        ChampionAlias {
+            id: None,
+            alias: alias.to_string(),
+            slug: slug.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_slug_name_and_alias() {
+        let champions = [
+            Champion::new("lorraine".to_string(), "Lorraine, Crux Knight".to_string()),
+            Champion::new("tristan-hired-blade".to_string(), "Tristan, Hired Blade".to_string()),
+        ];
+        let resolver = ChampionResolver::new(&champions, &[alias("lorraine-old", "lorraine")]);
+
+        assert_eq!(resolver.resolve("lorraine"), Some("lorraine"));
+        assert_eq!(resolver.resolve("Lorraine, Crux Knight"), Some("lorraine"));
+        assert_eq!(resolver.resolve("TRISTAN_hired blade"), Some("tristan-hired-blade"));
+        assert_eq!(resolver.resolve("Lorraine Old"), Some("lorraine"));
+        assert_eq!(resolver.resolve("rai"), None);
+    }
+
+    #[test]
+    fn test_resolve_rows_counts_each_row_once() {
+        let resolver = ChampionResolver::new(&[Champion::new("lorraine".to_string(), "Lorraine".to_string())], &[]);
+        let mut rows = [
+            (1, "p1", "LORRAINE".to_string()),
+            (1, "p2", "Mystery".to_string()),
+            // The same row again, as when an event is re-ingested
+            (1, "p2", "Mystery".to_string()),
+            (2, "p2", "Mystery".to_string()),
+        ];
+
+        let unresolved = resolve_rows(&resolver, rows.iter_mut().map(|(e, p, c)| (*e, *p, c)));
+
+        assert_eq!(rows[0].2, "lorraine");
+        assert_eq!(rows[1].2, "Mystery");
+        assert_eq!(unresolved.len(), 1);
+        assert_eq!(unresolved["Mystery"], BTreeSet::from([(1, "p2".to_string()), (2, "p2".to_string())]));
+    }
+
+    #[test]
+    fn test_alias_overrides_champion() {
+        let champions = [Champion::new("lorraine".to_string(), "Lorraine".to_string())];
+        let resolver = ChampionResolver::new(&champions, &[alias("lorraine", "lorraine-crux-knight")]);
+
+        assert_eq!(resolver.resolve("Lorraine"), Some("lorraine-crux-knight"));
+    }
+}
@@ -0,0 +1,244 @@
+//! Flat Arrow schemas for writing tournament data as Parquet. Column names
+//! and types are part of the export contract: add columns at the end and
+//! never rename or retype existing ones.
+
+use crate::models::{Decklist, DecklistCard, Event, Standing};
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int32Array, RecordBatch, StringArray,
+    TimestampMillisecondArray,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+/// A model that can be flattened into Arrow record batches
+pub trait ColumnarRecord {
+    fn schema() -> SchemaRef;
+
+    fn batch(records: &[Self]) -> Result<RecordBatch, ArrowError>
+    where
+        Self: Sized;
+}
+
+fn timestamp() -> DataType {
+    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+}
+
+fn int32<T>(records: &[T], value: impl Fn(&T) -> i32) -> ArrayRef {
+    Arc::new(records.iter().map(value).collect::<Int32Array>())
+}
+
+fn int32_opt<T>(records: &[T], value: impl Fn(&T) -> Option<i32>) -> ArrayRef {
+    Arc::new(records.iter().map(value).collect::<Int32Array>())
+}
+
+fn float64_opt<T>(records: &[T], value: impl Fn(&T) -> Option<f64>) -> ArrayRef {
+    Arc::new(records.iter().map(value).collect::<Float64Array>())
+}
+
+fn boolean<T>(records: &[T], value: impl Fn(&T) -> bool) -> ArrayRef {
+    Arc::new(records.iter().map(|r| Some(value(r))).collect::<BooleanArray>())
+}
+
+fn utf8<'a, T: 'a>(records: &'a [T], value: impl Fn(&'a T) -> &'a str) -> ArrayRef {
+    Arc::new(records.iter().map(|r| Some(value(r))).collect::<StringArray>())
+}
+
+fn utf8_opt<'a, T: 'a>(records: &'a [T], value: impl Fn(&'a T) -> Option<&'a str>) -> ArrayRef {
+    Arc::new(records.iter().map(value).collect::<StringArray>())
+}
+
+fn timestamp_opt<T>(records: &[T], value: impl Fn(&T) -> Option<DateTime<Utc>>) -> ArrayRef {
+    let millis = records.iter().map(|r| value(r).map(|d| d.timestamp_millis()));
+    Arc::new(millis.collect::<TimestampMillisecondArray>().with_timezone("UTC"))
+}
+
+impl ColumnarRecord for Event {
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("event_id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+            Field::new("format", DataType::Utf8, false),
+            Field::new("status", DataType::Utf8, false),
+            Field::new("ranked", DataType::Boolean, false),
+            Field::new("player_count", DataType::Int32, false),
+            Field::new("start_date", timestamp(), true),
+            Field::new("end_date", timestamp(), true),
+            Field::new("location", DataType::Utf8, true),
+            Field::new("organizer", DataType::Utf8, true),
+            Field::new("rounds", DataType::Int32, true),
+            Field::new("has_decklists", DataType::Boolean, false),
+            Field::new("tier", DataType::Utf8, true),
+        ]))
+    }
+
+    fn batch(events: &[Self]) -> Result<RecordBatch, ArrowError> {
+        RecordBatch::try_new(
+            Self::schema(),
+            vec![
+                int32(events, |e| e.event_id),
+                utf8(events, |e| &e.name),
+                utf8(events, |e| e.format.as_str()),
+                utf8(events, |e| &e.status),
+                boolean(events, |e| e.ranked),
+                int32(events, |e| e.player_count),
+                timestamp_opt(events, |e| e.start_date),
+                timestamp_opt(events, |e| e.end_date),
+                utf8_opt(events, |e| e.location.as_deref()),
+                utf8_opt(events, |e| e.organizer.as_deref()),
+                int32_opt(events, |e| e.rounds),
+                boolean(events, |e| e.has_decklists),
+                utf8_opt(events, |e| e.tier.as_deref()),
+            ],
+        )
+    }
+}
+
+impl ColumnarRecord for Standing {
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("event_id", DataType::Int32, false),
+            Field::new("player_id", DataType::Utf8, false),
+            Field::new("player_name", DataType::Utf8, false),
+            Field::new("rank", DataType::Int32, false),
+            Field::new("champion", DataType::Utf8, false),
+            Field::new("wins", DataType::Int32, false),
+            Field::new("losses", DataType::Int32, false),
+            Field::new("draws", DataType::Int32, false),
+            Field::new("match_win_rate", DataType::Float64, true),
+            Field::new("has_decklist", DataType::Boolean, false),
+        ]))
+    }
+
+    fn batch(standings: &[Self]) -> Result<RecordBatch, ArrowError> {
+        RecordBatch::try_new(
+            Self::schema(),
+            vec![
+                int32(standings, |s| s.event_id),
+                utf8(standings, |s| &s.player_id),
+                utf8(standings, |s| &s.player_name),
+                int32(standings, |s| s.rank),
+                utf8(standings, |s| &s.champion),
+                int32(standings, |s| s.wins),
+                int32(standings, |s| s.losses),
+                int32(standings, |s| s.draws),
+                float64_opt(standings, |s| s.match_win_rate),
+                boolean(standings, |s| s.has_decklist),
+            ],
+        )
+    }
+}
+
+/// One card entry of a decklist, with the decklist's identifying columns
+struct DecklistEntry<'a> {
+    decklist: &'a Decklist,
+    section: &'static str,
+    card: &'a DecklistCard,
+}
+
+/// Decklists are written one row per card entry, so card usage can be
+/// aggregated without unnesting
+impl ColumnarRecord for Decklist {
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("event_id", DataType::Int32, false),
+            Field::new("player_id", DataType::Utf8, false),
+            Field::new("player_name", DataType::Utf8, false),
+            Field::new("champion", DataType::Utf8, false),
+            Field::new("rank", DataType::Int32, false),
+            Field::new("section", DataType::Utf8, false),
+            Field::new("slug", DataType::Utf8, false),
+            Field::new("name", DataType::Utf8, false),
+            Field::new("quantity", DataType::Int32, false),
+            Field::new("card_type", DataType::Utf8, true),
+            Field::new("element", DataType::Utf8, true),
+            Field::new("cost", DataType::Int32, true),
+        ]))
+    }
+
+    fn batch(decklists: &[Self]) -> Result<RecordBatch, ArrowError> {
+        let entries: Vec<DecklistEntry> = decklists
+            .iter()
+            .flat_map(|decklist| {
+                let main = decklist.main_deck.iter().map(move |card| DecklistEntry {
+                    decklist,
+                    section: "main",
+                    card,
+                });
+                let side = decklist.sideboard.iter().map(move |card| DecklistEntry {
+                    decklist,
+                    section: "sideboard",
+                    card,
+                });
+                let material = decklist.material_deck.iter().map(move |card| DecklistEntry {
+                    decklist,
+                    section: "material",
+                    card,
+                });
+                main.chain(side).chain(material)
+            })
+            .collect();
+
+        RecordBatch::try_new(
+            Self::schema(),
+            vec![
+                int32(&entries, |e| e.decklist.event_id),
+                utf8(&entries, |e| &e.decklist.player_id),
+                utf8(&entries, |e| &e.decklist.player_name),
+                utf8(&entries, |e| &e.decklist.champion),
+                int32(&entries, |e| e.decklist.rank),
+                utf8(&entries, |e| e.section),
+                utf8(&entries, |e| &e.card.slug),
+                utf8(&entries, |e| &e.card.name),
+                int32(&entries, |e| e.card.quantity),
+                utf8_opt(&entries, |e| e.card.card_type.as_deref()),
+                utf8_opt(&entries, |e| e.card.element.as_deref()),
+                int32_opt(&entries, |e| e.card.cost),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::EventFormat;
+
+    fn card(slug: &str, quantity: i32) -> DecklistCard {
+        DecklistCard {
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            quantity,
+            card_type: None,
+            element: Some("FIRE".to_string()),
+            cost: Some(2),
+            level: None,
+        }
+    }
+
+    #[test]
+    fn test_decklists_flatten_to_card_rows() {
+        let mut decklist = Decklist::new(7, "p1".to_string(), "Jane".to_string(), "lorraine".to_string(), 1);
+        decklist.main_deck = vec![card("fireball", 4), card("spark", 2)];
+        decklist.sideboard = vec![card("ward", 1)];
+
+        let batch = Decklist::batch(&[decklist]).unwrap();
+        assert_eq!(batch.num_rows(), 3);
+
+        let sections = batch.column(5).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(sections.value(2), "sideboard");
+        let quantities = batch.column(8).as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(quantities.values(), &[4, 2, 1]);
+    }
+
+    #[test]
+    fn test_event_batch_matches_schema() {
+        let mut event = Event::new(1, "Regional".to_string(), EventFormat::Standard, "complete".to_string());
+        event.start_date = Some(Utc::now());
+
+        let batch = Event::batch(&[event]).unwrap();
+        assert_eq!(batch.schema(), Event::schema());
+        assert_eq!(batch.column(6).null_count(), 0);
+        assert_eq!(batch.column(7).null_count(), 1);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::models::{Card, Decklist, DECKLIST_SCHEMA_VERSION};
+use futures::stream::TryStreamExt;
+use log::info;
+use mongodb::bson::{doc, Document};
+use mongodb::options::FindOptions;
+use mongodb::{Collection, Database};
+use std::collections::{HashMap, HashSet};
+
+/// Decklists upgraded per backfill batch
+const BACKFILL_BATCH_SIZE: i64 = 500;
+
+/// Decklists whose statistics may change once `changed_slugs` are synced.
+/// Statistics only cover the main deck.
+fn refresh_filter(changed_slugs: &[String]) -> Document {
+    let mut conditions = vec![doc! { "deck_stats.uncatalogued_cards": { "$gt": 0 } }];
+    if !changed_slugs.is_empty() {
+        conditions.push(doc! { "main_deck.slug": { "$in": changed_slugs } });
+    }
+    doc! { "$or": conditions }
+}
+
+/// Upgrades decklist documents to the current schema version: separates
+/// the material deck and computes statistics from the card catalogue
+pub struct DeckStatsService {
+    database: Database,
+}
+
+impl DeckStatsService {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// Catalogue entries for every card in the given decklists
+    async fn catalogue(&self, decklists: &[Decklist]) -> Result<HashMap<String, Card>, mongodb::error::Error> {
+        let slugs: HashSet<&str> = decklists
+            .iter()
+            .flat_map(|d| d.main_deck.iter().chain(&d.sideboard).chain(&d.material_deck))
+            .map(|c| c.slug.as_str())
+            .collect();
+        if slugs.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let cards: Collection<Card> = self.database.collection("cards");
+        let slugs: Vec<&str> = slugs.into_iter().collect();
+        let cards: Vec<Card> = cards
+            .find(doc! { "slug": { "$in": slugs } }, None)
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(cards.into_iter().map(|card| (card.slug.clone(), card)).collect())
+    }
+
+    /// Upgrade decklists about to be stored to the current schema version
+    pub async fn enrich(&self, decklists: &mut [Decklist]) -> Result<(), mongodb::error::Error> {
+        self.upgrade(decklists, DECKLIST_SCHEMA_VERSION).await
+    }
+
+    async fn upgrade(&self, decklists: &mut [Decklist], version: i32) -> Result<(), mongodb::error::Error> {
+        let catalogue = self.catalogue(decklists).await?;
+        for decklist in decklists {
+            decklist.upgrade_to(version, &catalogue);
+        }
+        Ok(())
+    }
+
+    /// Upgrade every decklist below schema `version` to it, returning the
+    /// number of documents updated. Each released migration passes its own
+    /// version so it keeps doing what it did when it was released.
+    pub async fn backfill(&self, version: i32) -> Result<u64, mongodb::error::Error> {
+        let decklists: Collection<Decklist> = self.database.collection("decklists");
+        // Also matches documents without a schema_version
+        let outdated = doc! { "schema_version": { "$not": { "$gte": version } } };
+        let options = FindOptions::builder().limit(BACKFILL_BATCH_SIZE).build();
+
+        let mut upgraded = 0;
+        loop {
+            // Upgraded documents no longer match, so each batch starts over
+            let mut batch: Vec<Decklist> = decklists
+                .find(outdated.clone(), options.clone())
+                .await?
+                .try_collect()
+                .await?;
+            if batch.is_empty() {
+                break;
+            }
+
+            self.upgrade(&mut batch, version).await?;
+
+            for decklist in &batch {
+                let filter = doc! { "event_id": decklist.event_id, "player_id": &decklist.player_id };
+                let mut set = doc! {
+                    "deck_stats": mongodb::bson::to_bson(&decklist.deck_stats)?,
+                    "schema_version": decklist.schema_version,
+                };
+                if version >= 2 {
+                    set.insert("sideboard", mongodb::bson::to_bson(&decklist.sideboard)?);
+                    set.insert("sideboard_count", decklist.sideboard_count);
+                    set.insert("material_deck", mongodb::bson::to_bson(&decklist.material_deck)?);
+                    set.insert("champion_lineage", &decklist.champion_lineage);
+                }
+                decklists.update_one(filter, doc! { "$set": set }, None).await?;
+            }
+
+            upgraded += batch.len() as u64;
+            info!("Upgraded {} decklists to schema version {}", upgraded, version);
+        }
+
+        Ok(upgraded)
+    }
+
+    /// Recompute card frequencies of decklists with a material deck, whose
+    /// material cards were once left out of the counts. Returns the number
+    /// of documents updated.
+    pub async fn recount_frequencies(&self) -> Result<u64, mongodb::error::Error> {
+        let decklists: Collection<Decklist> = self.database.collection("decklists");
+        let mut cursor = decklists.find(doc! { "material_deck.0": { "$exists": true } }, None).await?;
+
+        let mut recounted = 0;
+        while let Some(mut decklist) = cursor.try_next().await? {
+            decklist.calculate_frequencies();
+            let filter = doc! { "event_id": decklist.event_id, "player_id": &decklist.player_id };
+            let update = doc! {
+                "$set": { "card_frequencies": mongodb::bson::to_bson(&decklist.card_frequencies)? }
+            };
+            decklists.update_one(filter, update, None).await?;
+            recounted += 1;
+        }
+
+        info!("Recounted card frequencies of {} decklists", recounted);
+        Ok(recounted)
+    }
+
+    /// Recompute the statistics of decklists that count uncatalogued cards
+    /// or contain one of `changed_slugs`, after those cards were synced.
+    /// Returns the number of documents whose statistics changed.
+    pub async fn refresh(&self, changed_slugs: &[String]) -> Result<u64, mongodb::error::Error> {
+        let decklists: Collection<Decklist> = self.database.collection("decklists");
+        let options = FindOptions::builder().batch_size(BACKFILL_BATCH_SIZE as u32).build();
+        let mut cursor = decklists.find(refresh_filter(changed_slugs), options).await?;
+
+        let mut refreshed = 0;
+        let mut batch = Vec::new();
+        loop {
+            let decklist = cursor.try_next().await?;
+            let done = decklist.is_none();
+            batch.extend(decklist);
+            if batch.len() < BACKFILL_BATCH_SIZE as usize && !done {
+                continue;
+            }
+
+            let catalogue = self.catalogue(&batch).await?;
+            for mut decklist in batch.drain(..) {
+                let previous = decklist.deck_stats.take();
+                decklist.calculate_stats(&catalogue);
+                if decklist.deck_stats == previous {
+                    continue;
+                }
+
+                let filter = doc! { "event_id": decklist.event_id, "player_id": &decklist.player_id };
+                let update = doc! { "$set": { "deck_stats": mongodb::bson::to_bson(&decklist.deck_stats)? } };
+                decklists.update_one(filter, update, None).await?;
+                refreshed += 1;
+            }
+
+            if done {
+                break;
+            }
+        }
+
+        info!("Refreshed deck statistics of {} decklists", refreshed);
+        Ok(refreshed)
+    }
+
+    /// Remove computed statistics and version markers
+    pub async fn clear(&self) -> Result<u64, mongodb::error::Error> {
+        let decklists: Collection<Document> = self.database.collection("decklists");
+        let result = decklists
+            .update_many(doc! {}, doc! { "$unset": { "deck_stats": "", "schema_version": "" } }, None)
+            .await?;
+        Ok(result.modified_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_filter() {
+        let uncatalogued = doc! { "deck_stats.uncatalogued_cards": { "$gt": 0 } };
+        assert_eq!(refresh_filter(&[]), doc! { "$or": [uncatalogued.clone()] });
+
+        let changed = ["fireball".to_string()];
+        assert_eq!(
+            refresh_filter(&changed),
+            doc! { "$or": [uncatalogued, { "main_deck.slug": { "$in": ["fireball"] } }] }
+        );
+    }
+}
@@ -1,10 +1,82 @@
 use crate::clients::{ApiClientError, OmnidexApiClient, OmniWebApiClient};
+use crate::config::Config;
 use crate::models::{Event, Standing, Decklist, CrawlerState};
+use crate::services::champion_aliases::ChampionResolver;
+use crate::services::{ChampionAliasService, DeckStatsService};
 use mongodb::{Database, Collection};
-use mongodb::bson::doc;
+use mongodb::bson::{doc, Document};
+use mongodb::options::UpdateOptions;
 use log::{info, warn, error, debug};
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Error, Debug)]
+pub enum CrawlError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiClientError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] mongodb::error::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] bson::ser::Error),
+}
+
+/// Options for ingesting a single event
+#[derive(Debug, Clone, Copy, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct IngestOptions {
+    /// Fetch standings even for uninteresting events, and delete stored
+    /// standings of players no longer in the results
+    #[serde(default)]
+    pub force_standings: bool,
+
+    /// Fetch decklists even if the event reports none, and delete stored
+    /// decklists of players without a published decklist
+    #[serde(default)]
+    pub force_decklists: bool,
+}
+
+impl IngestOptions {
+    fn forced(&self) -> bool {
+        self.force_standings || self.force_decklists
+    }
+
+    /// Whether standings (and possibly decklists) are fetched for `event`
+    fn fetches_details(&self, event: &Event) -> bool {
+        event.is_interesting() || self.forced()
+    }
+
+    /// Whether decklists are fetched for `event` once its standings are in
+    fn fetches_decklists(&self, event: &Event) -> bool {
+        event.has_decklists || self.force_decklists
+    }
+}
+
+/// Filter deleting an event's rows for players other than `kept`. `None`
+/// when `kept` is empty: an empty fetch is far more likely an upstream
+/// failure than results without players, so nothing is pruned.
+fn prune_filter<S: AsRef<str>>(event_id: i32, kept: &[S]) -> Option<Document> {
+    if kept.is_empty() {
+        return None;
+    }
+    let kept: Vec<&str> = kept.iter().map(AsRef::as_ref).collect();
+    Some(doc! { "event_id": event_id, "player_id": { "$nin": kept } })
+}
+
+/// Outcome of ingesting a single event
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct IngestReport {
+    pub event_id: i32,
+    pub name: String,
+    pub standings_saved: usize,
+    pub standings_removed: u64,
+    pub decklists_saved: usize,
+    pub decklists_removed: u64,
+}
 
 /// Service for crawling events from the Omnidex API
 /// Uses sequential discovery since no list endpoint exists
@@ -31,129 +103,47 @@ impl EventCrawler {
         }
     }
 
-    /// Crawl events starting from a specific ID until max_404s consecutive 404s
+    /// Create a crawler with API clients built from the configuration
+    pub fn from_config(config: &Config, database: Database) -> Self {
+        let omnidex = OmnidexApiClient::new(config.request_delay_ms, config.request_timeout_secs, config.max_retries);
+        let omni_web = OmniWebApiClient::new(config.request_delay_ms, config.request_timeout_secs, config.max_retries);
+
+        Self::new(Arc::new(omnidex), Arc::new(omni_web), database, config.crawler_max_404s)
+    }
+
+    /// Crawl events starting from a specific ID until max_404s consecutive 404s,
+    /// or until `end_id` (inclusive) when given
     /// This is used for historical backfill
-    pub async fn crawl_historical_events(&self, start_id: i32) -> Result<i32, Box<dyn std::error::Error>> {
+    pub async fn crawl_historical_events(
+        &self,
+        start_id: i32,
+        end_id: Option<i32>,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
         info!("Starting historical event crawl from event ID {}", start_id);
 
-        let events_collection: Collection<Event> = self.database.collection("events");
-        let standings_collection: Collection<Standing> = self.database.collection("standings");
-        let decklists_collection: Collection<Decklist> = self.database.collection("decklists");
         let crawler_collection: Collection<CrawlerState> = self.database.collection("crawler_state");
 
         let mut current_id = start_id;
         let mut consecutive_404s = 0;
         let mut events_found = 0;
 
-        while consecutive_404s < self.max_404s {
+        while consecutive_404s < self.max_404s && end_id.is_none_or(|end| current_id <= end) {
             debug!("Checking event ID: {}", current_id);
 
-            match self.omnidex_client.fetch_event(current_id).await {
-                Ok(Some(mut event)) => {
+            match self.ingest_event(current_id, IngestOptions::default()).await {
+                Ok(Some(_)) => {
                     consecutive_404s = 0;
                     events_found += 1;
-
-                    info!("Found event {}: {}", event.event_id, event.name);
-
-                    // Fetch additional statistics if available
-                    if let Ok(Some((player_count, has_decklists))) =
-                        self.omnidex_client.fetch_event_statistics(current_id).await
-                    {
-                        event.player_count = player_count;
-                        event.has_decklists = has_decklists;
-                    }
-
-                    // Only crawl details for interesting events
-                    if event.is_interesting() {
-                        info!("Event {} is interesting, fetching details...", event.event_id);
-
-                        // Fetch standings
-                        match self.omnidex_client.fetch_standings(current_id).await {
-                            Ok(standings) => {
-                                info!("Found {} standings for event {}", standings.len(), event.event_id);
-
-                                // Save standings
-                                for standing in &standings {
-                                    let filter = doc! {
-                                        "event_id": standing.event_id,
-                                        "player_id": &standing.player_id
-                                    };
-                                    let update = doc! {
-                                        "$set": mongodb::bson::to_document(&standing)?
-                                    };
-                                    use mongodb::options::UpdateOptions;
-                                    let options = UpdateOptions::builder().upsert(true).build();
-                                    standings_collection
-                                        .update_one(filter, update, options)
-                                        .await?;
-                                }
-
-                                // Fetch decklists if available
-                                if event.has_decklists {
-                                    let player_ids: Vec<String> = standings
-                                        .iter()
-                                        .filter(|s| s.has_decklist)
-                                        .map(|s| s.player_id.clone())
-                                        .collect();
-
-                                    info!("Fetching {} decklists for event {}", player_ids.len(), event.event_id);
-
-                                    match self.omni_web_client.fetch_decklists(current_id, &player_ids).await {
-                                        Ok(decklists) => {
-                                            info!("Found {} decklists for event {}", decklists.len(), event.event_id);
-
-                                            for decklist in decklists {
-                                                let filter = doc! {
-                                                    "event_id": decklist.event_id,
-                                                    "player_id": &decklist.player_id
-                                                };
-                                                let update = doc! {
-                                                    "$set": mongodb::bson::to_document(&decklist)?
-                                                };
-                                                use mongodb::options::UpdateOptions;
-                                                let options = UpdateOptions::builder().upsert(true).build();
-                                                decklists_collection
-                                                    .update_one(filter, update, options)
-                                                    .await?;
-                                            }
-                                        }
-                                        Err(e) => {
-                                            warn!("Error fetching decklists for event {}: {}", event.event_id, e);
-                                        }
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                warn!("Error fetching standings for event {}: {}", event.event_id, e);
-                            }
-                        }
-                    } else {
-                        debug!("Event {} is not interesting, skipping details", event.event_id);
-                    }
-
-                    // Save event
-                    let filter = doc! { "event_id": event.event_id };
-                    let update = doc! {
-                        "$set": mongodb::bson::to_document(&event)?
-                    };
-                    use mongodb::options::UpdateOptions;
-                    let options = UpdateOptions::builder().upsert(true).build();
-                    events_collection
-                        .update_one(filter, update, options)
-                        .await?;
                 }
                 Ok(None) => {
-                    debug!("Event {} returned empty data", current_id);
-                    consecutive_404s += 1;
-                }
-                Err(ApiClientError::NotFound) => {
-                    debug!("Event {} not found (404)", current_id);
+                    debug!("Event {} not found", current_id);
                     consecutive_404s += 1;
                 }
-                Err(e) => {
+                Err(CrawlError::Api(e)) => {
                     error!("Error fetching event {}: {}", current_id, e);
                     consecutive_404s += 1;
                 }
+                Err(e) => return Err(e.into()),
             }
 
             current_id += 1;
@@ -172,6 +162,181 @@ impl EventCrawler {
         Ok(current_id - 1)
     }
 
+    /// Fetch one event and store it with its standings and decklists.
+    /// Details are only fetched for interesting events unless forced; forcing
+    /// also removes stored rows the organizer has since dropped from the
+    /// results. Returns `None` if the event does not exist.
+    pub async fn ingest_event(
+        &self,
+        event_id: i32,
+        options: IngestOptions,
+    ) -> Result<Option<IngestReport>, CrawlError> {
+        let mut event = match self.omnidex_client.fetch_event(event_id).await {
+            Ok(Some(event)) => event,
+            Ok(None) | Err(ApiClientError::NotFound) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        info!("Found event {}: {}", event.event_id, event.name);
+
+        let mut report = IngestReport {
+            event_id,
+            name: event.name.clone(),
+            ..Default::default()
+        };
+
+        // Fetch additional statistics if available
+        if let Ok(Some((player_count, has_decklists))) =
+            self.omnidex_client.fetch_event_statistics(event_id).await
+        {
+            event.player_count = player_count;
+            event.has_decklists = has_decklists;
+        }
+
+        // Only crawl details for interesting events
+        if options.fetches_details(&event) {
+            info!("Fetching details for event {}...", event.event_id);
+
+            match self.omnidex_client.fetch_standings(event_id).await {
+                Ok(mut standings) => {
+                    info!("Found {} standings for event {}", standings.len(), event.event_id);
+
+                    let aliases = ChampionAliasService::new(self.database.clone());
+                    let resolver = aliases.resolver().await?;
+                    aliases
+                        .normalize(&resolver, standings.iter_mut().map(|s| (s.event_id, s.player_id.as_str(), &mut s.champion)))
+                        .await?;
+
+                    (report.standings_saved, report.standings_removed) =
+                        self.save_standings(event_id, &standings, options.force_standings).await?;
+
+                    // Fetch decklists if available
+                    if options.fetches_decklists(&event) {
+                        (report.decklists_saved, report.decklists_removed) =
+                            self.ingest_decklists(event_id, &standings, &resolver, options).await?;
+                    }
+                }
+                Err(e) if options.forced() => return Err(e.into()),
+                Err(e) => {
+                    warn!("Error fetching standings for event {}: {}", event.event_id, e);
+                }
+            }
+        } else {
+            debug!("Event {} is not interesting, skipping details", event.event_id);
+        }
+
+        // Save event
+        let events_collection: Collection<Event> = self.database.collection("events");
+        let filter = doc! { "event_id": event.event_id };
+        let update = doc! {
+            "$set": event.to_document()?
+        };
+        let options = UpdateOptions::builder().upsert(true).build();
+        events_collection
+            .update_one(filter, update, options)
+            .await?;
+
+        Ok(Some(report))
+    }
+
+    /// Upsert an event's standings. With `replace`, standings of players no
+    /// longer in the results are deleted, unless no standings were fetched.
+    async fn save_standings(
+        &self,
+        event_id: i32,
+        standings: &[Standing],
+        replace: bool,
+    ) -> Result<(usize, u64), CrawlError> {
+        let standings_collection: Collection<Standing> = self.database.collection("standings");
+
+        for standing in standings {
+            let filter = doc! {
+                "event_id": standing.event_id,
+                "player_id": &standing.player_id
+            };
+            let update = doc! {
+                "$set": mongodb::bson::to_document(&standing)?
+            };
+            let options = UpdateOptions::builder().upsert(true).build();
+            standings_collection
+                .update_one(filter, update, options)
+                .await?;
+        }
+
+        let mut removed = 0;
+        if replace {
+            let player_ids: Vec<&str> = standings.iter().map(|s| s.player_id.as_str()).collect();
+            match prune_filter(event_id, &player_ids) {
+                Some(filter) => removed = standings_collection.delete_many(filter, None).await?.deleted_count,
+                None => warn!("No standings fetched for event {}, keeping stored standings", event_id),
+            }
+        }
+
+        Ok((standings.len(), removed))
+    }
+
+    /// Fetch and upsert the decklists of players with a published decklist.
+    /// When forced, decklists of players without one in the standings are
+    /// deleted, unless no decklists were fetched.
+    async fn ingest_decklists(
+        &self,
+        event_id: i32,
+        standings: &[Standing],
+        resolver: &ChampionResolver,
+        options: IngestOptions,
+    ) -> Result<(usize, u64), CrawlError> {
+        let decklists_collection: Collection<Decklist> = self.database.collection("decklists");
+
+        let player_ids: Vec<String> = standings
+            .iter()
+            .filter(|s| s.has_decklist)
+            .map(|s| s.player_id.clone())
+            .collect();
+
+        info!("Fetching {} decklists for event {}", player_ids.len(), event_id);
+
+        let mut decklists = match self.omni_web_client.fetch_decklists(event_id, &player_ids).await {
+            Ok(decklists) => decklists,
+            Err(e) if options.force_decklists => return Err(e.into()),
+            Err(e) => {
+                warn!("Error fetching decklists for event {}: {}", event_id, e);
+                return Ok((0, 0));
+            }
+        };
+
+        info!("Found {} decklists for event {}", decklists.len(), event_id);
+
+        ChampionAliasService::new(self.database.clone())
+            .normalize(resolver, decklists.iter_mut().map(|d| (d.event_id, d.player_id.as_str(), &mut d.champion)))
+            .await?;
+        DeckStatsService::new(self.database.clone()).enrich(&mut decklists).await?;
+
+        for decklist in &decklists {
+            let filter = doc! {
+                "event_id": decklist.event_id,
+                "player_id": &decklist.player_id
+            };
+            let update = doc! {
+                "$set": mongodb::bson::to_document(decklist)?
+            };
+            let options = UpdateOptions::builder().upsert(true).build();
+            decklists_collection
+                .update_one(filter, update, options)
+                .await?;
+        }
+
+        let mut removed = 0;
+        if options.force_decklists {
+            let filter = if decklists.is_empty() { None } else { prune_filter(event_id, &player_ids) };
+            match filter {
+                Some(filter) => removed = decklists_collection.delete_many(filter, None).await?.deleted_count,
+                None => warn!("No decklists fetched for event {}, keeping stored decklists", event_id),
+            }
+        }
+
+        Ok((decklists.len(), removed))
+    }
+
     /// Incremental crawl starting from the last known event ID
     pub async fn crawl_incremental(&self) -> Result<i32, Box<dyn std::error::Error>> {
         info!("Starting incremental event crawl");
@@ -193,7 +358,7 @@ impl EventCrawler {
 
         info!("Resuming from event ID {}", start_id);
 
-        self.crawl_historical_events(start_id).await
+        self.crawl_historical_events(start_id, None).await
     }
 
     /// Save crawler state to database
@@ -234,6 +399,51 @@ impl EventCrawler {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::EventFormat;
+    use actix_web::web;
+
+    fn event(interesting: bool, has_decklists: bool) -> Event {
+        let mut event = Event::new(1, "Test".to_string(), EventFormat::Standard, "complete".to_string());
+        event.ranked = interesting;
+        event.player_count = 100;
+        event.has_decklists = has_decklists;
+        event
+    }
+
+    #[test]
+    fn test_ingest_options_from_query() {
+        let options = web::Query::<IngestOptions>::from_query("").unwrap().into_inner();
+        assert!(!options.force_standings && !options.force_decklists);
 
-    // Integration tests would require a test database
+        let options = web::Query::<IngestOptions>::from_query("force_decklists=true").unwrap().into_inner();
+        assert!(!options.force_standings && options.force_decklists);
+    }
+
+    #[test]
+    fn test_ingest_options_select_details() {
+        let default = IngestOptions::default();
+        assert!(default.fetches_details(&event(true, false)));
+        assert!(!default.fetches_details(&event(false, true)));
+        assert!(!default.fetches_decklists(&event(true, false)));
+        assert!(default.fetches_decklists(&event(true, true)));
+
+        let standings = IngestOptions { force_standings: true, ..Default::default() };
+        assert!(standings.fetches_details(&event(false, false)));
+        assert!(!standings.fetches_decklists(&event(false, false)));
+
+        let decklists = IngestOptions { force_decklists: true, ..Default::default() };
+        assert!(decklists.fetches_details(&event(false, false)));
+        assert!(decklists.fetches_decklists(&event(false, false)));
+    }
+
+    #[test]
+    fn test_prune_filter() {
+        assert_eq!(
+            prune_filter(7, &["a", "b"]),
+            Some(doc! { "event_id": 7, "player_id": { "$nin": ["a", "b"] } })
+        );
+        // An empty fetch must never delete every stored row
+        assert_eq!(prune_filter::<&str>(7, &[]), None);
+        assert_eq!(prune_filter::<String>(7, &[]), None);
+    }
 }
@@ -0,0 +1,663 @@
+use crate::models::{Card, DateRange, Decklist, DecklistCard, Event, EventFormat, Standing};
+use super::columnar::ColumnarRecord;
+use actix_web::web::Bytes;
+use chrono::{DateTime, NaiveDate, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures::stream::{self, BoxStream, StreamExt};
+use log::{info, warn};
+use mongodb::bson::{doc, Document};
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression as ParquetCompression;
+use parquet::file::properties::WriterProperties;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use thiserror::Error;
+
+/// File name of the manifest written alongside each dump
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Records per Parquet row group batch
+const PARQUET_BATCH_SIZE: usize = 8192;
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("Database error: {0}")]
+    Database(#[from] mongodb::error::Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] bson::ser::Error),
+
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] arrow_schema::ArrowError),
+
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[error("{0} cannot be exported as {1}")]
+    Unsupported(&'static str, &'static str),
+}
+
+/// Collections available for bulk export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dataset {
+    Events,
+    Standings,
+    Decklists,
+    Cards,
+}
+
+impl Dataset {
+    pub const ALL: [Dataset; 4] = [Dataset::Events, Dataset::Standings, Dataset::Decklists, Dataset::Cards];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "events" => Some(Dataset::Events),
+            "standings" => Some(Dataset::Standings),
+            "decklists" => Some(Dataset::Decklists),
+            "cards" => Some(Dataset::Cards),
+            _ => None,
+        }
+    }
+
+    /// Dataset name, which is also its collection name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dataset::Events => "events",
+            Dataset::Standings => "standings",
+            Dataset::Decklists => "decklists",
+            Dataset::Cards => "cards",
+        }
+    }
+
+    /// Whether `format` can represent this dataset. Parquet covers the
+    /// tournament data only; the card catalogue is nested and small.
+    pub fn supports(&self, format: ExportFormat) -> bool {
+        !(format == ExportFormat::Parquet && *self == Dataset::Cards)
+    }
+
+    /// Stable export order, so consecutive dumps diff cleanly
+    fn sort(&self) -> Document {
+        match self {
+            Dataset::Events => doc! { "event_id": 1 },
+            Dataset::Standings | Dataset::Decklists => doc! { "event_id": 1, "rank": 1, "player_id": 1 },
+            Dataset::Cards => doc! { "slug": 1 },
+        }
+    }
+}
+
+/// Export file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Newline-delimited JSON, one record per line
+    Ndjson,
+    /// CSV with a header row; nested lists are flattened into single columns
+    Csv,
+    /// Snappy-compressed Parquet with a fixed schema per dataset
+    Parquet,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Ndjson, ExportFormat::Csv, ExportFormat::Parquet];
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "ndjson" | "jsonl" => Some(ExportFormat::Ndjson),
+            "csv" => Some(ExportFormat::Csv),
+            "parquet" => Some(ExportFormat::Parquet),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "application/x-ndjson",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+}
+
+/// Which records to export. Event filters restrict standings and decklists
+/// to the matching events; `card_filter` applies to cards only.
+#[derive(Debug, Clone, Default)]
+pub struct ExportScope {
+    pub format: Option<EventFormat>,
+    pub range: DateRange,
+    pub min_players: Option<i32>,
+    /// Champion slug, for standings and decklists
+    pub champion: Option<String>,
+    pub card_filter: Document,
+}
+
+impl ExportScope {
+    fn restricts_events(&self) -> bool {
+        self.format.is_some() || !self.range.is_unbounded() || self.min_players.is_some()
+    }
+
+    fn event_filter(&self) -> Result<Document, ExportError> {
+        let mut filter = doc! { "status": "complete" };
+        if let Some(format) = &self.format {
+            filter.insert("format", mongodb::bson::to_bson(format)?);
+        }
+        if let Some(start_date) = self.range.condition() {
+            filter.insert("start_date", start_date);
+        }
+        if let Some(min_players) = self.min_players {
+            filter.insert("player_count", doc! { "$gte": min_players });
+        }
+        Ok(filter)
+    }
+}
+
+/// A model that can be written as an NDJSON line or a CSV row
+pub trait ExportRecord: Serialize + DeserializeOwned + Unpin + Send + Sync + 'static {
+    const CSV_HEADER: &'static [&'static str];
+
+    fn csv_row(&self) -> Vec<String>;
+}
+
+fn opt<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(ToString::to_string).unwrap_or_default()
+}
+
+fn date(value: &Option<DateTime<Utc>>) -> String {
+    value.map(|d| d.to_rfc3339()).unwrap_or_default()
+}
+
+fn list(values: &Option<Vec<String>>) -> String {
+    values.as_ref().map(|v| v.join("|")).unwrap_or_default()
+}
+
+/// Cards as `slug:quantity` pairs separated by `|`
+fn cards(cards: &[DecklistCard]) -> String {
+    cards
+        .iter()
+        .map(|c| format!("{}:{}", c.slug, c.quantity))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+impl ExportRecord for Event {
+    const CSV_HEADER: &'static [&'static str] = &[
+        "event_id", "name", "format", "status", "ranked", "player_count", "start_date", "end_date",
+        "location", "organizer", "rounds", "has_decklists", "tier",
+    ];
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.event_id.to_string(),
+            self.name.clone(),
+            self.format.as_str().to_string(),
+            self.status.clone(),
+            self.ranked.to_string(),
+            self.player_count.to_string(),
+            date(&self.start_date),
+            date(&self.end_date),
+            opt(&self.location),
+            opt(&self.organizer),
+            opt(&self.rounds),
+            self.has_decklists.to_string(),
+            opt(&self.tier),
+        ]
+    }
+}
+
+impl ExportRecord for Standing {
+    const CSV_HEADER: &'static [&'static str] = &[
+        "event_id", "player_id", "player_name", "rank", "champion", "wins", "losses", "draws",
+        "match_win_rate", "has_decklist",
+    ];
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.event_id.to_string(),
+            self.player_id.clone(),
+            self.player_name.clone(),
+            self.rank.to_string(),
+            self.champion.clone(),
+            self.wins.to_string(),
+            self.losses.to_string(),
+            self.draws.to_string(),
+            opt(&self.match_win_rate),
+            self.has_decklist.to_string(),
+        ]
+    }
+}
+
+impl ExportRecord for Decklist {
+    const CSV_HEADER: &'static [&'static str] = &[
+        "event_id", "player_id", "player_name", "champion", "rank", "main_deck_count",
+        "sideboard_count", "main_deck", "sideboard", "material_deck", "champion_lineage",
+    ];
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.event_id.to_string(),
+            self.player_id.clone(),
+            self.player_name.clone(),
+            self.champion.clone(),
+            self.rank.to_string(),
+            self.main_deck_count.to_string(),
+            self.sideboard_count.to_string(),
+            cards(&self.main_deck),
+            cards(&self.sideboard),
+            cards(&self.material_deck),
+            self.champion_lineage.join("|"),
+        ]
+    }
+}
+
+impl ExportRecord for Card {
+    const CSV_HEADER: &'static [&'static str] = &[
+        "slug", "name", "card_type", "element", "classes", "subtypes", "cost", "reserve_cost",
+        "power", "life_modifier", "set_name", "card_number", "rarity", "banned_standard",
+        "banned_limited",
+    ];
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.slug.clone(),
+            self.name.clone(),
+            opt(&self.card_type),
+            opt(&self.element),
+            list(&self.classes),
+            list(&self.subtypes),
+            opt(&self.cost),
+            opt(&self.reserve_cost),
+            opt(&self.power),
+            opt(&self.life_modifier),
+            opt(&self.set_name),
+            opt(&self.card_number),
+            opt(&self.rarity),
+            self.banned_standard.to_string(),
+            self.banned_limited.to_string(),
+        ]
+    }
+}
+
+/// Encode one CSV row, including the line terminator
+fn csv_line<I, S>(fields: I) -> Result<Vec<u8>, ExportError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<[u8]>,
+{
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(fields)?;
+    writer.into_inner().map_err(|e| ExportError::Io(e.into_error()))
+}
+
+/// Encode one record as a line in `format`
+pub fn encode<T: ExportRecord>(record: &T, format: ExportFormat) -> Result<Bytes, ExportError> {
+    let line = match format {
+        ExportFormat::Ndjson => {
+            let mut line = serde_json::to_vec(record)?;
+            line.push(b'\n');
+            line
+        }
+        ExportFormat::Csv => csv_line(record.csv_row())?,
+        ExportFormat::Parquet => return Err(ExportError::Unsupported("A single record", "Parquet")),
+    };
+    Ok(Bytes::from(line))
+}
+
+/// One file of a dump
+#[derive(Debug, Clone, Serialize)]
+pub struct DumpFile {
+    pub dataset: &'static str,
+    pub format: &'static str,
+    /// File name, relative to the manifest
+    pub file: String,
+    pub rows: u64,
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+/// Manifest describing a dump directory
+#[derive(Debug, Clone, Serialize)]
+pub struct DumpManifest {
+    pub generated_at: DateTime<Utc>,
+    pub files: Vec<DumpFile>,
+}
+
+/// Size and SHA-256 of a file
+fn checksum(path: &Path) -> io::Result<(u64, String)> {
+    let mut hasher = Sha256::new();
+    let bytes = io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok((bytes, hex::encode(hasher.finalize())))
+}
+
+/// Delete all but the newest `keep` dump directories in `dir`.
+/// Only directories named `YYYY-MM-DD` are considered.
+pub fn prune_dumps(dir: &Path, keep: usize) -> io::Result<usize> {
+    let mut dumps = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type()?.is_dir() && NaiveDate::parse_from_str(&name, "%Y-%m-%d").is_ok() {
+            dumps.push(name);
+        }
+    }
+
+    dumps.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut removed = 0;
+    for name in dumps.iter().skip(keep) {
+        fs::remove_dir_all(dir.join(name))?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+/// Service streaming tournament data for bulk export and writing nightly dumps
+pub struct ExportService {
+    database: Database,
+}
+
+impl ExportService {
+    /// Create a new export service
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// IDs of completed events matching the scope's event filters
+    async fn matching_event_ids(&self, scope: &ExportScope) -> Result<Vec<i32>, ExportError> {
+        let options = FindOptions::builder().projection(doc! { "event_id": 1 }).build();
+        let mut cursor = self
+            .database
+            .collection::<Document>("events")
+            .find(scope.event_filter()?, options)
+            .await?;
+
+        let mut event_ids = Vec::new();
+        while let Some(result) = cursor.next().await {
+            if let Ok(event_id) = result?.get_i32("event_id") {
+                event_ids.push(event_id);
+            }
+        }
+        Ok(event_ids)
+    }
+
+    /// MongoDB filter selecting the records of `dataset` in `scope`
+    async fn filter(&self, dataset: Dataset, scope: &ExportScope) -> Result<Document, ExportError> {
+        match dataset {
+            Dataset::Events => scope.event_filter(),
+            Dataset::Standings | Dataset::Decklists => {
+                let mut filter = doc! {};
+                if let Some(champion) = &scope.champion {
+                    filter.insert("champion", champion);
+                }
+                if scope.restricts_events() {
+                    let event_ids = self.matching_event_ids(scope).await?;
+                    filter.insert("event_id", doc! { "$in": event_ids });
+                }
+                Ok(filter)
+            }
+            Dataset::Cards => Ok(scope.card_filter.clone()),
+        }
+    }
+
+    async fn records<T: ExportRecord>(
+        &self,
+        dataset: Dataset,
+        filter: Document,
+    ) -> Result<BoxStream<'static, Result<T, ExportError>>, ExportError> {
+        let options = FindOptions::builder()
+            .sort(dataset.sort())
+            .projection(doc! { "_id": 0 })
+            .build();
+
+        let cursor = self
+            .database
+            .collection::<T>(dataset.name())
+            .find(filter, options)
+            .await?;
+
+        Ok(cursor.map(|result| result.map_err(ExportError::from)).boxed())
+    }
+
+    fn encoded<T: ExportRecord>(
+        records: BoxStream<'static, Result<T, ExportError>>,
+        format: ExportFormat,
+    ) -> Result<BoxStream<'static, Result<Bytes, ExportError>>, ExportError> {
+        let header = match format {
+            ExportFormat::Csv => Some(Ok(Bytes::from(csv_line(T::CSV_HEADER)?))),
+            _ => None,
+        };
+
+        let rows = records.map(move |result| result.and_then(|record| encode(&record, format)));
+        Ok(stream::iter(header).chain(rows).boxed())
+    }
+
+    /// Write `records` as Parquet in batches, returning the number of rows written
+    async fn parquet<T, W>(
+        mut records: BoxStream<'static, Result<T, ExportError>>,
+        writer: &mut W,
+    ) -> Result<u64, ExportError>
+    where
+        T: ColumnarRecord,
+        W: Write + Send,
+    {
+        let properties = WriterProperties::builder()
+            .set_compression(ParquetCompression::SNAPPY)
+            .build();
+        let mut parquet = ArrowWriter::try_new(writer, T::schema(), Some(properties))?;
+
+        let mut batch = Vec::with_capacity(PARQUET_BATCH_SIZE);
+        while let Some(record) = records.next().await {
+            batch.push(record?);
+            if batch.len() == PARQUET_BATCH_SIZE {
+                parquet.write(&T::batch(&batch)?)?;
+                batch.clear();
+            }
+        }
+        if !batch.is_empty() {
+            parquet.write(&T::batch(&batch)?)?;
+        }
+
+        Ok(parquet.close()?.num_rows as u64)
+    }
+
+    /// Stream `dataset` in a line format, one chunk per line. CSV streams start with the header row.
+    async fn lines(
+        &self,
+        dataset: Dataset,
+        filter: Document,
+        format: ExportFormat,
+    ) -> Result<BoxStream<'static, Result<Bytes, ExportError>>, ExportError> {
+        match dataset {
+            Dataset::Events => Self::encoded(self.records::<Event>(dataset, filter).await?, format),
+            Dataset::Standings => Self::encoded(self.records::<Standing>(dataset, filter).await?, format),
+            Dataset::Decklists => Self::encoded(self.records::<Decklist>(dataset, filter).await?, format),
+            Dataset::Cards => Self::encoded(self.records::<Card>(dataset, filter).await?, format),
+        }
+    }
+
+    /// Write `dataset` in `format` to `writer`, returning the number of rows written.
+    /// Decklist rows in Parquet are card entries, not decklists.
+    pub async fn write<W: Write + Send>(
+        &self,
+        dataset: Dataset,
+        scope: &ExportScope,
+        format: ExportFormat,
+        writer: &mut W,
+    ) -> Result<u64, ExportError> {
+        if !dataset.supports(format) {
+            return Err(ExportError::Unsupported(dataset.name(), format.extension()));
+        }
+        let filter = self.filter(dataset, scope).await?;
+
+        if format == ExportFormat::Parquet {
+            return match dataset {
+                Dataset::Events => Self::parquet(self.records::<Event>(dataset, filter).await?, writer).await,
+                Dataset::Standings => Self::parquet(self.records::<Standing>(dataset, filter).await?, writer).await,
+                Dataset::Decklists => Self::parquet(self.records::<Decklist>(dataset, filter).await?, writer).await,
+                Dataset::Cards => Err(ExportError::Unsupported(dataset.name(), format.extension())),
+            };
+        }
+
+        let mut lines = 0u64;
+        let mut chunks = self.lines(dataset, filter, format).await?;
+        while let Some(chunk) = chunks.next().await {
+            writer.write_all(&chunk?)?;
+            lines += 1;
+        }
+
+        Ok(match format {
+            ExportFormat::Csv => lines.saturating_sub(1),
+            _ => lines,
+        })
+    }
+
+    /// Stream `dataset` in `format`. NDJSON and CSV are streamed one line per
+    /// chunk; Parquet needs its footer before it can be read, so it is built
+    /// in memory and sent as a single chunk.
+    pub async fn stream(
+        &self,
+        dataset: Dataset,
+        scope: &ExportScope,
+        format: ExportFormat,
+    ) -> Result<BoxStream<'static, Result<Bytes, ExportError>>, ExportError> {
+        if format == ExportFormat::Parquet {
+            let mut buffer = Vec::new();
+            self.write(dataset, scope, format, &mut buffer).await?;
+            return Ok(stream::once(async { Ok(Bytes::from(buffer)) }).boxed());
+        }
+
+        let filter = self.filter(dataset, scope).await?;
+        self.lines(dataset, filter, format).await
+    }
+
+    /// Write a file of `dataset` in `format` into `dir`. Line formats are
+    /// gzip-compressed; Parquet is compressed internally.
+    async fn write_file(&self, dir: &Path, dataset: Dataset, format: ExportFormat) -> Result<DumpFile, ExportError> {
+        let scope = ExportScope::default();
+
+        let (file, rows) = if format == ExportFormat::Parquet {
+            let file = format!("{}.{}", dataset.name(), format.extension());
+            let mut writer = BufWriter::new(File::create(dir.join(&file))?);
+            let rows = self.write(dataset, &scope, format, &mut writer).await?;
+            writer.flush()?;
+            (file, rows)
+        } else {
+            let file = format!("{}.{}.gz", dataset.name(), format.extension());
+            let mut encoder = GzEncoder::new(BufWriter::new(File::create(dir.join(&file))?), Compression::default());
+            let rows = self.write(dataset, &scope, format, &mut encoder).await?;
+            encoder.finish()?.flush()?;
+            (file, rows)
+        };
+
+        let (bytes, sha256) = checksum(&dir.join(&file))?;
+
+        Ok(DumpFile {
+            dataset: dataset.name(),
+            format: format.extension(),
+            file,
+            rows,
+            bytes,
+            sha256,
+        })
+    }
+
+    /// Write a full dump of every dataset in every supported format to `dir/YYYY-MM-DD`
+    /// with a manifest, replacing any dump from the same day, then prune all
+    /// but the newest `keep` dumps. The dump is assembled in a `.partial`
+    /// directory and renamed into place, so readers never see half a dump.
+    pub async fn write_dump(&self, dir: &Path, keep: usize) -> Result<DumpManifest, ExportError> {
+        let generated_at = Utc::now();
+        let name = generated_at.format("%Y-%m-%d").to_string();
+        let partial = dir.join(format!("{}.partial", name));
+        let target = dir.join(&name);
+
+        if partial.exists() {
+            fs::remove_dir_all(&partial)?;
+        }
+        fs::create_dir_all(&partial)?;
+
+        let mut files = Vec::new();
+        for dataset in Dataset::ALL {
+            for format in ExportFormat::ALL.into_iter().filter(|f| dataset.supports(*f)) {
+                let file = self.write_file(&partial, dataset, format).await?;
+                info!("Exported {} rows to {}", file.rows, file.file);
+                files.push(file);
+            }
+        }
+
+        let manifest = DumpManifest { generated_at, files };
+        fs::write(partial.join(MANIFEST_FILE), serde_json::to_vec_pretty(&manifest)?)?;
+
+        if target.exists() {
+            fs::remove_dir_all(&target)?;
+        }
+        fs::rename(&partial, &target)?;
+
+        match prune_dumps(dir, keep) {
+            Ok(removed) if removed > 0 => info!("Pruned {} old dumps", removed),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to prune old dumps: {}", e),
+        }
+
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_encoding() {
+        let mut standing = Standing::new(7, "p1".to_string(), "Doe, \"Jane\"".to_string(), 1, "lorraine".to_string());
+        standing.wins = 5;
+
+        let line = encode(&standing, ExportFormat::Csv).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&line).unwrap(),
+            "7,p1,\"Doe, \"\"Jane\"\"\",1,lorraine,5,0,0,,false\n"
+        );
+        assert_eq!(Standing::CSV_HEADER.len(), standing.csv_row().len());
+
+        let line = encode(&standing, ExportFormat::Ndjson).unwrap();
+        assert!(line.ends_with(b"\n"));
+        assert_eq!(line.iter().filter(|&&b| b == b'\n').count(), 1);
+    }
+
+    #[test]
+    fn test_prune_dumps_keeps_newest() {
+        let dir = std::env::temp_dir().join(format!("export-prune-{}", std::process::id()));
+        for name in ["2024-01-01", "2024-01-02", "2024-01-03", "2024-01-04.partial", "other"] {
+            fs::create_dir_all(dir.join(name)).unwrap();
+        }
+
+        assert_eq!(prune_dumps(&dir, 2).unwrap(), 1);
+        assert!(!dir.join("2024-01-01").exists());
+        assert!(dir.join("2024-01-03").exists());
+        assert!(dir.join("2024-01-04.partial").exists());
+        assert!(dir.join("other").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,15 +1,35 @@
-use crate::models::{EventFormat, CardPerformance};
+use crate::models::{rating_pool, CardSet, DateRange, Decklist, Event, EventFormat, CardPerformance, CardUsage, ChampionCardUsage, Printing};
+use crate::services::card_sync::{PRINTINGS_COLLECTION, SETS_COLLECTION};
+use crate::services::ratings::Glicko2;
 use mongodb::Database;
 use mongodb::bson::{doc, Document};
+use mongodb::options::FindOptions;
 use log::info;
+use async_graphql::SimpleObject;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use chrono::{Utc, Duration as ChronoDuration};
+use utoipa::ToSchema;
+use std::collections::{HashMap, HashSet};
+use chrono::{DateTime, Duration, Utc};
+
+/// How meta breakdown rows are grouped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema, async_graphql::Enum)]
+#[serde(rename_all = "lowercase")]
+pub enum MetaGrouping {
+    /// One row per final-level champion
+    #[default]
+    Champion,
+    /// One row per champion lineage family, e.g. every Lorraine
+    Lineage,
+}
 
 /// Meta breakdown statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct MetaBreakdown {
+    /// Champion slug, or lineage family slug when grouped by lineage
     pub champion: String,
+    /// Champion slugs counted in a lineage row
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub champions: Vec<String>,
     pub deck_count: i32,
     pub meta_percentage: f64,
     pub avg_placement: f64,
@@ -19,7 +39,7 @@ pub struct MetaBreakdown {
 }
 
 /// Champion performance metrics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct ChampionPerformance {
     pub champion: String,
     pub total_appearances: i32,
@@ -29,6 +49,247 @@ pub struct ChampionPerformance {
     pub top_8_rate: f64,
     pub top_16_rate: f64,
     pub conversion_rate: f64, // Top 8 / Total appearances
+    /// Average pre-event rating of the players piloting this champion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_player_rating: Option<f64>,
+    /// Win rate relative to what the pilots' ratings predicted, centred on 0.5
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skill_adjusted_win_rate: Option<f64>,
+}
+
+/// Number of top finishes included in a champion overview
+const OVERVIEW_TOP_FINISHES: usize = 10;
+
+/// Number of cards included in a champion overview
+const OVERVIEW_TOP_CARDS: usize = 40;
+
+/// Champion's share of the field for one month
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct MetaSharePoint {
+    /// Month bucket, e.g. "2025-10"
+    pub period: String,
+    pub appearances: i32,
+    pub total_players: i32,
+    pub meta_percentage: f64,
+}
+
+/// Top placement achieved with a champion
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct TopFinish {
+    pub event_id: i32,
+    pub event_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<DateTime<Utc>>,
+    pub player_count: i32,
+    pub player_id: String,
+    pub player_name: String,
+    pub rank: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decklist_url: Option<String>,
+}
+
+/// How often a card appears in a champion's decklists
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct CardInclusion {
+    pub slug: String,
+    pub name: String,
+    pub deck_count: i32,
+    /// Percentage of the champion's decklists including the card
+    pub inclusion_rate: f64,
+    pub avg_quantity: f64,
+}
+
+/// Everything the champion page needs in a single response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct ChampionOverview {
+    pub champion: String,
+    pub total_appearances: i32,
+    pub total_decklists: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub win_rate: Option<f64>,
+    pub top_8_count: i32,
+    pub conversion_rate: f64,
+    pub avg_placement: f64,
+    pub meta_share: Vec<MetaSharePoint>,
+    pub top_finishes: Vec<TopFinish>,
+    pub top_cards: Vec<CardInclusion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_top_list: Option<Decklist>,
+}
+
+/// How often a champion lineage is played
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct LineageUsage {
+    /// Champion card slugs, lowest level first
+    pub lineage: Vec<String>,
+    pub deck_count: i32,
+    /// Percentage of the champion's decklists playing this lineage
+    pub inclusion_rate: f64,
+}
+
+/// Material deck choices across a champion's decklists
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct MaterialChoices {
+    pub champion: String,
+    /// Decklists with a material deck
+    pub total_decklists: i32,
+    pub lineages: Vec<LineageUsage>,
+    /// Champion cards, by how many decklists include them
+    pub champion_cards: Vec<CardInclusion>,
+    /// Regalia, by how many decklists include them
+    pub regalia: Vec<CardInclusion>,
+}
+
+/// Days after its set's release during which a card counts as new
+pub const NEW_CARD_WINDOW_DAYS: i64 = 90;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct NewCardUsage {
+    pub slug: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set_name: Option<String>,
+    /// Release of the card's first set
+    pub release_date: DateTime<Utc>,
+    /// Decklists that played the card while it was new
+    pub deck_count: i32,
+    pub inclusion_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct NewCardAdoption {
+    pub window_days: i64,
+    pub total_decklists: i32,
+    /// Decklists playing at least one card that was new at their event
+    pub decklists_with_new_cards: i32,
+    pub adoption_rate: f64,
+    pub cards: Vec<NewCardUsage>,
+}
+
+/// First release date and set name of each card, by slug
+type CardReleases = HashMap<String, (DateTime<Utc>, String)>;
+
+/// Whether a card first released at `release` was new at an event on `event_date`
+fn is_new_card(release: DateTime<Utc>, event_date: DateTime<Utc>, window_days: i64) -> bool {
+    release <= event_date && event_date - release <= Duration::days(window_days)
+}
+
+/// How widely cards were played while new across `decklists`, given the
+/// start date of each event; decklists of undated events are left out
+fn new_card_adoption(
+    event_dates: &HashMap<i32, DateTime<Utc>>,
+    releases: &CardReleases,
+    decklists: &[Decklist],
+    window_days: i64,
+) -> NewCardAdoption {
+    let mut total_decklists = 0;
+    let mut decklists_with_new_cards = 0;
+    // slug -> (name, deck count)
+    let mut usage: HashMap<String, (String, i32)> = HashMap::new();
+
+    for decklist in decklists {
+        let Some(&event_date) = event_dates.get(&decklist.event_id) else { continue };
+        total_decklists += 1;
+
+        let mut new_cards = HashSet::new();
+        for card in decklist.main_deck.iter().chain(&decklist.sideboard).chain(&decklist.material_deck) {
+            let Some((release_date, _)) = releases.get(&card.slug) else { continue };
+            if is_new_card(*release_date, event_date, window_days) && new_cards.insert(card.slug.as_str()) {
+                usage.entry(card.slug.clone()).or_insert_with(|| (card.name.clone(), 0)).1 += 1;
+            }
+        }
+
+        if !new_cards.is_empty() {
+            decklists_with_new_cards += 1;
+        }
+    }
+
+    let rate = |count: i32| {
+        if total_decklists > 0 {
+            (count as f64 / total_decklists as f64) * 100.0
+        } else {
+            0.0
+        }
+    };
+
+    let mut cards: Vec<NewCardUsage> = usage
+        .into_iter()
+        .filter_map(|(slug, (name, deck_count))| {
+            let (release_date, set_name) = releases.get(&slug)?.clone();
+            Some(NewCardUsage {
+                slug,
+                name,
+                set_name: Some(set_name),
+                release_date,
+                deck_count,
+                inclusion_rate: rate(deck_count),
+            })
+        })
+        .collect();
+    cards.sort_by(|a, b| b.deck_count.cmp(&a.deck_count).then_with(|| a.slug.cmp(&b.slug)));
+
+    NewCardAdoption {
+        window_days,
+        total_decklists,
+        decklists_with_new_cards,
+        adoption_rate: rate(decklists_with_new_cards),
+        cards,
+    }
+}
+
+/// Summary of an event used while building an overview
+struct EventSummary {
+    name: String,
+    start_date: Option<DateTime<Utc>>,
+    player_count: i32,
+}
+
+impl From<Event> for EventSummary {
+    fn from(event: Event) -> Self {
+        Self {
+            name: event.name,
+            start_date: event.start_date,
+            player_count: event.player_count,
+        }
+    }
+}
+
+/// Monthly share of the field from per-event `(event_id, appearances, total_players)`
+/// counts. Events without a start date cannot be bucketed and are left out.
+fn meta_share(events: &HashMap<i32, EventSummary>, per_event: &[(i32, i32, i32)]) -> Vec<MetaSharePoint> {
+    let mut share_by_month: std::collections::BTreeMap<String, (i32, i32)> = std::collections::BTreeMap::new();
+
+    for (event_id, appearances, total_players) in per_event {
+        let Some(date) = events.get(event_id).and_then(|e| e.start_date) else {
+            continue;
+        };
+        let entry = share_by_month.entry(date.format("%Y-%m").to_string()).or_insert((0, 0));
+        entry.0 += appearances;
+        entry.1 += total_players;
+    }
+
+    share_by_month
+        .into_iter()
+        .map(|(period, (appearances, total_players))| MetaSharePoint {
+            period,
+            appearances,
+            total_players,
+            meta_percentage: if total_players > 0 {
+                (appearances as f64 / total_players as f64) * 100.0
+            } else {
+                0.0
+            },
+        })
+        .collect()
+}
+
+/// Whether a list from an event on `date` placing `rank` replaces the
+/// current latest top list: newer events win, then better placements
+fn is_later_top_list(date: Option<DateTime<Utc>>, rank: i32, current: Option<(Option<DateTime<Utc>>, i32)>) -> bool {
+    match current {
+        None => true,
+        Some((best_date, best_rank)) => date > best_date || (date == best_date && rank < best_rank),
+    }
 }
 
 /// Service for meta analysis and statistics
@@ -46,21 +307,21 @@ impl MetaAnalysisService {
     pub async fn calculate_meta_breakdown(
         &self,
         format: Option<EventFormat>,
-        days: Option<i32>,
+        range: DateRange,
+        grouping: MetaGrouping,
     ) -> Result<Vec<MetaBreakdown>, Box<dyn std::error::Error>> {
-        info!("Calculating meta breakdown for format: {:?}, days: {:?}", format, days);
+        info!("Calculating meta breakdown for format: {:?}, range: {:?}, grouping: {:?}", format, range, grouping);
 
         let decklists_collection = self.database.collection::<Document>("decklists");
         let events_collection = self.database.collection::<Document>("events");
 
-        // Build date filter if days specified
+        // Build date filter if a range is specified
         let mut event_filter = doc! { "status": "complete", "ranked": true };
         if let Some(fmt) = format {
             event_filter.insert("format", mongodb::bson::to_bson(&fmt)?);
         }
-        if let Some(d) = days {
-            let cutoff_date = Utc::now() - ChronoDuration::days(d as i64);
-            event_filter.insert("start_date", doc! { "$gte": mongodb::bson::DateTime::from_chrono(cutoff_date) });
+        if let Some(start_date) = range.condition() {
+            event_filter.insert("start_date", start_date);
         }
 
         // Get event IDs matching criteria
@@ -84,12 +345,28 @@ impl MetaAnalysisService {
 
         info!("Analyzing {} events", event_ids.len());
 
-        // Aggregate decklist data
-        let pipeline = vec![
-            doc! { "$match": { "event_id": { "$in": event_ids } } },
+        // Aggregate decklist data, per champion or per lineage family.
+        // Champions without a known family form a lineage of their own.
+        let mut pipeline = vec![doc! { "$match": { "event_id": { "$in": event_ids } } }];
+        let group_key = match grouping {
+            MetaGrouping::Champion => mongodb::bson::Bson::from("$champion"),
+            MetaGrouping::Lineage => {
+                pipeline.push(doc! {
+                    "$lookup": {
+                        "from": "champions",
+                        "localField": "champion",
+                        "foreignField": "slug",
+                        "as": "champion_card",
+                    }
+                });
+                doc! { "$ifNull": [{ "$arrayElemAt": ["$champion_card.family", 0] }, "$champion"] }.into()
+            }
+        };
+        pipeline.extend([
             doc! {
                 "$group": {
-                    "_id": "$champion",
+                    "_id": group_key,
+                    "champions": { "$addToSet": "$champion" },
                     "deck_count": { "$sum": 1 },
                     "avg_placement": { "$avg": "$rank" },
                     "top_8_count": {
@@ -100,7 +377,7 @@ impl MetaAnalysisService {
                 }
             },
             doc! { "$sort": { "deck_count": -1 } },
-        ];
+        ]);
 
         let mut cursor = decklists_collection.aggregate(pipeline, None).await?;
         let mut results = Vec::new();
@@ -113,16 +390,26 @@ impl MetaAnalysisService {
                 let avg_placement = doc.get_f64("avg_placement").unwrap_or(0.0);
                 let top_8_count = doc.get_i32("top_8_count").unwrap_or(0);
 
+                // Member champions are only listed for lineage rows
+                let mut champions: Vec<String> = match grouping {
+                    MetaGrouping::Champion => Vec::new(),
+                    MetaGrouping::Lineage => doc
+                        .get_array("champions")
+                        .map(|slugs| slugs.iter().filter_map(|s| s.as_str().map(str::to_string)).collect())
+                        .unwrap_or_default(),
+                };
+                champions.sort();
+
                 total_decks += deck_count;
 
-                results.push((champion, deck_count, avg_placement, top_8_count));
+                results.push((champion, champions, deck_count, avg_placement, top_8_count));
             }
         }
 
         // Calculate percentages
         let breakdown: Vec<MetaBreakdown> = results
             .into_iter()
-            .map(|(champion, deck_count, avg_placement, top_8_count)| {
+            .map(|(champion, champions, deck_count, avg_placement, top_8_count)| {
                 let meta_percentage = if total_decks > 0 {
                     (deck_count as f64 / total_decks as f64) * 100.0
                 } else {
@@ -137,6 +424,7 @@ impl MetaAnalysisService {
 
                 MetaBreakdown {
                     champion,
+                    champions,
                     deck_count,
                     meta_percentage,
                     avg_placement,
@@ -155,18 +443,21 @@ impl MetaAnalysisService {
     /// Calculate champion performance metrics
     pub async fn calculate_champion_performance(
         &self,
-        days: Option<i32>,
+        format: Option<EventFormat>,
+        range: DateRange,
     ) -> Result<Vec<ChampionPerformance>, Box<dyn std::error::Error>> {
-        info!("Calculating champion performance for days: {:?}", days);
+        info!("Calculating champion performance for format: {:?}, range: {:?}", format, range);
 
         let standings_collection = self.database.collection::<Document>("standings");
         let events_collection = self.database.collection::<Document>("events");
 
         // Build date filter
         let mut event_filter = doc! { "status": "complete", "ranked": true };
-        if let Some(d) = days {
-            let cutoff_date = Utc::now() - ChronoDuration::days(d as i64);
-            event_filter.insert("start_date", doc! { "$gte": mongodb::bson::DateTime::from_chrono(cutoff_date) });
+        if let Some(fmt) = &format {
+            event_filter.insert("format", mongodb::bson::to_bson(fmt)?);
+        }
+        if let Some(start_date) = range.condition() {
+            event_filter.insert("start_date", start_date);
         }
 
         // Get event IDs
@@ -213,6 +504,9 @@ impl MetaAnalysisService {
 
         let mut cursor = standings_collection.aggregate(pipeline, None).await?;
         let mut performances = Vec::new();
+        let adjustments = self
+            .calculate_skill_adjustments(&event_ids, &rating_pool(format.as_ref()))
+            .await?;
 
         while let Some(result) = cursor.next().await {
             if let Ok(doc) = result {
@@ -236,7 +530,6 @@ impl MetaAnalysisService {
                 };
 
                 performances.push(ChampionPerformance {
-                    champion,
                     total_appearances,
                     total_events: event_ids.len() as i32,
                     avg_placement,
@@ -244,6 +537,9 @@ impl MetaAnalysisService {
                     top_8_rate,
                     top_16_rate,
                     conversion_rate: top_8_rate,
+                    avg_player_rating: adjustments.get(&champion).map(|a| a.0),
+                    skill_adjusted_win_rate: adjustments.get(&champion).map(|a| a.1),
+                    champion,
                 });
             }
         }
@@ -253,12 +549,483 @@ impl MetaAnalysisService {
         Ok(performances)
     }
 
+    /// Compare each champion's results with what its pilots' pre-event ratings predicted.
+    /// Returns champion -> (average pilot rating, skill-adjusted win rate).
+    async fn calculate_skill_adjustments(
+        &self,
+        event_ids: &[i32],
+        pool: &str,
+    ) -> Result<HashMap<String, (f64, f64)>, Box<dyn std::error::Error>> {
+        let history_collection = self.database.collection::<Document>("rating_history");
+        let standings_collection = self.database.collection::<Document>("standings");
+
+        use futures::stream::StreamExt;
+
+        // Pre-event ratings per (event, player), and the average rating of each event's field
+        let mut pre_ratings: HashMap<(i32, String), Glicko2> = HashMap::new();
+        let mut field: HashMap<i32, (f64, f64, i32)> = HashMap::new();
+
+        let mut cursor = history_collection
+            .find(doc! { "pool": pool, "event_id": { "$in": event_ids } }, None)
+            .await?;
+
+        while let Some(result) = cursor.next().await {
+            if let Ok(doc) = result {
+                let (Ok(event_id), Ok(player_id), Ok(rating), Ok(deviation)) = (
+                    doc.get_i32("event_id"),
+                    doc.get_str("player_id"),
+                    doc.get_f64("rating_before"),
+                    doc.get_f64("deviation_before"),
+                ) else {
+                    continue;
+                };
+
+                let entry = field.entry(event_id).or_insert((0.0, 0.0, 0));
+                entry.0 += rating;
+                entry.1 += deviation;
+                entry.2 += 1;
+
+                pre_ratings.insert(
+                    (event_id, player_id.to_string()),
+                    Glicko2 { rating, deviation, volatility: 0.0 },
+                );
+            }
+        }
+
+        if pre_ratings.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        // champion -> (rating sum, pilots, actual score, expected score, games)
+        let mut totals: HashMap<String, (f64, i32, f64, f64, f64)> = HashMap::new();
+
+        let mut cursor = standings_collection
+            .find(doc! { "event_id": { "$in": event_ids } }, None)
+            .await?;
+
+        while let Some(result) = cursor.next().await {
+            if let Ok(doc) = result {
+                let (Ok(event_id), Ok(player_id), Ok(champion)) = (
+                    doc.get_i32("event_id"),
+                    doc.get_str("player_id"),
+                    doc.get_str("champion"),
+                ) else {
+                    continue;
+                };
+
+                let Some(rating) = pre_ratings.get(&(event_id, player_id.to_string())) else {
+                    continue;
+                };
+                let Some(&(rating_sum, deviation_sum, count)) = field.get(&event_id) else {
+                    continue;
+                };
+
+                let wins = doc.get_i32("wins").unwrap_or(0) as f64;
+                let losses = doc.get_i32("losses").unwrap_or(0) as f64;
+                let draws = doc.get_i32("draws").unwrap_or(0) as f64;
+                let games = wins + losses + draws;
+
+                let expected = rating.expected_score(rating_sum / count as f64, deviation_sum / count as f64);
+
+                let entry = totals.entry(champion.to_string()).or_insert((0.0, 0, 0.0, 0.0, 0.0));
+                entry.0 += rating.rating;
+                entry.1 += 1;
+                entry.2 += wins + 0.5 * draws;
+                entry.3 += expected * games;
+                entry.4 += games;
+            }
+        }
+
+        Ok(totals
+            .into_iter()
+            .filter(|(_, t)| t.1 > 0 && t.4 > 0.0)
+            .map(|(champion, (rating_sum, pilots, actual, expected, games))| {
+                let adjusted = (0.5 + (actual - expected) / games).clamp(0.0, 1.0);
+                (champion, (rating_sum / pilots as f64, adjusted))
+            })
+            .collect())
+    }
+
+    /// Build the aggregate champion page data for a champion slug
+    pub async fn calculate_champion_overview(
+        &self,
+        champion: &str,
+        format: Option<EventFormat>,
+        range: DateRange,
+    ) -> Result<ChampionOverview, Box<dyn std::error::Error>> {
+        info!("Calculating overview for champion '{}'", champion);
+
+        let events_collection = self.database.collection::<Event>("events");
+        let standings_collection = self.database.collection::<Document>("standings");
+        let decklists_collection = self.database.collection::<Decklist>("decklists");
+        let cards_collection = self.database.collection::<Document>("cards");
+
+        let mut event_filter = doc! { "status": "complete", "ranked": true };
+        if let Some(fmt) = &format {
+            event_filter.insert("format", mongodb::bson::to_bson(fmt)?);
+        }
+        if let Some(start_date) = range.condition() {
+            event_filter.insert("start_date", start_date);
+        }
+
+        use futures::stream::StreamExt;
+
+        let mut events = HashMap::new();
+        let mut cursor = events_collection.find(event_filter, None).await?;
+
+        while let Some(result) = cursor.next().await {
+            if let Ok(event) = result {
+                events.insert(event.event_id, EventSummary::from(event));
+            }
+        }
+
+        let event_ids: Vec<i32> = events.keys().copied().collect();
+
+        // Field size and champion appearances per event, bucketed by month
+        let pipeline = vec![
+            doc! { "$match": { "event_id": { "$in": &event_ids } } },
+            doc! {
+                "$group": {
+                    "_id": "$event_id",
+                    "total_players": { "$sum": 1 },
+                    "appearances": {
+                        "$sum": { "$cond": [{ "$eq": ["$champion", champion] }, 1, 0] }
+                    },
+                }
+            },
+        ];
+
+        let mut per_event = Vec::new();
+        let mut cursor = standings_collection.aggregate(pipeline, None).await?;
+
+        while let Some(result) = cursor.next().await {
+            if let Ok(doc) = result {
+                if let Ok(event_id) = doc.get_i32("_id") {
+                    per_event.push((
+                        event_id,
+                        doc.get_i32("appearances").unwrap_or(0),
+                        doc.get_i32("total_players").unwrap_or(0),
+                    ));
+                }
+            }
+        }
+
+        let meta_share = meta_share(&events, &per_event);
+
+        // Lifetime record and conversion, plus the best finishes
+        let mut total_appearances = 0;
+        let mut top_8_count = 0;
+        let (mut wins, mut losses, mut draws, mut rank_sum) = (0, 0, 0, 0i64);
+        let mut top_finishes = Vec::new();
+
+        let options = FindOptions::builder().sort(doc! { "rank": 1 }).build();
+        let mut cursor = standings_collection
+            .find(doc! { "event_id": { "$in": &event_ids }, "champion": champion }, options)
+            .await?;
+
+        while let Some(result) = cursor.next().await {
+            if let Ok(doc) = result {
+                let event_id = doc.get_i32("event_id").unwrap_or(0);
+                let rank = doc.get_i32("rank").unwrap_or(0);
+
+                total_appearances += 1;
+                rank_sum += rank as i64;
+                wins += doc.get_i32("wins").unwrap_or(0);
+                losses += doc.get_i32("losses").unwrap_or(0);
+                draws += doc.get_i32("draws").unwrap_or(0);
+                if rank <= 8 {
+                    top_8_count += 1;
+                }
+
+                if let Some(event) = events.get(&event_id) {
+                    let player_id = doc.get_str("player_id").unwrap_or_default().to_string();
+                    let decklist_url = doc
+                        .get_bool("has_decklist")
+                        .unwrap_or(false)
+                        .then(|| format!("/api/decklists/{}?event={}", player_id, event_id));
+
+                    top_finishes.push(TopFinish {
+                        event_id,
+                        event_name: event.name.clone(),
+                        start_date: event.start_date,
+                        player_count: event.player_count,
+                        player_name: doc.get_str("player_name").unwrap_or_default().to_string(),
+                        player_id,
+                        rank,
+                        decklist_url,
+                    });
+                }
+            }
+        }
+
+        // Best placements first, larger events breaking ties
+        top_finishes.sort_by(|a, b| a.rank.cmp(&b.rank).then_with(|| b.player_count.cmp(&a.player_count)));
+        top_finishes.truncate(OVERVIEW_TOP_FINISHES);
+
+        // Card inclusion rates and the most recent best-placing list
+        let mut card_stats: HashMap<String, (i32, i32)> = HashMap::new();
+        let mut total_decklists = 0;
+        let mut latest_top_list: Option<(Option<DateTime<Utc>>, Decklist)> = None;
+
+        let mut cursor = decklists_collection
+            .find(doc! { "event_id": { "$in": &event_ids }, "champion": champion }, None)
+            .await?;
+
+        while let Some(result) = cursor.next().await {
+            if let Ok(decklist) = result {
+                total_decklists += 1;
+
+                if let Some(frequencies) = &decklist.card_frequencies {
+                    for (slug, quantity) in frequencies {
+                        let entry = card_stats.entry(slug.clone()).or_insert((0, 0));
+                        entry.0 += 1;
+                        entry.1 += quantity;
+                    }
+                }
+
+                let date = events.get(&decklist.event_id).and_then(|e| e.start_date);
+                let current = latest_top_list.as_ref().map(|(best_date, best)| (*best_date, best.rank));
+                if is_later_top_list(date, decklist.rank, current) {
+                    latest_top_list = Some((date, decklist));
+                }
+            }
+        }
+
+        let mut top_cards: Vec<(String, i32, i32)> = card_stats
+            .into_iter()
+            .map(|(slug, (deck_count, quantity))| (slug, deck_count, quantity))
+            .collect();
+        top_cards.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_cards.truncate(OVERVIEW_TOP_CARDS);
+
+        let slugs: Vec<&str> = top_cards.iter().map(|c| c.0.as_str()).collect();
+        let mut names = HashMap::new();
+        let mut cursor = cards_collection.find(doc! { "slug": { "$in": &slugs } }, None).await?;
+
+        while let Some(result) = cursor.next().await {
+            if let Ok(doc) = result {
+                if let (Ok(slug), Ok(name)) = (doc.get_str("slug"), doc.get_str("name")) {
+                    names.insert(slug.to_string(), name.to_string());
+                }
+            }
+        }
+
+        let top_cards = top_cards
+            .into_iter()
+            .map(|(slug, deck_count, quantity)| CardInclusion {
+                name: names.get(&slug).cloned().unwrap_or_else(|| slug.clone()),
+                slug,
+                deck_count,
+                inclusion_rate: (deck_count as f64 / total_decklists as f64) * 100.0,
+                avg_quantity: quantity as f64 / deck_count as f64,
+            })
+            .collect();
+
+        let total_matches = wins + losses + draws;
+
+        Ok(ChampionOverview {
+            champion: champion.to_string(),
+            total_appearances,
+            total_decklists,
+            win_rate: (total_matches > 0).then(|| wins as f64 / total_matches as f64),
+            top_8_count,
+            conversion_rate: if total_appearances > 0 {
+                (top_8_count as f64 / total_appearances as f64) * 100.0
+            } else {
+                0.0
+            },
+            avg_placement: if total_appearances > 0 {
+                rank_sum as f64 / total_appearances as f64
+            } else {
+                0.0
+            },
+            meta_share,
+            top_finishes,
+            top_cards,
+            latest_top_list: latest_top_list.map(|(_, decklist)| decklist),
+        })
+    }
+
+    /// Calculate the lineages, champion cards and regalia played by a champion's decklists
+    pub async fn calculate_material_choices(
+        &self,
+        champion: &str,
+        format: Option<EventFormat>,
+        range: DateRange,
+    ) -> Result<MaterialChoices, Box<dyn std::error::Error>> {
+        info!("Calculating material choices for champion '{}'", champion);
+
+        let events_collection = self.database.collection::<Document>("events");
+        let decklists_collection = self.database.collection::<Decklist>("decklists");
+
+        let mut event_filter = doc! { "status": "complete", "ranked": true };
+        if let Some(fmt) = &format {
+            event_filter.insert("format", mongodb::bson::to_bson(fmt)?);
+        }
+        if let Some(start_date) = range.condition() {
+            event_filter.insert("start_date", start_date);
+        }
+
+        let event_ids: Vec<i32> = events_collection
+            .distinct("event_id", event_filter, None)
+            .await?
+            .into_iter()
+            .filter_map(|id| id.as_i32())
+            .collect();
+
+        let filter = doc! {
+            "event_id": { "$in": &event_ids },
+            "champion": champion,
+            "material_deck.0": { "$exists": true },
+        };
+        let mut cursor = decklists_collection.find(filter, None).await?;
+
+        use futures::stream::StreamExt;
+
+        let mut total_decklists = 0;
+        let mut lineages: HashMap<Vec<String>, i32> = HashMap::new();
+        // slug -> (name, deck count, total quantity)
+        let mut champion_cards: HashMap<String, (String, i32, i32)> = HashMap::new();
+        let mut regalia: HashMap<String, (String, i32, i32)> = HashMap::new();
+
+        while let Some(result) = cursor.next().await {
+            let Ok(decklist) = result else { continue };
+            total_decklists += 1;
+
+            if !decklist.champion_lineage.is_empty() {
+                *lineages.entry(decklist.champion_lineage.clone()).or_insert(0) += 1;
+            }
+
+            for card in &decklist.material_deck {
+                let stats = if card.is_champion() { &mut champion_cards } else { &mut regalia };
+                let entry = stats.entry(card.slug.clone()).or_insert_with(|| (card.name.clone(), 0, 0));
+                entry.1 += 1;
+                entry.2 += card.quantity;
+            }
+        }
+
+        let rate = |count: i32| (count as f64 / total_decklists as f64) * 100.0;
+
+        let inclusions = |stats: HashMap<String, (String, i32, i32)>| {
+            let mut cards: Vec<CardInclusion> = stats
+                .into_iter()
+                .map(|(slug, (name, deck_count, quantity))| CardInclusion {
+                    slug,
+                    name,
+                    deck_count,
+                    inclusion_rate: rate(deck_count),
+                    avg_quantity: quantity as f64 / deck_count as f64,
+                })
+                .collect();
+            cards.sort_by(|a, b| b.deck_count.cmp(&a.deck_count).then_with(|| a.slug.cmp(&b.slug)));
+            cards
+        };
+
+        let mut lineages: Vec<LineageUsage> = lineages
+            .into_iter()
+            .map(|(lineage, deck_count)| LineageUsage {
+                lineage,
+                deck_count,
+                inclusion_rate: rate(deck_count),
+            })
+            .collect();
+        lineages.sort_by(|a, b| b.deck_count.cmp(&a.deck_count).then_with(|| a.lineage.cmp(&b.lineage)));
+
+        Ok(MaterialChoices {
+            champion: champion.to_string(),
+            total_decklists,
+            lineages,
+            champion_cards: inclusions(champion_cards),
+            regalia: inclusions(regalia),
+        })
+    }
+
+    /// Earliest set release and that set's name for every card with a dated printing
+    async fn card_release_dates(&self) -> Result<CardReleases, Box<dyn std::error::Error>> {
+        use futures::stream::TryStreamExt;
+
+        let sets: Vec<CardSet> = self
+            .database
+            .collection::<CardSet>(SETS_COLLECTION)
+            .find(doc! { "release_date": { "$exists": true } }, None)
+            .await?
+            .try_collect()
+            .await?;
+        let sets: HashMap<String, CardSet> = sets.into_iter().map(|set| (set.prefix.clone(), set)).collect();
+
+        let mut releases: CardReleases = HashMap::new();
+        let mut cursor = self
+            .database
+            .collection::<Printing>(PRINTINGS_COLLECTION)
+            .find(doc! { "set_prefix": { "$exists": true } }, None)
+            .await?;
+
+        while let Some(printing) = cursor.try_next().await? {
+            let Some(set) = printing.set_prefix.as_ref().and_then(|prefix| sets.get(prefix)) else { continue };
+            let Some(release_date) = set.release_date else { continue };
+
+            let entry = releases
+                .entry(printing.card_slug)
+                .or_insert_with(|| (release_date, set.name.clone()));
+            if release_date < entry.0 {
+                *entry = (release_date, set.name.clone());
+            }
+        }
+
+        Ok(releases)
+    }
+
+    /// Calculate how widely cards were played while new, that is within
+    /// `window_days` of their first set's release when the event took place
+    pub async fn calculate_new_card_adoption(
+        &self,
+        format: Option<EventFormat>,
+        range: DateRange,
+        window_days: i64,
+    ) -> Result<NewCardAdoption, Box<dyn std::error::Error>> {
+        info!("Calculating new card adoption ({} day window)", window_days);
+
+        let events_collection = self.database.collection::<Event>("events");
+        let decklists_collection = self.database.collection::<Decklist>("decklists");
+
+        let mut event_filter = doc! { "status": "complete", "ranked": true };
+        if let Some(fmt) = &format {
+            event_filter.insert("format", mongodb::bson::to_bson(fmt)?);
+        }
+        if let Some(start_date) = range.condition() {
+            event_filter.insert("start_date", start_date);
+        }
+
+        use futures::stream::StreamExt;
+
+        let mut event_dates: HashMap<i32, DateTime<Utc>> = HashMap::new();
+        let mut cursor = events_collection.find(event_filter, None).await?;
+        while let Some(result) = cursor.next().await {
+            let Ok(event) = result else { continue };
+            if let Some(start_date) = event.start_date {
+                event_dates.insert(event.event_id, start_date);
+            }
+        }
+
+        let releases = self.card_release_dates().await?;
+
+        let event_ids: Vec<i32> = event_dates.keys().copied().collect();
+        let decklists: Vec<Decklist> = decklists_collection
+            .find(doc! { "event_id": { "$in": &event_ids } }, None)
+            .await?
+            .filter_map(|result| async move { result.ok() })
+            .collect()
+            .await;
+
+        Ok(new_card_adoption(&event_dates, &releases, &decklists, window_days))
+    }
+
     /// Calculate card performance metrics
     pub async fn calculate_card_performance(
         &self,
         format: Option<EventFormat>,
-        days: Option<i32>,
-        limit: Option<i64>,
+        range: DateRange,
+        limit: Option<usize>,
     ) -> Result<Vec<CardPerformance>, Box<dyn std::error::Error>> {
         info!("Calculating card performance");
 
@@ -271,9 +1038,8 @@ impl MetaAnalysisService {
         if let Some(fmt) = format {
             event_filter.insert("format", mongodb::bson::to_bson(&fmt)?);
         }
-        if let Some(d) = days {
-            let cutoff_date = Utc::now() - ChronoDuration::days(d as i64);
-            event_filter.insert("start_date", doc! { "$gte": mongodb::bson::DateTime::from_chrono(cutoff_date) });
+        if let Some(start_date) = range.condition() {
+            event_filter.insert("start_date", start_date);
         }
 
         // Get event IDs
@@ -359,22 +1125,177 @@ impl MetaAnalysisService {
         }
 
         // Sort by deck count
-        performances.sort_by(|a, b| b.deck_count.cmp(&a.deck_count));
+        performances.sort_by_key(|p| std::cmp::Reverse(p.deck_count));
 
         // Apply limit if specified
         if let Some(lim) = limit {
-            performances.truncate(lim as usize);
+            performances.truncate(lim);
         }
 
         info!("Card performance calculated: {} cards", performances.len());
 
         Ok(performances)
     }
+
+    /// Calculate all-time tournament usage for a single card
+    pub async fn calculate_card_usage(
+        &self,
+        slug: &str,
+    ) -> Result<CardUsage, Box<dyn std::error::Error>> {
+        info!("Calculating card usage for '{}'", slug);
+
+        let decklists_collection = self.database.collection::<Document>("decklists");
+
+        let frequency_field = format!("card_frequencies.{}", slug);
+        let total_decks = decklists_collection.count_documents(doc! {}, None).await?;
+
+        let pipeline = vec![
+            doc! { "$match": { &frequency_field: { "$exists": true } } },
+            doc! {
+                "$group": {
+                    "_id": "$champion",
+                    "deck_count": { "$sum": 1 },
+                    "total_quantity": { "$sum": format!("${}", frequency_field) },
+                    "total_placement": { "$sum": "$rank" },
+                }
+            },
+            doc! { "$sort": { "deck_count": -1 } },
+        ];
+
+        let mut cursor = decklists_collection.aggregate(pipeline, None).await?;
+
+        use futures::stream::StreamExt;
+
+        let mut usage = CardUsage::default();
+        let mut total_quantity = 0;
+        let mut total_placement = 0;
+
+        while let Some(result) = cursor.next().await {
+            if let Ok(doc) = result {
+                let deck_count = doc.get_i32("deck_count").unwrap_or(0);
+                total_quantity += doc.get_i32("total_quantity").unwrap_or(0);
+                total_placement += doc.get_i32("total_placement").unwrap_or(0);
+                usage.deck_count += deck_count;
+
+                usage.champions.push(ChampionCardUsage {
+                    champion: doc.get_str("_id").unwrap_or("Unknown").to_string(),
+                    deck_count,
+                });
+            }
+        }
+
+        if usage.deck_count > 0 {
+            usage.avg_quantity = total_quantity as f64 / usage.deck_count as f64;
+            usage.avg_placement = Some(total_placement as f64 / usage.deck_count as f64);
+        }
+
+        if total_decks > 0 {
+            usage.meta_percentage = (usage.deck_count as f64 / total_decks as f64) * 100.0;
+        }
+
+        Ok(usage)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    // Integration tests would require a test database
+    /// Read back the way the crawler stores events
+    fn stored(event: Event) -> Event {
+        mongodb::bson::from_document(event.to_document().unwrap()).unwrap()
+    }
+
+    fn summary(event: Event) -> EventSummary {
+        EventSummary::from(stored(event))
+    }
+
+    fn event_on(event_id: i32, date: &str) -> Event {
+        let mut event = Event::new(event_id, format!("Event {}", event_id), EventFormat::Standard, "complete".to_string());
+        event.start_date = Some(date.parse().unwrap());
+        event
+    }
+
+    #[test]
+    fn test_meta_share_by_month() {
+        let events = HashMap::from([
+            (1, summary(event_on(1, "2025-09-06T10:00:00Z"))),
+            (2, summary(event_on(2, "2025-09-20T10:00:00Z"))),
+            (3, summary(event_on(3, "2025-10-04T10:00:00Z"))),
+            (4, summary(Event::new(4, "Undated".to_string(), EventFormat::Standard, "complete".to_string()))),
+        ]);
+        assert!(events[&1].start_date.is_some());
+
+        let share = meta_share(&events, &[(3, 0, 16), (1, 4, 32), (2, 2, 32), (4, 5, 10)]);
+
+        let periods: Vec<_> = share.iter().map(|p| p.period.as_str()).collect();
+        assert_eq!(periods, ["2025-09", "2025-10"]);
+        assert_eq!((share[0].appearances, share[0].total_players), (6, 64));
+        assert!((share[0].meta_percentage - 9.375).abs() < 1e-9);
+        assert_eq!(share[1].meta_percentage, 0.0);
+    }
+
+    #[test]
+    fn test_latest_top_list() {
+        let older = summary(event_on(1, "2025-09-06T10:00:00Z")).start_date;
+        let newer = summary(event_on(2, "2025-10-04T10:00:00Z")).start_date;
+
+        assert!(is_later_top_list(older, 5, None));
+        assert!(is_later_top_list(newer, 8, Some((older, 1))));
+        assert!(!is_later_top_list(older, 1, Some((newer, 8))));
+        assert!(is_later_top_list(newer, 2, Some((newer, 8))));
+        assert!(!is_later_top_list(newer, 8, Some((newer, 2))));
+        // Undated events never beat dated ones
+        assert!(!is_later_top_list(None, 1, Some((older, 8))));
+    }
+
+    #[test]
+    fn test_is_new_card() {
+        let release = Utc::now() - Duration::days(100);
+        assert!(is_new_card(release, release, 30));
+        assert!(is_new_card(release, release + Duration::days(30), 30));
+        assert!(!is_new_card(release, release + Duration::days(31), 30));
+        // Played before release, e.g. a preview event
+        assert!(!is_new_card(release, release - Duration::days(1), 30));
+    }
+
+    #[test]
+    fn test_new_card_adoption() {
+        let event = stored(event_on(1, "2024-03-20T10:00:00Z"));
+        let event_dates = HashMap::from([(event.event_id, event.start_date.unwrap())]);
+        let releases = CardReleases::from([
+            ("fireball".to_string(), ("2024-03-01T00:00:00Z".parse().unwrap(), "Dawn of Ashes".to_string())),
+            ("spark".to_string(), ("2023-01-01T00:00:00Z".parse().unwrap(), "Alpha".to_string())),
+        ]);
+
+        let card = |slug: &str| crate::models::DecklistCard {
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            quantity: 1,
+            card_type: None,
+            element: None,
+            cost: None,
+            level: None,
+        };
+        let decklist = |player: &str, event_id, slugs: &[&str]| {
+            let mut decklist = Decklist::new(event_id, player.to_string(), player.to_string(), "lorraine".to_string(), 1);
+            decklist.main_deck = slugs.iter().map(|slug| card(slug)).collect();
+            decklist
+        };
+        let decklists = [
+            decklist("p1", 1, &["fireball", "spark"]),
+            decklist("p2", 1, &["spark"]),
+            // Event outside the filter
+            decklist("p3", 2, &["fireball"]),
+        ];
+
+        let adoption = new_card_adoption(&event_dates, &releases, &decklists, 90);
+
+        assert_eq!(adoption.total_decklists, 2);
+        assert_eq!(adoption.decklists_with_new_cards, 1);
+        assert_eq!(adoption.adoption_rate, 50.0);
+        assert_eq!(adoption.cards.len(), 1);
+        assert_eq!(adoption.cards[0].slug, "fireball");
+        assert_eq!(adoption.cards[0].set_name.as_deref(), Some("Dawn of Ashes"));
+    }
 }
@@ -1,7 +1,21 @@
+pub mod auth;
 pub mod event_crawler;
 pub mod card_sync;
+pub mod champion_aliases;
+pub mod columnar;
+pub mod deck_stats;
+pub mod export;
 pub mod meta_analysis;
+pub mod player_stats;
+pub mod ratings;
+pub mod saved_decklists;
 
+pub use auth::AuthService;
 pub use event_crawler::EventCrawler;
 pub use card_sync::CardSyncService;
+pub use champion_aliases::ChampionAliasService;
+pub use deck_stats::DeckStatsService;
+pub use export::ExportService;
 pub use meta_analysis::MetaAnalysisService;
+pub use player_stats::PlayerStatsService;
+pub use ratings::RatingService;
@@ -0,0 +1,279 @@
+use crate::models::{
+    Event, PlayerChampionUsage, PlayerEventResult, PlayerProfile, PlayerRecord, PlayerSummary,
+    Standing,
+};
+use mongodb::{Database, Collection};
+use mongodb::bson::{doc, Document, Regex};
+use log::info;
+use std::collections::{HashMap, HashSet};
+
+/// Number of best finishes included in a player profile
+const BEST_FINISHES: usize = 5;
+
+/// Service for aggregating player histories from standings
+pub struct PlayerStatsService {
+    database: Database,
+}
+
+impl PlayerStatsService {
+    /// Create a new player stats service
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// Build a player's profile from all of their standings.
+    /// Returns `None` when the player has no recorded standings.
+    pub async fn get_profile(
+        &self,
+        player_id: &str,
+    ) -> Result<Option<PlayerProfile>, Box<dyn std::error::Error>> {
+        info!("Building profile for player '{}'", player_id);
+
+        let standings_collection: Collection<Standing> = self.database.collection("standings");
+        let events_collection: Collection<Event> = self.database.collection("events");
+        let decklists_collection = self.database.collection::<Document>("decklists");
+
+        use futures::stream::StreamExt;
+
+        let mut standings = Vec::new();
+        let mut cursor = standings_collection
+            .find(doc! { "player_id": player_id }, None)
+            .await?;
+
+        while let Some(result) = cursor.next().await {
+            if let Ok(standing) = result {
+                standings.push(standing);
+            }
+        }
+
+        if standings.is_empty() {
+            return Ok(None);
+        }
+
+        let event_ids: Vec<i32> = standings.iter().map(|s| s.event_id).collect();
+
+        let mut events = HashMap::new();
+        let mut cursor = events_collection
+            .find(doc! { "event_id": { "$in": &event_ids } }, None)
+            .await?;
+
+        while let Some(result) = cursor.next().await {
+            if let Ok(event) = result {
+                events.insert(event.event_id, event);
+            }
+        }
+
+        let mut decklist_events = HashSet::new();
+        let options = mongodb::options::FindOptions::builder()
+            .projection(doc! { "event_id": 1 })
+            .build();
+        let mut cursor = decklists_collection
+            .find(doc! { "player_id": player_id }, options)
+            .await?;
+
+        while let Some(result) = cursor.next().await {
+            if let Ok(doc) = result {
+                if let Ok(event_id) = doc.get_i32("event_id") {
+                    decklist_events.insert(event_id);
+                }
+            }
+        }
+
+        Ok(Some(build_profile(player_id, standings, &events, &decklist_events)))
+    }
+
+    /// Search players by name, most active first.
+    /// Returns the requested page and the total number of matching players.
+    pub async fn search_players(
+        &self,
+        name: Option<Regex>,
+        skip: u64,
+        limit: u64,
+    ) -> Result<(Vec<PlayerSummary>, u64), Box<dyn std::error::Error>> {
+        let standings_collection = self.database.collection::<Document>("standings");
+
+        let mut pipeline = Vec::new();
+        if let Some(name) = name {
+            pipeline.push(doc! { "$match": { "player_name": name } });
+        }
+        pipeline.push(doc! { "$sort": { "updated_at": 1 } });
+        pipeline.push(doc! {
+            "$group": {
+                "_id": "$player_id",
+                "player_name": { "$last": "$player_name" },
+                "events_played": { "$sum": 1 },
+            }
+        });
+        pipeline.push(doc! {
+            "$facet": {
+                "players": [
+                    { "$sort": { "events_played": -1, "player_name": 1, "_id": 1 } },
+                    { "$skip": skip as i64 },
+                    { "$limit": limit as i64 },
+                ],
+                "total": [{ "$count": "count" }],
+            }
+        });
+
+        let mut cursor = standings_collection.aggregate(pipeline, None).await?;
+
+        use futures::stream::StreamExt;
+
+        let mut players = Vec::new();
+        let mut total = 0;
+
+        if let Some(Ok(facet)) = cursor.next().await {
+            if let Ok(docs) = facet.get_array("players") {
+                for doc in docs.iter().filter_map(|d| d.as_document()) {
+                    players.push(PlayerSummary {
+                        player_id: doc.get_str("_id").unwrap_or_default().to_string(),
+                        player_name: doc.get_str("player_name").unwrap_or_default().to_string(),
+                        events_played: doc.get_i32("events_played").unwrap_or(0),
+                    });
+                }
+            }
+
+            total = facet
+                .get_array("total")
+                .ok()
+                .and_then(|t| t.first())
+                .and_then(|t| t.as_document())
+                .and_then(|t| t.get_i32("count").ok())
+                .unwrap_or(0) as u64;
+        }
+
+        Ok((players, total))
+    }
+}
+
+/// Aggregate standings into a profile
+fn build_profile(
+    player_id: &str,
+    standings: Vec<Standing>,
+    events: &HashMap<i32, Event>,
+    decklist_events: &HashSet<i32>,
+) -> PlayerProfile {
+    let mut record = PlayerRecord::default();
+    let mut champion_usage: HashMap<String, PlayerChampionUsage> = HashMap::new();
+    let mut history = Vec::with_capacity(standings.len());
+
+    for standing in &standings {
+        record.wins += standing.wins;
+        record.losses += standing.losses;
+        record.draws += standing.draws;
+
+        let event = events.get(&standing.event_id);
+        let start_date = event.and_then(|e| e.start_date);
+
+        let usage = champion_usage
+            .entry(standing.champion.clone())
+            .or_insert_with(|| PlayerChampionUsage {
+                champion: standing.champion.clone(),
+                events: 0,
+                first_played: None,
+                last_played: None,
+            });
+        usage.events += 1;
+        if let Some(date) = start_date {
+            usage.first_played = Some(usage.first_played.map_or(date, |d| d.min(date)));
+            usage.last_played = Some(usage.last_played.map_or(date, |d| d.max(date)));
+        }
+
+        let decklist_url = decklist_events.contains(&standing.event_id).then(|| {
+            format!("/api/decklists/{}?event={}", player_id, standing.event_id)
+        });
+
+        history.push(PlayerEventResult {
+            event_id: standing.event_id,
+            event_name: event.map(|e| e.name.clone()),
+            format: event.map(|e| e.format),
+            start_date,
+            player_count: event.map(|e| e.player_count),
+            champion: standing.champion.clone(),
+            rank: standing.rank,
+            wins: standing.wins,
+            losses: standing.losses,
+            draws: standing.draws,
+            decklist_url,
+        });
+    }
+
+    let total_matches = record.wins + record.losses + record.draws;
+    if total_matches > 0 {
+        record.match_win_rate = Some(record.wins as f64 / total_matches as f64);
+    }
+
+    // Most recent first; events without dates fall back to event ID order
+    history.sort_by(|a, b| {
+        b.start_date
+            .cmp(&a.start_date)
+            .then_with(|| b.event_id.cmp(&a.event_id))
+    });
+
+    let player_name = standings
+        .iter()
+        .max_by_key(|s| s.updated_at)
+        .map(|s| s.player_name.clone())
+        .unwrap_or_default();
+
+    let mut best_finishes = history.clone();
+    best_finishes.sort_by(|a, b| {
+        a.rank
+            .cmp(&b.rank)
+            .then_with(|| b.player_count.cmp(&a.player_count))
+    });
+    best_finishes.truncate(BEST_FINISHES);
+
+    let mut champions: Vec<PlayerChampionUsage> = champion_usage.into_values().collect();
+    champions.sort_by(|a, b| b.events.cmp(&a.events).then_with(|| a.champion.cmp(&b.champion)));
+
+    PlayerProfile {
+        player_id: player_id.to_string(),
+        player_name,
+        events_played: history.len() as i32,
+        record,
+        champions,
+        best_finishes,
+        history,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::EventFormat;
+
+    fn standing(event_id: i32, rank: i32, champion: &str, wins: i32, losses: i32) -> Standing {
+        let mut standing = Standing::new(event_id, "p1".to_string(), "Player One".to_string(), rank, champion.to_string());
+        standing.wins = wins;
+        standing.losses = losses;
+        standing
+    }
+
+    #[test]
+    fn test_build_profile() {
+        let standings = vec![
+            standing(1, 12, "lorraine", 4, 2),
+            standing(2, 1, "silvie", 6, 0),
+            standing(3, 3, "lorraine", 5, 1),
+        ];
+
+        let mut events = HashMap::new();
+        let mut event = Event::new(2, "Regional".to_string(), EventFormat::Standard, "complete".to_string());
+        event.start_date = Some(chrono::Utc::now());
+        events.insert(2, event);
+
+        let decklists = HashSet::from([2]);
+        let profile = build_profile("p1", standings, &events, &decklists);
+
+        assert_eq!(profile.events_played, 3);
+        assert_eq!(profile.record.wins, 15);
+        assert_eq!(profile.record.losses, 3);
+        assert_eq!(profile.champions[0].champion, "lorraine");
+        assert_eq!(profile.champions[0].events, 2);
+        assert_eq!(profile.best_finishes[0].event_id, 2);
+        assert_eq!(profile.history[0].event_id, 2);
+        assert_eq!(profile.history[0].decklist_url.as_deref(), Some("/api/decklists/p1?event=2"));
+        assert!(profile.history[1].decklist_url.is_none());
+    }
+}
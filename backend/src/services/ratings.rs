@@ -0,0 +1,462 @@
+use crate::models::{
+    rating_pool, Event, EventFormat, PlayerRating, RatingHistoryEntry, Standing,
+    OVERALL_RATING_POOL,
+};
+use mongodb::{Database, Collection};
+use mongodb::bson::doc;
+use mongodb::options::FindOptions;
+use log::{info, debug};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// Starting rating for unrated players
+pub const DEFAULT_RATING: f64 = 1500.0;
+
+/// Starting (and maximum) rating deviation
+pub const DEFAULT_DEVIATION: f64 = 350.0;
+
+/// Starting volatility
+pub const DEFAULT_VOLATILITY: f64 = 0.06;
+
+/// System constant constraining volatility change over time
+const TAU: f64 = 0.5;
+
+/// Conversion factor between the Glicko and Glicko-2 scales
+const GLICKO2_SCALE: f64 = 173.7178;
+
+/// Convergence tolerance for the volatility iteration
+const CONVERGENCE_TOLERANCE: f64 = 0.000001;
+
+/// Length of a rating period used to grow deviation for inactive players
+const RATING_PERIOD_DAYS: i64 = 30;
+
+/// Number of documents written per batch when persisting ratings
+const WRITE_BATCH_SIZE: usize = 1000;
+
+/// Suffix of the collections new ratings are written to before being swapped in
+const STAGING_SUFFIX: &str = "_staging";
+
+/// A Glicko-2 rating expressed on the Glicko scale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glicko2 {
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+}
+
+/// A single rated game against an opponent
+#[derive(Debug, Clone, Copy)]
+pub struct Game {
+    pub opponent_rating: f64,
+    pub opponent_deviation: f64,
+
+    /// 1 for a win, 0.5 for a draw, 0 for a loss
+    pub score: f64,
+}
+
+impl Default for Glicko2 {
+    fn default() -> Self {
+        Self {
+            rating: DEFAULT_RATING,
+            deviation: DEFAULT_DEVIATION,
+            volatility: DEFAULT_VOLATILITY,
+        }
+    }
+}
+
+fn g(phi: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt()
+}
+
+fn expectation(mu: f64, mu_j: f64, phi_j: f64) -> f64 {
+    1.0 / (1.0 + (-g(phi_j) * (mu - mu_j)).exp())
+}
+
+impl Glicko2 {
+    /// Grow the deviation for `periods` rating periods without games
+    pub fn inflate(&self, periods: i64) -> Self {
+        if periods <= 0 {
+            return *self;
+        }
+
+        let phi = self.deviation / GLICKO2_SCALE;
+        let phi = (phi * phi + periods as f64 * self.volatility * self.volatility).sqrt();
+
+        Self {
+            deviation: (phi * GLICKO2_SCALE).min(DEFAULT_DEVIATION),
+            ..*self
+        }
+    }
+
+    /// Expected score against an opponent
+    pub fn expected_score(&self, opponent_rating: f64, opponent_deviation: f64) -> f64 {
+        expectation(
+            (self.rating - DEFAULT_RATING) / GLICKO2_SCALE,
+            (opponent_rating - DEFAULT_RATING) / GLICKO2_SCALE,
+            opponent_deviation / GLICKO2_SCALE,
+        )
+    }
+
+    /// Apply one rating period of games (Glickman, "Example of the Glicko-2 system")
+    pub fn update(&self, games: &[Game]) -> Self {
+        if games.is_empty() {
+            return self.inflate(1);
+        }
+
+        let mu = (self.rating - DEFAULT_RATING) / GLICKO2_SCALE;
+        let phi = self.deviation / GLICKO2_SCALE;
+        let sigma = self.volatility;
+
+        let mut v_inv = 0.0;
+        let mut delta_sum = 0.0;
+        for game in games {
+            let mu_j = (game.opponent_rating - DEFAULT_RATING) / GLICKO2_SCALE;
+            let phi_j = game.opponent_deviation / GLICKO2_SCALE;
+            let g_j = g(phi_j);
+            let e_j = expectation(mu, mu_j, phi_j);
+
+            v_inv += g_j * g_j * e_j * (1.0 - e_j);
+            delta_sum += g_j * (game.score - e_j);
+        }
+        let v = 1.0 / v_inv;
+        let delta = v * delta_sum;
+
+        // Solve for the new volatility with the Illinois algorithm
+        let a = (sigma * sigma).ln();
+        let f = |x: f64| {
+            let ex = x.exp();
+            let d = phi * phi + v + ex;
+            ex * (delta * delta - phi * phi - v - ex) / (2.0 * d * d) - (x - a) / (TAU * TAU)
+        };
+
+        let mut big_a = a;
+        let mut big_b = if delta * delta > phi * phi + v {
+            (delta * delta - phi * phi - v).ln()
+        } else {
+            let mut k = 1.0;
+            while f(a - k * TAU) < 0.0 {
+                k += 1.0;
+            }
+            a - k * TAU
+        };
+
+        let mut f_a = f(big_a);
+        let mut f_b = f(big_b);
+        while (big_b - big_a).abs() > CONVERGENCE_TOLERANCE {
+            let big_c = big_a + (big_a - big_b) * f_a / (f_b - f_a);
+            let f_c = f(big_c);
+            if f_c * f_b <= 0.0 {
+                big_a = big_b;
+                f_a = f_b;
+            } else {
+                f_a /= 2.0;
+            }
+            big_b = big_c;
+            f_b = f_c;
+        }
+
+        let new_sigma = (big_a / 2.0).exp();
+        let phi_star = (phi * phi + new_sigma * new_sigma).sqrt();
+        let new_phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / v).sqrt();
+        let new_mu = mu + new_phi * new_phi * delta_sum;
+
+        Self {
+            rating: new_mu * GLICKO2_SCALE + DEFAULT_RATING,
+            deviation: (new_phi * GLICKO2_SCALE).min(DEFAULT_DEVIATION),
+            volatility: new_sigma,
+        }
+    }
+}
+
+/// In-memory rating state for a player while replaying events
+#[derive(Debug, Clone)]
+struct RatingState {
+    player_name: String,
+    rating: Glicko2,
+    events_played: i32,
+    games_played: i32,
+    last_event_id: i32,
+    last_played: Option<DateTime<Utc>>,
+}
+
+/// Service for computing Glicko-2 player ratings from tournament results
+pub struct RatingService {
+    database: Database,
+}
+
+impl RatingService {
+    /// Create a new rating service
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// Replay every completed, ranked event in chronological order and rebuild
+    /// the `player_ratings` and `rating_history` collections.
+    /// Returns the number of events rated.
+    pub async fn recompute_all(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        info!("Recomputing player ratings");
+
+        let events_collection: Collection<Event> = self.database.collection("events");
+        let standings_collection: Collection<Standing> = self.database.collection("standings");
+
+        let options = FindOptions::builder()
+            .sort(doc! { "start_date": 1, "event_id": 1 })
+            .build();
+        let mut cursor = events_collection
+            .find(doc! { "status": "complete", "ranked": true }, options)
+            .await?;
+
+        use futures::stream::StreamExt;
+
+        let mut events = Vec::new();
+        while let Some(result) = cursor.next().await {
+            if let Ok(event) = result {
+                events.push(event);
+            }
+        }
+
+        let mut states: HashMap<(String, String), RatingState> = HashMap::new();
+        let mut history = Vec::new();
+        let mut events_rated = 0;
+
+        for event in &events {
+            let mut standings = Vec::new();
+            let mut cursor = standings_collection
+                .find(doc! { "event_id": event.event_id }, None)
+                .await?;
+            while let Some(result) = cursor.next().await {
+                if let Ok(standing) = result {
+                    standings.push(standing);
+                }
+            }
+
+            if standings.is_empty() {
+                continue;
+            }
+
+            let mut pools = vec![OVERALL_RATING_POOL.to_string()];
+            if event.format != EventFormat::Unknown {
+                pools.push(rating_pool(Some(&event.format)));
+            }
+
+            for pool in &pools {
+                history.extend(apply_event(pool, event, &standings, &mut states));
+            }
+
+            events_rated += 1;
+            debug!("Rated event {} ({} standings)", event.event_id, standings.len());
+        }
+
+        let now = Utc::now();
+        let ratings: Vec<PlayerRating> = states
+            .into_iter()
+            .map(|((pool, player_id), state)| PlayerRating {
+                id: None,
+                player_id,
+                player_name: state.player_name,
+                pool,
+                rating: state.rating.rating,
+                rating_deviation: state.rating.deviation,
+                volatility: state.rating.volatility,
+                events_played: state.events_played,
+                games_played: state.games_played,
+                last_event_id: state.last_event_id,
+                last_played: state.last_played,
+                updated_at: now,
+            })
+            .collect();
+
+        self.save(ratings, history).await?;
+
+        info!("Player ratings recomputed from {} events", events_rated);
+
+        Ok(events_rated)
+    }
+
+    /// Replace stored ratings and history with freshly computed values
+    async fn save(
+        &self,
+        ratings: Vec<PlayerRating>,
+        history: Vec<RatingHistoryEntry>,
+    ) -> Result<(), mongodb::error::Error> {
+        self.replace_collection("player_ratings", &ratings).await?;
+        self.replace_collection("rating_history", &history).await?;
+
+        info!("Saved {} player ratings and {} history entries", ratings.len(), history.len());
+
+        Ok(())
+    }
+
+    /// Write `documents` to a staging collection, then `$out` it over the
+    /// live one. `$out` swaps the contents in atomically and keeps the live
+    /// collection's indexes; if anything fails the old contents stay in place.
+    async fn replace_collection<T: Serialize>(&self, name: &str, documents: &[T]) -> Result<(), mongodb::error::Error> {
+        let staging_name = format!("{}{}", name, STAGING_SUFFIX);
+        let staging: Collection<T> = self.database.collection(&staging_name);
+
+        // Left over from a recompute that failed before the swap
+        staging.drop(None).await?;
+        self.database.create_collection(&staging_name, None).await?;
+
+        for batch in documents.chunks(WRITE_BATCH_SIZE) {
+            staging.insert_many(batch, None).await?;
+        }
+
+        staging.aggregate(vec![doc! { "$out": name }], None).await?;
+        staging.drop(None).await?;
+
+        Ok(())
+    }
+}
+
+/// Rate one event within a pool, updating `states` and returning history entries
+fn apply_event(
+    pool: &str,
+    event: &Event,
+    standings: &[Standing],
+    states: &mut HashMap<(String, String), RatingState>,
+) -> Vec<RatingHistoryEntry> {
+    // Ratings entering the event, with deviation grown for inactivity
+    let pre: HashMap<&str, Glicko2> = standings
+        .iter()
+        .map(|s| {
+            let rating = states
+                .get(&(pool.to_string(), s.player_id.clone()))
+                .map(|state| {
+                    let periods = match (state.last_played, event.start_date) {
+                        (Some(last), Some(current)) => (current - last).num_days() / RATING_PERIOD_DAYS,
+                        _ => 0,
+                    };
+                    state.rating.inflate(periods)
+                })
+                .unwrap_or_default();
+            (s.player_id.as_str(), rating)
+        })
+        .collect();
+
+    // Approximate each player's games as played against the average of the rest of the field
+    let count = pre.len() as f64;
+    let rating_sum: f64 = pre.values().map(|r| r.rating).sum();
+    let deviation_sum: f64 = pre.values().map(|r| r.deviation).sum();
+    let mut games: HashMap<&str, Vec<Game>> = HashMap::new();
+
+    for standing in standings {
+        let own = pre[standing.player_id.as_str()];
+        let (field_rating, field_deviation) = if count > 1.0 {
+            ((rating_sum - own.rating) / (count - 1.0), (deviation_sum - own.deviation) / (count - 1.0))
+        } else {
+            (DEFAULT_RATING, DEFAULT_DEVIATION)
+        };
+
+        let results = std::iter::repeat_n(1.0, standing.wins.max(0) as usize)
+            .chain(std::iter::repeat_n(0.0, standing.losses.max(0) as usize))
+            .chain(std::iter::repeat_n(0.5, standing.draws.max(0) as usize));
+
+        games.entry(standing.player_id.as_str()).or_default().extend(results.map(|score| Game {
+            opponent_rating: field_rating,
+            opponent_deviation: field_deviation,
+            score,
+        }));
+    }
+
+    let mut history = Vec::new();
+
+    for standing in standings {
+        let Some(player_games) = games.get(standing.player_id.as_str()).filter(|g| !g.is_empty()) else {
+            continue;
+        };
+
+        let before = pre[standing.player_id.as_str()];
+        let after = before.update(player_games);
+        let score: f64 = player_games.iter().map(|g| g.score).sum();
+
+        let state = states
+            .entry((pool.to_string(), standing.player_id.clone()))
+            .or_insert_with(|| RatingState {
+                player_name: standing.player_name.clone(),
+                rating: Glicko2::default(),
+                events_played: 0,
+                games_played: 0,
+                last_event_id: event.event_id,
+                last_played: None,
+            });
+
+        state.player_name = standing.player_name.clone();
+        state.rating = after;
+        state.events_played += 1;
+        state.games_played += player_games.len() as i32;
+        state.last_event_id = event.event_id;
+        state.last_played = event.start_date.or(state.last_played);
+
+        history.push(RatingHistoryEntry {
+            id: None,
+            player_id: standing.player_id.clone(),
+            pool: pool.to_string(),
+            event_id: event.event_id,
+            event_date: event.start_date,
+            rating_before: before.rating,
+            deviation_before: before.deviation,
+            rating_after: after.rating,
+            deviation_after: after.deviation,
+            volatility_after: after.volatility,
+            games: player_games.len() as i32,
+            score,
+        });
+    }
+
+    history
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glickman_example() {
+        let player = Glicko2 {
+            rating: 1500.0,
+            deviation: 200.0,
+            volatility: 0.06,
+        };
+
+        let games = [
+            Game { opponent_rating: 1400.0, opponent_deviation: 30.0, score: 1.0 },
+            Game { opponent_rating: 1550.0, opponent_deviation: 100.0, score: 0.0 },
+            Game { opponent_rating: 1700.0, opponent_deviation: 300.0, score: 0.0 },
+        ];
+
+        let updated = player.update(&games);
+        assert!((updated.rating - 1464.06).abs() < 0.01);
+        assert!((updated.deviation - 151.52).abs() < 0.01);
+        assert!((updated.volatility - 0.05999).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_inflate_is_capped() {
+        let rating = Glicko2 { rating: 1600.0, deviation: 340.0, volatility: 0.06 };
+        assert_eq!(rating.inflate(0), rating);
+        assert_eq!(rating.inflate(1000).deviation, DEFAULT_DEVIATION);
+    }
+
+    #[test]
+    fn test_apply_event_from_standings() {
+        let event = Event::new(1, "Test".to_string(), EventFormat::Standard, "complete".to_string());
+
+        let mut winner = Standing::new(1, "a".to_string(), "A".to_string(), 1, "lorraine".to_string());
+        winner.wins = 3;
+        let mut loser = Standing::new(1, "b".to_string(), "B".to_string(), 2, "silvie".to_string());
+        loser.losses = 3;
+
+        let mut states = HashMap::new();
+        let history = apply_event(OVERALL_RATING_POOL, &event, &[winner, loser], &mut states);
+
+        assert_eq!(history.len(), 2);
+        let a = &states[&(OVERALL_RATING_POOL.to_string(), "a".to_string())];
+        let b = &states[&(OVERALL_RATING_POOL.to_string(), "b".to_string())];
+        assert!(a.rating.rating > DEFAULT_RATING);
+        assert!(b.rating.rating < DEFAULT_RATING);
+        assert_eq!(a.games_played, 3);
+    }
+}
@@ -0,0 +1,345 @@
+use crate::models::{
+    deck_hash, Decklist, DecklistCard, DecklistSource, SavedDecklist, SavedDecklistInput,
+};
+use mongodb::{Database, Collection};
+use mongodb::bson::{doc, Document};
+use mongodb::bson::oid::ObjectId;
+use mongodb::options::FindOptions;
+use log::info;
+use chrono::Utc;
+use thiserror::Error;
+
+/// Maximum number of tags on a saved decklist
+const MAX_TAGS: usize = 20;
+
+#[derive(Error, Debug)]
+pub enum SavedDecklistError {
+    #[error("Saved decklist not found")]
+    NotFound,
+
+    #[error("Tournament decklist not found")]
+    SourceNotFound,
+
+    #[error("An identical decklist is already saved")]
+    Duplicate(ObjectId),
+
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] mongodb::error::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] bson::ser::Error),
+}
+
+/// Service managing user-owned decklists
+pub struct SavedDecklistService {
+    database: Database,
+}
+
+impl SavedDecklistService {
+    /// Create a new saved decklist service
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    fn collection(&self) -> Collection<SavedDecklist> {
+        self.database.collection("saved_decklists")
+    }
+
+    /// List a user's saved decklists matching `filter`, returning the page and total count
+    pub async fn list(
+        &self,
+        user_id: ObjectId,
+        mut filter: Document,
+        options: FindOptions,
+    ) -> Result<(Vec<SavedDecklist>, u64), SavedDecklistError> {
+        filter.insert("user_id", user_id);
+
+        let total = self.collection().count_documents(filter.clone(), None).await?;
+
+        use futures::stream::StreamExt;
+
+        let mut decklists = Vec::new();
+        let mut cursor = self.collection().find(filter, options).await?;
+
+        while let Some(result) = cursor.next().await {
+            if let Ok(decklist) = result {
+                decklists.push(decklist);
+            }
+        }
+
+        Ok((decklists, total))
+    }
+
+    /// Get one of a user's saved decklists
+    pub async fn get(&self, user_id: ObjectId, id: ObjectId) -> Result<SavedDecklist, SavedDecklistError> {
+        self.collection()
+            .find_one(doc! { "_id": id, "user_id": user_id }, None)
+            .await?
+            .ok_or(SavedDecklistError::NotFound)
+    }
+
+    /// Save a new decklist for a user
+    pub async fn create(
+        &self,
+        user_id: ObjectId,
+        input: SavedDecklistInput,
+    ) -> Result<SavedDecklist, SavedDecklistError> {
+        self.insert(user_id, input, None).await
+    }
+
+    /// Replace the contents of one of a user's saved decklists
+    pub async fn update(
+        &self,
+        user_id: ObjectId,
+        id: ObjectId,
+        input: SavedDecklistInput,
+    ) -> Result<SavedDecklist, SavedDecklistError> {
+        let mut decklist = self.get(user_id, id).await?;
+        let input = normalize(input)?;
+        let hash = deck_hash(&input.champion_slug, &input.main_deck, &input.sideboard, &input.material_deck);
+
+        if hash != decklist.deck_hash {
+            self.ensure_unique(user_id, &hash, Some(id)).await?;
+        }
+
+        decklist.name = input.name;
+        decklist.description = input.description;
+        decklist.champion_slug = input.champion_slug;
+        decklist.main_deck = input.main_deck;
+        decklist.sideboard = input.sideboard;
+        decklist.material_deck = input.material_deck;
+        decklist.tags = input.tags;
+        decklist.deck_hash = hash;
+        decklist.updated_at = Utc::now();
+
+        self.collection()
+            .replace_one(doc! { "_id": id, "user_id": user_id }, &decklist, None)
+            .await?;
+
+        Ok(decklist)
+    }
+
+    /// Delete one of a user's saved decklists
+    pub async fn delete(&self, user_id: ObjectId, id: ObjectId) -> Result<(), SavedDecklistError> {
+        let result = self
+            .collection()
+            .delete_one(doc! { "_id": id, "user_id": user_id }, None)
+            .await?;
+
+        if result.deleted_count == 0 {
+            return Err(SavedDecklistError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Copy a tournament decklist into a user's saved decklists
+    pub async fn fork(
+        &self,
+        user_id: ObjectId,
+        event_id: i32,
+        player_id: &str,
+        name: Option<String>,
+    ) -> Result<SavedDecklist, SavedDecklistError> {
+        let decklists: Collection<Decklist> = self.database.collection("decklists");
+
+        let source = decklists
+            .find_one(doc! { "event_id": event_id, "player_id": player_id }, None)
+            .await?
+            .ok_or(SavedDecklistError::SourceNotFound)?;
+
+        let input = fork_input(source, name);
+
+        let origin = DecklistSource {
+            event_id,
+            player_id: player_id.to_string(),
+        };
+
+        self.insert(user_id, input, Some(origin)).await
+    }
+
+    async fn insert(
+        &self,
+        user_id: ObjectId,
+        input: SavedDecklistInput,
+        forked_from: Option<DecklistSource>,
+    ) -> Result<SavedDecklist, SavedDecklistError> {
+        let input = normalize(input)?;
+        let hash = deck_hash(&input.champion_slug, &input.main_deck, &input.sideboard, &input.material_deck);
+
+        self.ensure_unique(user_id, &hash, None).await?;
+
+        let now = Utc::now();
+        let mut decklist = SavedDecklist {
+            id: None,
+            user_id,
+            name: input.name,
+            description: input.description,
+            champion_slug: input.champion_slug,
+            main_deck: input.main_deck,
+            sideboard: input.sideboard,
+            material_deck: input.material_deck,
+            tags: input.tags,
+            deck_hash: hash,
+            forked_from,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let result = self.collection().insert_one(&decklist, None).await?;
+        decklist.id = result.inserted_id.as_object_id();
+
+        info!("Saved decklist '{}' for user {}", decklist.name, user_id);
+
+        Ok(decklist)
+    }
+
+    /// Fail with `Duplicate` when the user already has a deck with `hash`
+    async fn ensure_unique(
+        &self,
+        user_id: ObjectId,
+        hash: &str,
+        exclude: Option<ObjectId>,
+    ) -> Result<(), SavedDecklistError> {
+        let mut filter = doc! { "user_id": user_id, "deck_hash": hash };
+        if let Some(id) = exclude {
+            filter.insert("_id", doc! { "$ne": id });
+        }
+
+        match self.collection().find_one(filter, None).await? {
+            Some(existing) => Err(SavedDecklistError::Duplicate(existing.id.unwrap_or_default())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Saved decklist contents copied from a tournament decklist
+fn fork_input(source: Decklist, name: Option<String>) -> SavedDecklistInput {
+    SavedDecklistInput {
+        name: name.unwrap_or_else(|| {
+            format!("{} - {} (event {})", source.player_name, source.champion, source.event_id)
+        }),
+        description: None,
+        champion_slug: source.champion,
+        main_deck: source.main_deck,
+        sideboard: source.sideboard,
+        material_deck: source.material_deck,
+        tags: Vec::new(),
+    }
+}
+
+/// Trim and validate user input
+fn normalize(mut input: SavedDecklistInput) -> Result<SavedDecklistInput, SavedDecklistError> {
+    input.name = input.name.trim().to_string();
+    input.champion_slug = input.champion_slug.trim().to_lowercase();
+    input.description = input
+        .description
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty());
+
+    if input.name.is_empty() {
+        return Err(SavedDecklistError::InvalidInput("name must not be empty".to_string()));
+    }
+
+    if input.champion_slug.is_empty() {
+        return Err(SavedDecklistError::InvalidInput("champion_slug must not be empty".to_string()));
+    }
+
+    if input.main_deck.is_empty() {
+        return Err(SavedDecklistError::InvalidInput("main_deck must not be empty".to_string()));
+    }
+
+    let cards: Vec<&DecklistCard> = input
+        .main_deck
+        .iter()
+        .chain(input.sideboard.iter())
+        .chain(input.material_deck.iter())
+        .collect();
+    if cards.iter().any(|card| card.slug.trim().is_empty() || card.quantity <= 0) {
+        return Err(SavedDecklistError::InvalidInput(
+            "cards must have a slug and a positive quantity".to_string(),
+        ));
+    }
+
+    let mut tags: Vec<String> = input
+        .tags
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+
+    if tags.len() > MAX_TAGS {
+        return Err(SavedDecklistError::InvalidInput(format!("at most {} tags are allowed", MAX_TAGS)));
+    }
+
+    input.tags = tags;
+
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(name: &str, tags: &[&str]) -> SavedDecklistInput {
+        SavedDecklistInput {
+            name: name.to_string(),
+            description: Some("  ".to_string()),
+            champion_slug: " Lorraine ".to_string(),
+            main_deck: vec![DecklistCard {
+                slug: "a".to_string(),
+                name: "A".to_string(),
+                quantity: 4,
+                card_type: None,
+                element: None,
+                cost: None,
+                level: None,
+            }],
+            sideboard: Vec::new(),
+            material_deck: Vec::new(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_trims_and_dedups() {
+        let normalized = normalize(input(" Aggro ", &["Fire", "fire ", ""])).unwrap();
+        assert_eq!(normalized.name, "Aggro");
+        assert_eq!(normalized.champion_slug, "lorraine");
+        assert_eq!(normalized.description, None);
+        assert_eq!(normalized.tags, vec!["fire".to_string()]);
+    }
+
+    #[test]
+    fn test_normalize_rejects_empty_name() {
+        assert!(matches!(
+            normalize(input("  ", &[])),
+            Err(SavedDecklistError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_fork_input_keeps_material_deck() {
+        let mut source = Decklist::new(42, "p1".to_string(), "Alice".to_string(), "lorraine".to_string(), 1);
+        source.main_deck = input("Aggro", &[]).main_deck;
+        source.material_deck = vec![DecklistCard {
+            slug: "lorraine-wandering-warrior".to_string(),
+            name: "Lorraine, Wandering Warrior".to_string(),
+            quantity: 1,
+            card_type: Some("CHAMPION".to_string()),
+            element: None,
+            cost: None,
+            level: Some(1),
+        }];
+
+        let forked = fork_input(source, None);
+        assert_eq!(forked.name, "Alice - lorraine (event 42)");
+        assert_eq!(forked.material_deck.len(), 1);
+        assert_eq!(forked.material_deck[0].slug, "lorraine-wandering-warrior");
+    }
+}